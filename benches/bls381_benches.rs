@@ -4,12 +4,15 @@ extern crate hex;
 extern crate milagro_bls;
 extern crate rand;
 
-use criterion::{black_box, criterion_group, criterion_main, Benchmark, Criterion};
+use criterion::{
+    black_box, criterion_group, criterion_main, Benchmark, Criterion, ParameterizedBenchmark,
+};
+use milagro_bls::hash_to_curve::{self, XmdHash};
 use milagro_bls::*;
 
 fn compression_signature(c: &mut Criterion) {
     let compressed_g2 = hex::decode("a666d31d7e6561371644eb9ca7dbcb87257d8fd84a09e38a7a491ce0bbac64a324aa26385aebc99f47432970399a2ecb0def2d4be359640e6dae6438119cbdc4f18e5e4496c68a979473a72b72d3badf98464412e9d8f8d2ea9b31953bb24899").unwrap();
-    let mut signature = Signature::from_bytes(&compressed_g2).unwrap();
+    let signature = Signature::from_bytes(&compressed_g2).unwrap();
 
     c.bench(
         "compression",
@@ -25,7 +28,7 @@ fn compression_signature(c: &mut Criterion) {
         "compression",
         Benchmark::new("Compress a Signature", move |b| {
             b.iter(|| {
-                black_box(Signature::as_bytes(&mut signature));
+                black_box(Signature::as_bytes(&signature));
             })
         })
         .sample_size(10),
@@ -34,7 +37,7 @@ fn compression_signature(c: &mut Criterion) {
 
 fn compression_public_key(c: &mut Criterion) {
     let compressed_g1 = hex::decode("a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a").unwrap();
-    let mut public_key = PublicKey::from_bytes(&compressed_g1).unwrap();
+    let public_key = PublicKey::from_bytes(&compressed_g1).unwrap();
 
     c.bench(
         "compression",
@@ -50,7 +53,7 @@ fn compression_public_key(c: &mut Criterion) {
         "compression",
         Benchmark::new("Compress a PublicKey", move |b| {
             b.iter(|| {
-                black_box(PublicKey::as_bytes(&mut public_key));
+                black_box(PublicKey::as_bytes(&public_key));
             })
         })
         .sample_size(10),
@@ -59,7 +62,7 @@ fn compression_public_key(c: &mut Criterion) {
 
 fn compression_public_key_bigs(c: &mut Criterion) {
     let compressed_g1 = hex::decode("a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a").unwrap();
-    let mut public_key = PublicKey::from_bytes(&compressed_g1).unwrap();
+    let public_key = PublicKey::from_bytes(&compressed_g1).unwrap();
     let uncompressed_bytes = public_key.as_uncompressed_bytes();
 
     c.bench(
@@ -244,6 +247,142 @@ fn aggregate_verfication_multiple_signatures(c: &mut Criterion) {
     );
 }
 
+fn aggregation_sizes(c: &mut Criterion) {
+    let msg = b"signed message";
+    let keypairs: Vec<Keypair> = (0..512)
+        .map(|_| Keypair::random(&mut rand::thread_rng()))
+        .collect();
+    let signatures: Vec<Signature> = keypairs
+        .iter()
+        .map(|keypair| Signature::new(&msg[..], &keypair.sk))
+        .collect();
+    let public_keys: Vec<PublicKey> = keypairs.into_iter().map(|keypair| keypair.pk).collect();
+
+    c.bench(
+        "aggregation",
+        ParameterizedBenchmark::new(
+            "Aggregate Signatures",
+            move |b, &n| {
+                let signatures: Vec<&Signature> = signatures[..n].iter().collect();
+                b.iter(|| {
+                    black_box(AggregateSignature::aggregate(&signatures));
+                })
+            },
+            vec![8, 64, 512],
+        )
+        .sample_size(10),
+    );
+
+    c.bench(
+        "aggregation",
+        ParameterizedBenchmark::new(
+            "Aggregate PublicKeys",
+            move |b, &n| {
+                let public_keys: Vec<&PublicKey> = public_keys[..n].iter().collect();
+                b.iter(|| {
+                    black_box(AggregatePublicKey::aggregate(&public_keys).unwrap());
+                })
+            },
+            vec![8, 64, 512],
+        )
+        .sample_size(10),
+    );
+}
+
+fn batch_verification(c: &mut Criterion) {
+    let keypairs: Vec<Keypair> = (0..64)
+        .map(|_| Keypair::random(&mut rand::thread_rng()))
+        .collect();
+    let msgs: Vec<Vec<u8>> = (0..64).map(|i| vec![i as u8; 32]).collect();
+    let public_keys: Vec<AggregatePublicKey> = keypairs
+        .iter()
+        .map(|keypair| AggregatePublicKey::from_public_key(&keypair.pk))
+        .collect();
+    let signatures: Vec<AggregateSignature> = keypairs
+        .iter()
+        .zip(&msgs)
+        .map(|(keypair, msg)| AggregateSignature::from_signature(&Signature::new(msg, &keypair.sk)))
+        .collect();
+
+    c.bench(
+        "batch-verification",
+        ParameterizedBenchmark::new(
+            "Verify Signatures of distinct messages",
+            move |b, &n| {
+                b.iter(|| {
+                    let signature_sets = signatures[..n]
+                        .iter()
+                        .zip(&public_keys[..n])
+                        .zip(&msgs[..n])
+                        .map(|((signature, public_key), msg)| (signature, public_key, &msg[..]));
+                    assert!(AggregateSignature::verify_multiple_aggregate_signatures(
+                        &mut rand::thread_rng(),
+                        signature_sets,
+                    ));
+                })
+            },
+            vec![1, 8, 64],
+        )
+        .sample_size(10),
+    );
+}
+
+fn hashing_to_curve(c: &mut Criterion) {
+    let msg = b"Some msg";
+    let dst_g1 = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+    let dst_g2 = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+    c.bench(
+        "hash-to-curve",
+        Benchmark::new("hash_to_curve G2 SHA-256", move |b| {
+            b.iter(|| {
+                black_box(hash_to_curve::hash_to_curve_g2(
+                    msg,
+                    dst_g2,
+                    XmdHash::Sha256,
+                ));
+            })
+        })
+        .with_function("hash_to_curve G2 SHA-512", move |b| {
+            b.iter(|| {
+                black_box(hash_to_curve::hash_to_curve_g2(
+                    msg,
+                    dst_g2,
+                    XmdHash::Sha512,
+                ));
+            })
+        })
+        .with_function("encode_to_curve G2 SHA-256", move |b| {
+            b.iter(|| {
+                black_box(hash_to_curve::encode_to_curve_g2(
+                    msg,
+                    dst_g2,
+                    XmdHash::Sha256,
+                ));
+            })
+        })
+        .with_function("hash_to_curve G1 SHA-256", move |b| {
+            b.iter(|| {
+                black_box(hash_to_curve::hash_to_curve_g1(
+                    msg,
+                    dst_g1,
+                    XmdHash::Sha256,
+                ));
+            })
+        })
+        .with_function("encode_to_curve G1 SHA-256", move |b| {
+            b.iter(|| {
+                black_box(hash_to_curve::encode_to_curve_g1(
+                    msg,
+                    dst_g1,
+                    XmdHash::Sha256,
+                ));
+            })
+        })
+        .sample_size(10),
+    );
+}
+
 fn key_generation(c: &mut Criterion) {
     c.bench(
         "key generation",
@@ -273,6 +412,9 @@ criterion_group!(
     aggregate_verfication_multiple_signatures,
     aggregate_verfication,
     aggregation,
+    aggregation_sizes,
+    batch_verification,
+    hashing_to_curve,
     compression_signature,
     compression_public_key,
     compression_public_key_bigs,
@@ -0,0 +1,63 @@
+/*
+ * C bindings for milagro_bls, BLS12-381 signatures using the Apache Milagro curve library.
+ *
+ * Keys and points are passed as fixed-size big-endian byte buffers using the compressed
+ * (or uncompressed where noted) Zcash encodings. Every function returns one of the
+ * MILAGRO_BLS_* codes below and only writes its output buffer on MILAGRO_BLS_SUCCESS.
+ * A message pointer may be NULL when its length is zero.
+ */
+
+#ifndef MILAGRO_BLS_H
+#define MILAGRO_BLS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define MILAGRO_BLS_SECRET_KEY_BYTES 32
+#define MILAGRO_BLS_PUBLIC_KEY_BYTES 48
+#define MILAGRO_BLS_PUBLIC_KEY_UNCOMPRESSED_BYTES 96
+#define MILAGRO_BLS_SIGNATURE_BYTES 96
+#define MILAGRO_BLS_SIGNATURE_UNCOMPRESSED_BYTES 192
+
+#define MILAGRO_BLS_SUCCESS 0
+#define MILAGRO_BLS_NULL_POINTER 1
+#define MILAGRO_BLS_BAD_ENCODING 2
+#define MILAGRO_BLS_NOT_IN_SUBGROUP 3
+#define MILAGRO_BLS_INFINITY 4
+#define MILAGRO_BLS_INVALID_SECRET_KEY 5
+#define MILAGRO_BLS_INVALID_IKM 6
+#define MILAGRO_BLS_AGGREGATE_EMPTY 7
+#define MILAGRO_BLS_VERIFY_FAILED 8
+#define MILAGRO_BLS_PANIC 9
+
+/* Keys and signing */
+int milagro_bls_key_generate(const uint8_t *ikm, size_t ikm_len, uint8_t *sk_out);
+int milagro_bls_secret_key_to_public_key(const uint8_t *sk, uint8_t *pk_out);
+int milagro_bls_sign(const uint8_t *sk, const uint8_t *msg, size_t msg_len, uint8_t *sig_out);
+int milagro_bls_verify(const uint8_t *pk, const uint8_t *msg, size_t msg_len, const uint8_t *sig);
+
+/* Aggregation, where pks and sigs are count concatenated compressed encodings */
+int milagro_bls_aggregate_signatures(const uint8_t *sigs, size_t count, uint8_t *sig_out);
+int milagro_bls_aggregate_public_keys(const uint8_t *pks, size_t count, uint8_t *pk_out);
+int milagro_bls_fast_aggregate_verify(const uint8_t *pks, size_t count, const uint8_t *msg,
+                                      size_t msg_len, const uint8_t *sig);
+int milagro_bls_aggregate_verify(const uint8_t *pks, const uint8_t *const *msgs,
+                                 const size_t *msg_lens, size_t count, const uint8_t *sig);
+
+/* Serialization */
+int milagro_bls_public_key_validate(const uint8_t *pk);
+int milagro_bls_public_key_uncompress(const uint8_t *pk, uint8_t *pk_out);
+int milagro_bls_public_key_compress(const uint8_t *pk, uint8_t *pk_out);
+int milagro_bls_signature_validate(const uint8_t *sig);
+int milagro_bls_signature_uncompress(const uint8_t *sig, uint8_t *sig_out);
+int milagro_bls_signature_compress(const uint8_t *sig, uint8_t *sig_out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MILAGRO_BLS_H */
@@ -0,0 +1,580 @@
+//! C bindings for milagro_bls.
+//!
+//! All points and keys are passed as fixed-size byte buffers in their compressed (or
+//! uncompressed where noted) encodings, and every function returns one of the `MILAGRO_BLS_*`
+//! integer codes. Output buffers are only written when `MILAGRO_BLS_SUCCESS` is returned.
+//! Panics do not unwind into the caller, they are reported as `MILAGRO_BLS_PANIC`.
+//!
+//! The matching C declarations are in `include/milagro_bls.h`.
+
+use milagro_bls::{
+    AggregatePublicKey, AggregateSignature, AmclError, DecodeError, PublicKey, SecretKey,
+    Signature, PUBLIC_KEY_BYTES, PUBLIC_KEY_UNCOMPRESSED_BYTES, SECRET_KEY_BYTES, SIGNATURE_BYTES,
+    SIGNATURE_UNCOMPRESSED_BYTES,
+};
+use std::os::raw::c_int;
+use std::panic::{self, AssertUnwindSafe};
+use std::{ptr, slice};
+
+/// The operation succeeded, or the signature is valid.
+pub const MILAGRO_BLS_SUCCESS: c_int = 0;
+/// A required pointer argument was null.
+pub const MILAGRO_BLS_NULL_POINTER: c_int = 1;
+/// A point is not a valid encoding or is not on the curve.
+pub const MILAGRO_BLS_BAD_ENCODING: c_int = 2;
+/// A point is on the curve but not in the prime order subgroup.
+pub const MILAGRO_BLS_NOT_IN_SUBGROUP: c_int = 3;
+/// A PublicKey is the point at infinity.
+pub const MILAGRO_BLS_INFINITY: c_int = 4;
+/// A SecretKey is zero or not less than the curve order.
+pub const MILAGRO_BLS_INVALID_SECRET_KEY: c_int = 5;
+/// The input keying material is shorter than 32 bytes.
+pub const MILAGRO_BLS_INVALID_IKM: c_int = 6;
+/// Aggregation or verification was attempted over zero points.
+pub const MILAGRO_BLS_AGGREGATE_EMPTY: c_int = 7;
+/// The signature is not valid.
+pub const MILAGRO_BLS_VERIFY_FAILED: c_int = 8;
+/// The library panicked, which is caught rather than unwinding into the caller.
+pub const MILAGRO_BLS_PANIC: c_int = 9;
+
+fn decode_error_code(error: DecodeError) -> c_int {
+    match error {
+        DecodeError::NotInSubgroup => MILAGRO_BLS_NOT_IN_SUBGROUP,
+        DecodeError::Infinity => MILAGRO_BLS_INFINITY,
+        DecodeError::InvalidSecretKey => MILAGRO_BLS_INVALID_SECRET_KEY,
+        _ => MILAGRO_BLS_BAD_ENCODING,
+    }
+}
+
+fn amcl_error_code(error: AmclError) -> c_int {
+    match error {
+        AmclError::AggregateEmptyPoints => MILAGRO_BLS_AGGREGATE_EMPTY,
+        // Only reported by key generation, for too little input keying material
+        AmclError::InvalidSecretKeySize => MILAGRO_BLS_INVALID_IKM,
+        _ => MILAGRO_BLS_BAD_ENCODING,
+    }
+}
+
+// Runs the body of an exported function, so that a panic is reported as `MILAGRO_BLS_PANIC`
+// rather than unwinding across the FFI boundary. Outputs are only written on success, so no
+// broken state is observable after a panic.
+fn catch_panic<F: FnOnce() -> c_int>(f: F) -> c_int {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(MILAGRO_BLS_PANIC)
+}
+
+// Returns the input as a slice, allowing a null pointer when the length is zero.
+unsafe fn input<'a>(data: *const u8, len: usize) -> Result<&'a [u8], c_int> {
+    if len == 0 {
+        Ok(&[])
+    } else if data.is_null() {
+        Err(MILAGRO_BLS_NULL_POINTER)
+    } else {
+        Ok(slice::from_raw_parts(data, len))
+    }
+}
+
+// Returns the input as a slice, rejecting a null pointer.
+unsafe fn fixed_input<'a>(data: *const u8, len: usize) -> Result<&'a [u8], c_int> {
+    if data.is_null() {
+        return Err(MILAGRO_BLS_NULL_POINTER);
+    }
+    Ok(slice::from_raw_parts(data, len))
+}
+
+unsafe fn output(bytes: &[u8], out: *mut u8) -> c_int {
+    if out.is_null() {
+        return MILAGRO_BLS_NULL_POINTER;
+    }
+    ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+    MILAGRO_BLS_SUCCESS
+}
+
+unsafe fn secret_key(sk: *const u8) -> Result<SecretKey, c_int> {
+    SecretKey::from_bytes(fixed_input(sk, SECRET_KEY_BYTES)?).map_err(decode_error_code)
+}
+
+unsafe fn public_key(pk: *const u8) -> Result<PublicKey, c_int> {
+    PublicKey::from_bytes(fixed_input(pk, PUBLIC_KEY_BYTES)?).map_err(decode_error_code)
+}
+
+unsafe fn signature(sig: *const u8) -> Result<Signature, c_int> {
+    Signature::from_bytes(fixed_input(sig, SIGNATURE_BYTES)?).map_err(decode_error_code)
+}
+
+// Decodes `count` concatenated compressed PublicKeys.
+unsafe fn public_keys(pks: *const u8, count: usize) -> Result<Vec<PublicKey>, c_int> {
+    if count == 0 {
+        return Err(MILAGRO_BLS_AGGREGATE_EMPTY);
+    }
+    let len = count.checked_mul(PUBLIC_KEY_BYTES).ok_or(MILAGRO_BLS_BAD_ENCODING)?;
+    fixed_input(pks, len)?
+        .chunks(PUBLIC_KEY_BYTES)
+        .map(|bytes| PublicKey::from_bytes(bytes).map_err(decode_error_code))
+        .collect()
+}
+
+macro_rules! try_code {
+    ($result: expr) => {
+        match $result {
+            Ok(value) => value,
+            Err(code) => return code,
+        }
+    };
+}
+
+/// Generate a 32 byte SecretKey from at least 32 bytes of input keying material.
+///
+/// # Safety
+///
+/// `ikm` must point to `ikm_len` readable bytes and `sk_out` to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_key_generate(
+    ikm: *const u8,
+    ikm_len: usize,
+    sk_out: *mut u8,
+) -> c_int {
+    catch_panic(|| {
+        let ikm = try_code!(input(ikm, ikm_len));
+        let sk = try_code!(SecretKey::key_generate(ikm, &[]).map_err(amcl_error_code));
+        output(&sk.as_bytes(), sk_out)
+    })
+}
+
+/// Derive the 48 byte compressed PublicKey of a 32 byte SecretKey.
+///
+/// # Safety
+///
+/// `sk` must point to 32 readable bytes and `pk_out` to 48 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_secret_key_to_public_key(
+    sk: *const u8,
+    pk_out: *mut u8,
+) -> c_int {
+    catch_panic(|| {
+        let sk = try_code!(secret_key(sk));
+        output(&PublicKey::from_secret_key(&sk).as_bytes(), pk_out)
+    })
+}
+
+/// Sign a message, writing the 96 byte compressed Signature.
+///
+/// # Safety
+///
+/// `sk` must point to 32 readable bytes, `msg` to `msg_len` readable bytes and `sig_out` to 96
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_sign(
+    sk: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    catch_panic(|| {
+        let sk = try_code!(secret_key(sk));
+        let msg = try_code!(input(msg, msg_len));
+        output(&Signature::new(msg, &sk).as_bytes(), sig_out)
+    })
+}
+
+/// Verify a Signature over a message, returning `MILAGRO_BLS_VERIFY_FAILED` if it is invalid.
+///
+/// # Safety
+///
+/// `pk` must point to 48 readable bytes, `msg` to `msg_len` readable bytes and `sig` to 96
+/// readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_verify(
+    pk: *const u8,
+    msg: *const u8,
+    msg_len: usize,
+    sig: *const u8,
+) -> c_int {
+    catch_panic(|| {
+        let pk = try_code!(public_key(pk));
+        let msg = try_code!(input(msg, msg_len));
+        let sig = try_code!(signature(sig));
+        if sig.verify(msg, &pk) {
+            MILAGRO_BLS_SUCCESS
+        } else {
+            MILAGRO_BLS_VERIFY_FAILED
+        }
+    })
+}
+
+/// Aggregate `count` concatenated 96 byte Signatures into a single 96 byte Signature.
+///
+/// # Safety
+///
+/// `sigs` must point to `count * 96` readable bytes and `sig_out` to 96 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_aggregate_signatures(
+    sigs: *const u8,
+    count: usize,
+    sig_out: *mut u8,
+) -> c_int {
+    catch_panic(|| {
+        if count == 0 {
+            return MILAGRO_BLS_AGGREGATE_EMPTY;
+        }
+        let len = try_code!(count.checked_mul(SIGNATURE_BYTES).ok_or(MILAGRO_BLS_BAD_ENCODING));
+        let sigs = try_code!(fixed_input(sigs, len));
+        let mut aggregate_signature = AggregateSignature::new();
+        for bytes in sigs.chunks(SIGNATURE_BYTES) {
+            let signature = try_code!(Signature::from_bytes(bytes).map_err(decode_error_code));
+            aggregate_signature.add(&signature);
+        }
+        output(&aggregate_signature.as_bytes(), sig_out)
+    })
+}
+
+/// Aggregate `count` concatenated 48 byte PublicKeys into a single 48 byte PublicKey.
+///
+/// # Safety
+///
+/// `pks` must point to `count * 48` readable bytes and `pk_out` to 48 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_aggregate_public_keys(
+    pks: *const u8,
+    count: usize,
+    pk_out: *mut u8,
+) -> c_int {
+    catch_panic(|| {
+        let pks = try_code!(public_keys(pks, count));
+        let aggregate_public_key =
+            try_code!(AggregatePublicKey::into_aggregate(&pks).map_err(amcl_error_code));
+        output(&aggregate_public_key.as_bytes(), pk_out)
+    })
+}
+
+/// FastAggregateVerify a Signature over a single message against `count` concatenated 48 byte
+/// PublicKeys, returning `MILAGRO_BLS_VERIFY_FAILED` if it is invalid.
+///
+/// # Safety
+///
+/// `pks` must point to `count * 48` readable bytes, `msg` to `msg_len` readable bytes and `sig`
+/// to 96 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_fast_aggregate_verify(
+    pks: *const u8,
+    count: usize,
+    msg: *const u8,
+    msg_len: usize,
+    sig: *const u8,
+) -> c_int {
+    catch_panic(|| {
+        let pks = try_code!(public_keys(pks, count));
+        let msg = try_code!(input(msg, msg_len));
+        let sig = try_code!(signature(sig));
+        let pks: Vec<&PublicKey> = pks.iter().collect();
+        if AggregateSignature::from_signature(&sig).fast_aggregate_verify(msg, &pks) {
+            MILAGRO_BLS_SUCCESS
+        } else {
+            MILAGRO_BLS_VERIFY_FAILED
+        }
+    })
+}
+
+/// AggregateVerify a Signature against `count` concatenated 48 byte PublicKeys, each signing
+/// the message of the same index, returning `MILAGRO_BLS_VERIFY_FAILED` if it is invalid.
+///
+/// # Safety
+///
+/// `pks` must point to `count * 48` readable bytes, `msgs` and `msg_lens` to `count` entries
+/// with each `msgs[i]` pointing to `msg_lens[i]` readable bytes, and `sig` to 96 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_aggregate_verify(
+    pks: *const u8,
+    msgs: *const *const u8,
+    msg_lens: *const usize,
+    count: usize,
+    sig: *const u8,
+) -> c_int {
+    catch_panic(|| {
+        let pks = try_code!(public_keys(pks, count));
+        if msgs.is_null() || msg_lens.is_null() {
+            return MILAGRO_BLS_NULL_POINTER;
+        }
+        let msgs = slice::from_raw_parts(msgs, count);
+        let msg_lens = slice::from_raw_parts(msg_lens, count);
+        let msgs: Vec<&[u8]> =
+            try_code!(msgs.iter().zip(msg_lens).map(|(msg, len)| input(*msg, *len)).collect());
+        let sig = try_code!(signature(sig));
+        let pks: Vec<&PublicKey> = pks.iter().collect();
+        if AggregateSignature::from_signature(&sig).aggregate_verify(&msgs, &pks) {
+            MILAGRO_BLS_SUCCESS
+        } else {
+            MILAGRO_BLS_VERIFY_FAILED
+        }
+    })
+}
+
+/// Check a 48 byte compressed PublicKey is valid, as done when deserializing.
+///
+/// # Safety
+///
+/// `pk` must point to 48 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_public_key_validate(pk: *const u8) -> c_int {
+    catch_panic(|| {
+        try_code!(public_key(pk));
+        MILAGRO_BLS_SUCCESS
+    })
+}
+
+/// Convert a 48 byte compressed PublicKey to its 96 byte uncompressed encoding.
+///
+/// # Safety
+///
+/// `pk` must point to 48 readable bytes and `pk_out` to 96 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_public_key_uncompress(
+    pk: *const u8,
+    pk_out: *mut u8,
+) -> c_int {
+    catch_panic(|| {
+        let pk = try_code!(public_key(pk));
+        output(&pk.to_uncompressed_bytes(), pk_out)
+    })
+}
+
+/// Convert a 96 byte uncompressed PublicKey to its 48 byte compressed encoding.
+///
+/// # Safety
+///
+/// `pk` must point to 96 readable bytes and `pk_out` to 48 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_public_key_compress(pk: *const u8, pk_out: *mut u8) -> c_int {
+    catch_panic(|| {
+        let bytes = try_code!(fixed_input(pk, PUBLIC_KEY_UNCOMPRESSED_BYTES));
+        let pk = try_code!(PublicKey::from_uncompressed_bytes(bytes).map_err(decode_error_code));
+        output(&pk.as_bytes(), pk_out)
+    })
+}
+
+/// Check a 96 byte compressed Signature is valid, including the subgroup check.
+///
+/// # Safety
+///
+/// `sig` must point to 96 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_signature_validate(sig: *const u8) -> c_int {
+    catch_panic(|| {
+        let sig = try_code!(signature(sig));
+        if !sig.is_in_subgroup() {
+            return MILAGRO_BLS_NOT_IN_SUBGROUP;
+        }
+        MILAGRO_BLS_SUCCESS
+    })
+}
+
+/// Convert a 96 byte compressed Signature to its 192 byte uncompressed encoding.
+///
+/// # Safety
+///
+/// `sig` must point to 96 readable bytes and `sig_out` to 192 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_signature_uncompress(
+    sig: *const u8,
+    sig_out: *mut u8,
+) -> c_int {
+    catch_panic(|| {
+        let sig = try_code!(signature(sig));
+        output(&sig.to_uncompressed_bytes(), sig_out)
+    })
+}
+
+/// Convert a 192 byte uncompressed Signature to its 96 byte compressed encoding.
+///
+/// # Safety
+///
+/// `sig` must point to 192 readable bytes and `sig_out` to 96 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn milagro_bls_signature_compress(sig: *const u8, sig_out: *mut u8) -> c_int {
+    catch_panic(|| {
+        let bytes = try_code!(fixed_input(sig, SIGNATURE_UNCOMPRESSED_BYTES));
+        let sig = try_code!(Signature::from_uncompressed_bytes(bytes).map_err(decode_error_code));
+        output(&sig.as_bytes(), sig_out)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keypair(seed: u8) -> ([u8; 32], [u8; 48]) {
+        let ikm = [seed; 32];
+        let mut sk = [0u8; 32];
+        let mut pk = [0u8; 48];
+        unsafe {
+            assert_eq!(milagro_bls_key_generate(ikm.as_ptr(), ikm.len(), sk.as_mut_ptr()), 0);
+            assert_eq!(milagro_bls_secret_key_to_public_key(sk.as_ptr(), pk.as_mut_ptr()), 0);
+        }
+        (sk, pk)
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let (sk, pk) = keypair(1);
+        let msg = b"cats";
+        let mut sig = [0u8; 96];
+        unsafe {
+            assert_eq!(milagro_bls_sign(sk.as_ptr(), msg.as_ptr(), msg.len(), sig.as_mut_ptr()), 0);
+            assert_eq!(milagro_bls_verify(pk.as_ptr(), msg.as_ptr(), msg.len(), sig.as_ptr()), 0);
+            assert_eq!(
+                milagro_bls_verify(pk.as_ptr(), msg.as_ptr(), 3, sig.as_ptr()),
+                MILAGRO_BLS_VERIFY_FAILED
+            );
+
+            // Empty messages may be passed as null
+            assert_eq!(milagro_bls_sign(sk.as_ptr(), ptr::null(), 0, sig.as_mut_ptr()), 0);
+            assert_eq!(milagro_bls_verify(pk.as_ptr(), ptr::null(), 0, sig.as_ptr()), 0);
+        }
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let msg = b"cats";
+        let mut pks = Vec::new();
+        let mut sigs = Vec::new();
+        let mut msgs = Vec::new();
+        for i in 0..3 {
+            let (sk, pk) = keypair(i);
+            let mut sig = [0u8; 96];
+            unsafe {
+                milagro_bls_sign(sk.as_ptr(), msg.as_ptr(), msg.len(), sig.as_mut_ptr());
+            }
+            pks.extend_from_slice(&pk);
+            sigs.extend_from_slice(&sig);
+            msgs.push(msg.as_ptr());
+        }
+
+        let mut agg_sig = [0u8; 96];
+        let mut agg_pk = [0u8; 48];
+        let msg_lens = [msg.len(); 3];
+        unsafe {
+            assert_eq!(milagro_bls_aggregate_signatures(sigs.as_ptr(), 3, agg_sig.as_mut_ptr()), 0);
+            assert_eq!(milagro_bls_aggregate_public_keys(pks.as_ptr(), 3, agg_pk.as_mut_ptr()), 0);
+            assert_eq!(
+                milagro_bls_verify(agg_pk.as_ptr(), msg.as_ptr(), msg.len(), agg_sig.as_ptr()),
+                0
+            );
+            assert_eq!(
+                milagro_bls_fast_aggregate_verify(
+                    pks.as_ptr(),
+                    3,
+                    msg.as_ptr(),
+                    msg.len(),
+                    agg_sig.as_ptr()
+                ),
+                0
+            );
+            assert_eq!(
+                milagro_bls_aggregate_verify(
+                    pks.as_ptr(),
+                    msgs.as_ptr(),
+                    msg_lens.as_ptr(),
+                    3,
+                    agg_sig.as_ptr()
+                ),
+                0
+            );
+            assert_eq!(
+                milagro_bls_fast_aggregate_verify(
+                    pks.as_ptr(),
+                    2,
+                    msg.as_ptr(),
+                    msg.len(),
+                    agg_sig.as_ptr()
+                ),
+                MILAGRO_BLS_VERIFY_FAILED
+            );
+
+            // Empty aggregates
+            assert_eq!(
+                milagro_bls_aggregate_signatures(ptr::null(), 0, agg_sig.as_mut_ptr()),
+                MILAGRO_BLS_AGGREGATE_EMPTY
+            );
+            assert_eq!(
+                milagro_bls_aggregate_public_keys(ptr::null(), 0, agg_pk.as_mut_ptr()),
+                MILAGRO_BLS_AGGREGATE_EMPTY
+            );
+
+            // Counts whose byte length overflows are rejected before reading
+            assert_eq!(
+                milagro_bls_aggregate_signatures(sigs.as_ptr(), usize::MAX, agg_sig.as_mut_ptr()),
+                MILAGRO_BLS_BAD_ENCODING
+            );
+            assert_eq!(
+                milagro_bls_aggregate_public_keys(pks.as_ptr(), usize::MAX, agg_pk.as_mut_ptr()),
+                MILAGRO_BLS_BAD_ENCODING
+            );
+        }
+    }
+
+    #[test]
+    fn test_serialization() {
+        let (sk, pk) = keypair(1);
+        let mut sig = [0u8; 96];
+        let mut uncompressed = [0u8; 192];
+        let mut compressed = [0u8; 96];
+        unsafe {
+            milagro_bls_sign(sk.as_ptr(), ptr::null(), 0, sig.as_mut_ptr());
+
+            assert_eq!(milagro_bls_public_key_validate(pk.as_ptr()), 0);
+            assert_eq!(
+                milagro_bls_public_key_uncompress(pk.as_ptr(), uncompressed.as_mut_ptr()),
+                0
+            );
+            assert_eq!(
+                milagro_bls_public_key_compress(uncompressed.as_ptr(), compressed.as_mut_ptr()),
+                0
+            );
+            assert_eq!(&compressed[..48], &pk[..]);
+
+            assert_eq!(milagro_bls_signature_validate(sig.as_ptr()), 0);
+            assert_eq!(
+                milagro_bls_signature_uncompress(sig.as_ptr(), uncompressed.as_mut_ptr()),
+                0
+            );
+            assert_eq!(
+                milagro_bls_signature_compress(uncompressed.as_ptr(), compressed.as_mut_ptr()),
+                0
+            );
+            assert_eq!(compressed, sig);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut out = [0u8; 96];
+        unsafe {
+            assert_eq!(
+                milagro_bls_key_generate([1u8; 31].as_ptr(), 31, out.as_mut_ptr()),
+                MILAGRO_BLS_INVALID_IKM
+            );
+            assert_eq!(
+                milagro_bls_secret_key_to_public_key([0u8; 32].as_ptr(), out.as_mut_ptr()),
+                MILAGRO_BLS_INVALID_SECRET_KEY
+            );
+            assert_eq!(milagro_bls_public_key_validate(ptr::null()), MILAGRO_BLS_NULL_POINTER);
+            assert_eq!(
+                milagro_bls_public_key_validate([0u8; 48].as_ptr()),
+                MILAGRO_BLS_BAD_ENCODING
+            );
+
+            let mut infinity = [0u8; 48];
+            infinity[0] = 0xc0;
+            assert_eq!(milagro_bls_public_key_validate(infinity.as_ptr()), MILAGRO_BLS_INFINITY);
+
+            let (sk, _) = keypair(1);
+            assert_eq!(
+                milagro_bls_sign(sk.as_ptr(), ptr::null(), 0, ptr::null_mut()),
+                MILAGRO_BLS_NULL_POINTER
+            );
+            assert_eq!(
+                milagro_bls_sign(sk.as_ptr(), ptr::null(), 1, out.as_mut_ptr()),
+                MILAGRO_BLS_NULL_POINTER
+            );
+        }
+    }
+}
@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use milagro_bls::AggregatePublicKey;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(point) = AggregatePublicKey::from_bytes(data) {
+        let data_round_trip = point.as_bytes();
+        assert_eq!(data.to_vec(), data_round_trip);
+    }
+});
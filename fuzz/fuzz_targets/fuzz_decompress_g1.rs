@@ -0,0 +1,19 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use milagro_bls::{PublicKey, PUBLIC_KEY_UNCOMPRESSED_BYTES};
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() == PUBLIC_KEY_UNCOMPRESSED_BYTES {
+        if let Ok(point) = PublicKey::from_uncompressed_bytes(data) {
+            assert_eq!(data.to_vec(), point.to_uncompressed_bytes().to_vec());
+        }
+    } else if let Ok(point) = PublicKey::from_bytes_unchecked(data) {
+        assert_eq!(data.to_vec(), point.as_bytes());
+
+        // Validated keys are a subset of the unchecked keys
+        let checked = PublicKey::from_bytes(data);
+        assert_eq!(checked.is_ok(), point.key_validate());
+    } else {
+        assert!(PublicKey::from_bytes(data).is_err());
+    }
+});
@@ -0,0 +1,17 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use milagro_bls::{Signature, SIGNATURE_UNCOMPRESSED_BYTES};
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() == SIGNATURE_UNCOMPRESSED_BYTES {
+        if let Ok(point) = Signature::from_uncompressed_bytes(data) {
+            assert_eq!(data.to_vec(), point.to_uncompressed_bytes().to_vec());
+        }
+    } else if let Ok(point) = Signature::from_bytes(data) {
+        assert_eq!(data.to_vec(), point.as_bytes());
+
+        // Both encodings decode to the same point
+        let uncompressed = Signature::from_uncompressed_bytes(&point.to_uncompressed_bytes());
+        assert_eq!(data.to_vec(), uncompressed.unwrap().as_bytes());
+    }
+});
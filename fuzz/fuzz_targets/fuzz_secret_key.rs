@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use milagro_bls::SecretKey;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(secret_key) = SecretKey::from_bytes(data) {
+        assert_eq!(data.to_vec(), secret_key.as_bytes());
+
+        // Scalars less than r are unchanged by reduction
+        let reduced = SecretKey::from_bytes_reduced(data).unwrap();
+        assert_eq!(reduced.as_bytes(), secret_key.as_bytes());
+    }
+});
@@ -1,13 +1,29 @@
 extern crate amcl;
-extern crate rand;
+extern crate rand_core;
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use core::ops::AddAssign;
+#[cfg(feature = "std")]
+use std::ops::AddAssign;
 
 use super::amcl_utils::{
-    self, ate2_evaluation, compress_g2, decompress_g2, g1mul, g2mul, hash_to_curve_g2, pair,
-    subgroup_check_g2, AmclError, Big, GroupG1, GroupG2, G2_BYTES,
+    ate2_evaluation, compress_g1, compress_g2, decompress_g1, decompress_g2, g1_to_eip2537, g1mul,
+    g2_to_eip2537, g2mul, hash_to_curve_g2, hash_to_curve_g2_with_dst, on_curve_check_g1,
+    on_curve_check_g2, pair, serialize_uncompressed_g1, serialize_uncompressed_g2,
+    subgroup_check_g1, subgroup_check_g2, AmclError, GroupG1, GroupG2, DST_G2, EIP2537_G1_BYTES,
+    EIP2537_G2_BYTES, G1_BYTES, G2_BYTES, NEGATIVE_GENERATORG1,
 };
-use super::keys::PublicKey;
+use super::errors::{DecodeError, VerifyError};
+use super::keys::{PublicKey, ALLOW_INFINITY_PUBLIC_KEY};
+use super::points::{G2Point, Scalar};
+use super::schemes;
 use super::signature::Signature;
-use rand::Rng;
+use super::strictness::{self, Strictness};
+#[cfg(feature = "parallel")]
+use super::thread_pool;
+use rand_core::{CryptoRng, RngCore};
 
 /// Allows for the adding/combining of multiple BLS PublicKeys.
 ///
@@ -17,7 +33,10 @@ use rand::Rng;
 #[derive(Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct AggregatePublicKey {
+    #[cfg(feature = "unstable-raw")]
     pub point: GroupG1,
+    #[cfg(not(feature = "unstable-raw"))]
+    pub(crate) point: GroupG1,
 }
 
 impl AggregatePublicKey {
@@ -26,16 +45,40 @@ impl AggregatePublicKey {
     /// This is a helper method combining the `new()` and `add()` functions.
     ///
     /// Pre-requsites: All public keys must be PoP verified before calling this function.
+    ///
+    /// Errors if there are no keys or, unless the `permissive-infinity` feature is enabled,
+    /// if any key is the point at infinity. With the `parallel` feature the keys are summed
+    /// across threads.
     pub fn aggregate(keys: &[&PublicKey]) -> Result<Self, AmclError> {
         if keys.is_empty() {
             return Err(AmclError::AggregateEmptyPoints);
         }
+        if !ALLOW_INFINITY_PUBLIC_KEY && keys.iter().any(|key| key.point.is_infinity()) {
+            return Err(AmclError::InvalidPoint);
+        }
 
-        let mut agg_key = Self { point: GroupG1::new() };
-        for key in keys {
-            agg_key.point.add(&key.point)
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            let point = thread_pool::install(|| {
+                keys.par_iter()
+                    .map(|key| key.point.clone())
+                    .reduce_with(|mut point, other| {
+                        point.add(&other);
+                        point
+                    })
+                    .unwrap_or_else(GroupG1::new)
+            });
+            Ok(Self { point })
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            let mut agg_key = Self { point: GroupG1::new() };
+            for key in keys {
+                agg_key.point.add(&key.point)
+            }
+            Ok(agg_key)
         }
-        Ok(agg_key)
     }
 
     /// Instantiate a new aggregate public key from a vector of PublicKeys.
@@ -43,10 +86,15 @@ impl AggregatePublicKey {
     /// This is a helper method combining the `new()` and `add()` functions.
     ///
     /// Pre-requsites: All public keys must be PoP verified before calling this function.
+    ///
+    /// Errors under the same conditions as `aggregate()`.
     pub fn into_aggregate(keys: &[PublicKey]) -> Result<Self, AmclError> {
         if keys.is_empty() {
             return Err(AmclError::AggregateEmptyPoints);
         }
+        if !ALLOW_INFINITY_PUBLIC_KEY && keys.iter().any(|key| key.point.is_infinity()) {
+            return Err(AmclError::InvalidPoint);
+        }
 
         let mut point = GroupG1::new();
         for key in keys {
@@ -55,6 +103,89 @@ impl AggregatePublicKey {
         Ok(Self { point })
     }
 
+    /// Instantiate a new aggregate public key from the PublicKeys selected by a bitfield.
+    ///
+    /// Bit `i` of the bitfield (little-endian bit order within each byte) selects `keys[i]`.
+    /// Bits beyond the end of the bitfield are treated as unset.
+    ///
+    /// Pre-requsites: All public keys must be PoP verified before calling this function.
+    ///
+    /// Errors if no key is selected, if a set bit has no corresponding key or, as in
+    /// `aggregate()`, if a selected key is the point at infinity.
+    pub fn from_pubkeys_filtered(keys: &[PublicKey], bitfield: &[u8]) -> Result<Self, AmclError> {
+        let mut point = GroupG1::new();
+        let mut participants = 0;
+        for (i, byte) in bitfield.iter().enumerate() {
+            for bit in 0..8 {
+                if byte >> bit & 1 == 1 {
+                    let key = keys.get(i * 8 + bit).ok_or(AmclError::InvalidPoint)?;
+                    if !ALLOW_INFINITY_PUBLIC_KEY && key.point.is_infinity() {
+                        return Err(AmclError::InvalidPoint);
+                    }
+                    point.add(&key.point);
+                    participants += 1;
+                }
+            }
+        }
+
+        if participants == 0 {
+            return Err(AmclError::AggregateEmptyPoints);
+        }
+        Ok(Self { point })
+    }
+
+    /// Instantiate a new aggregate public key from a vector of PublicKeys, adding each distinct
+    /// PublicKey only once.
+    ///
+    /// Returns the aggregate along with the repeated PublicKeys which were skipped, see
+    /// `AggregatePublicKey::duplicates()`.
+    ///
+    /// Pre-requsites: All public keys must be PoP verified before calling this function.
+    #[cfg(feature = "alloc")]
+    pub fn aggregate_deduplicated(
+        keys: &[&PublicKey],
+    ) -> Result<(Self, Vec<(usize, usize)>), AmclError> {
+        if keys.is_empty() {
+            return Err(AmclError::AggregateEmptyPoints);
+        }
+
+        let duplicates = Self::duplicates(keys);
+        let mut duplicate_indices: Vec<usize> = duplicates.iter().map(|(_, i)| *i).collect();
+        duplicate_indices.sort_unstable();
+
+        let mut point = GroupG1::new();
+        for (i, key) in keys.iter().enumerate() {
+            if duplicate_indices.binary_search(&i).is_err() {
+                point.add(&key.point);
+            }
+        }
+        Ok((Self { point }, duplicates))
+    }
+
+    /// Find the PublicKeys which appear more than once.
+    ///
+    /// Returns `(first, repeat)` index pairs, where `keys[repeat]` is the same PublicKey as the
+    /// earlier `keys[first]`, ordered by `repeat`.
+    #[cfg(feature = "alloc")]
+    pub fn duplicates(keys: &[&PublicKey]) -> Vec<(usize, usize)> {
+        // Sort by compressed bytes so equal PublicKeys are adjacent
+        let mut sorted: Vec<([u8; G1_BYTES], usize)> =
+            keys.iter().enumerate().map(|(i, key)| (key.as_bytes(), i)).collect();
+        sorted.sort_unstable();
+
+        let mut duplicates = Vec::new();
+        let mut first = 0;
+        for i in 1..sorted.len() {
+            if sorted[i].0 == sorted[first].0 {
+                duplicates.push((sorted[first].1, sorted[i].1));
+            } else {
+                first = i;
+            }
+        }
+        duplicates.sort_unstable_by_key(|(_, repeat)| *repeat);
+        duplicates
+    }
+
     /// Instantiate a new aggregate public key from a single PublicKey.
     ///
     /// Pre-requsites: Public key must be PoP verified before calling this function.
@@ -62,6 +193,13 @@ impl AggregatePublicKey {
         AggregatePublicKey { point: key.point.clone() }
     }
 
+    /// Convert the AggregatePublicKey to a PublicKey, e.g. for APIs taking a single key.
+    ///
+    /// The aggregate may be the point at infinity, which `PublicKey::key_validate()` rejects.
+    pub fn to_public_key(&self) -> PublicKey {
+        PublicKey { point: self.point.clone() }
+    }
+
     /// Add a PublicKey to the AggregatePublicKey.
     ///
     /// Pre-requsites: Public keys must be PoP verified before calling this function.
@@ -75,6 +213,47 @@ impl AggregatePublicKey {
         // which add to a multiple of the group order.
         self.point.add(&aggregate_public_key.point);
     }
+
+    /// Remove a PublicKey from the AggregatePublicKey.
+    ///
+    /// The PublicKey must have previously been added, otherwise the result will not correspond
+    /// to any set of PublicKeys.
+    pub fn remove(&mut self, public_key: &PublicKey) {
+        self.point.sub(&public_key.point);
+    }
+
+    /// Instantiate an AggregatePublicKey from compressed bytes.
+    ///
+    /// The point must be in G1, the point at infinity is accepted as PublicKeys may sum to it.
+    pub fn from_bytes(bytes: &[u8]) -> Result<AggregatePublicKey, DecodeError> {
+        let point = decompress_g1(bytes)?;
+        Ok(Self { point })
+    }
+
+    /// Export (serialize) the AggregatePublicKey to compressed bytes.
+    pub fn as_bytes(&self) -> [u8; G1_BYTES] {
+        compress_g1(&self.point)
+    }
+
+    /// Export (serialize) the AggregatePublicKey to uncompressed (x, y) bytes.
+    pub fn to_uncompressed_bytes(&self) -> [u8; G1_BYTES * 2] {
+        serialize_uncompressed_g1(&self.point)
+    }
+
+    /// Export the AggregatePublicKey to the 128 byte encoding used by the EIP-2537 precompiles.
+    pub fn to_eip2537_bytes(&self) -> [u8; EIP2537_G1_BYTES] {
+        g1_to_eip2537(&self.point)
+    }
+
+    /// Returns true if the AggregatePublicKey is on the curve E1.
+    pub fn is_on_curve(&self) -> bool {
+        on_curve_check_g1(&self.point)
+    }
+
+    /// Returns true if the AggregatePublicKey is in the G1 subgroup.
+    pub fn is_in_subgroup(&self) -> bool {
+        subgroup_check_g1(&self.point)
+    }
 }
 
 /// Allows for the adding/combining of multiple BLS Signatures.
@@ -83,7 +262,10 @@ impl AggregatePublicKey {
 #[derive(Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct AggregateSignature {
+    #[cfg(feature = "unstable-raw")]
     pub point: GroupG2,
+    #[cfg(not(feature = "unstable-raw"))]
+    pub(crate) point: GroupG2,
 }
 
 impl AggregateSignature {
@@ -96,13 +278,33 @@ impl AggregateSignature {
 
     /// Instantiate a new AggregateSignature from a vector of Signatures.
     ///
+    /// With the `parallel` feature the Signatures are summed across threads.
+    ///
     /// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-02#section-2.8
     pub fn aggregate(signatures: &[&Signature]) -> Self {
-        let mut aggregate_signature = AggregateSignature::new();
-        for sig in signatures {
-            aggregate_signature.point.add(&sig.point);
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            let point = thread_pool::install(|| {
+                signatures
+                    .par_iter()
+                    .map(|sig| sig.point.clone())
+                    .reduce_with(|mut point, other| {
+                        point.add(&other);
+                        point
+                    })
+                    .unwrap_or_else(GroupG2::new)
+            });
+            Self { point }
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            let mut aggregate_signature = AggregateSignature::new();
+            for sig in signatures {
+                aggregate_signature.point.add(&sig.point);
+            }
+            aggregate_signature
         }
-        aggregate_signature
     }
 
     /// Instantiate a new AggregateSignature from a single Signature.
@@ -110,6 +312,24 @@ impl AggregateSignature {
         AggregateSignature { point: signature.point.clone() }
     }
 
+    /// Convert the AggregateSignature to a Signature, e.g. for APIs taking a single signature.
+    pub fn to_signature(&self) -> Signature {
+        Signature { point: self.point.clone() }
+    }
+
+    /// Advanced: instantiate an AggregateSignature directly from a G2 point, without a byte
+    /// round-trip.
+    ///
+    /// The point is not checked, verification still rejects points outside of G2.
+    pub fn from_point(point: &G2Point) -> Self {
+        AggregateSignature { point: point.0.clone() }
+    }
+
+    /// Advanced: the G2 point underlying the AggregateSignature.
+    pub fn as_point(&self) -> G2Point {
+        G2Point(self.point.clone())
+    }
+
     /// Add a Signature to the AggregateSignature.
     pub fn add(&mut self, signature: &Signature) {
         self.point.add(&signature.point);
@@ -123,27 +343,53 @@ impl AggregateSignature {
         self.point.add(&aggregate_signature.point);
     }
 
+    /// Remove a Signature from the AggregateSignature.
+    ///
+    /// The Signature must have previously been added, e.g. to strip an invalid or duplicated
+    /// Signature before re-publishing the AggregateSignature.
+    pub fn remove(&mut self, signature: &Signature) {
+        self.point.sub(&signature.point);
+    }
+
     /// AggregateVerify
     ///
     /// Verifies an AggregateSignature against a list of Messages and PublicKeys
     /// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-02#section-3.3
     pub fn aggregate_verify(&self, msgs: &[&[u8]], public_keys: &[&PublicKey]) -> bool {
-        // Require same number of messages as PublicKeys and >=1 PublicKeys.
-        if msgs.len() != public_keys.len() || public_keys.is_empty() {
-            return false;
-        }
+        self.try_aggregate_verify(msgs, public_keys).is_ok()
+    }
+
+    /// AggregateVerify - with the reason for failure
+    ///
+    /// Verifies an AggregateSignature against a list of Messages and PublicKeys, returning why
+    /// verification failed.
+    pub fn try_aggregate_verify(
+        &self,
+        msgs: &[&[u8]],
+        public_keys: &[&PublicKey],
+    ) -> Result<(), VerifyError> {
+        schemes::core_aggregate_verify(public_keys, msgs, self, DST_G2)
+    }
 
+    // CoreAggregateVerify against PublicKeys and their already hashed messages, the caller
+    // checks there is at least one PublicKey.
+    pub(crate) fn verify_hash_points<'a, I>(&self, pairs: I) -> Result<(), VerifyError>
+    where
+        I: IntoIterator<Item = (&'a PublicKey, GroupG2)>,
+    {
         // Subgroup check for signature
         if !subgroup_check_g2(&self.point) {
-            return false;
+            return Err(VerifyError::SignatureNotInSubgroup);
         }
 
         // Stores current value of pairings
         let mut pairing = pair::initmp();
 
-        for (i, pk) in public_keys.iter().enumerate() {
-            // Hash message to curve
-            let mut msg_hash = hash_to_curve_g2(msgs[i]);
+        for (pk, mut msg_hash) in pairs {
+            // PublicKey must not be infinity
+            if !ALLOW_INFINITY_PUBLIC_KEY && pk.point.is_infinity() {
+                return Err(VerifyError::InvalidPublicKey);
+            }
 
             // Points must be affine for pairing
             let mut pk_affine = pk.point.clone();
@@ -156,17 +402,18 @@ impl AggregateSignature {
 
         // Affine for signature
         let mut sig_point = self.point.clone();
-        let mut generator_g1_negative = amcl_utils::GroupG1::generator();
         sig_point.affine();
-        generator_g1_negative.neg(); // already affine
 
-        // pairing *= e(signature, G1)
-        pair::another(&mut pairing, &sig_point, &generator_g1_negative);
+        // pairing *= e(signature, -G1)
+        pair::another(&mut pairing, &sig_point, &NEGATIVE_GENERATORG1);
 
         // Complete pairing and verify output is 1.
         let mut v = pair::miller(&pairing);
         v = pair::fexp(&v);
-        v.is_unity()
+        if !v.is_unity() {
+            return Err(VerifyError::InvalidSignature);
+        }
+        Ok(())
     }
 
     /// FastAggregateVerify
@@ -175,43 +422,63 @@ impl AggregateSignature {
     /// PublicKeys must all be verified via Proof of Possession before running this function.
     /// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-02#section-3.3.4
     pub fn fast_aggregate_verify(&self, msg: &[u8], public_keys: &[&PublicKey]) -> bool {
+        self.try_fast_aggregate_verify(msg, public_keys).is_ok()
+    }
+
+    /// FastAggregateVerify - with the reason for failure
+    ///
+    /// Verifies an AggregateSignature against a list of PublicKeys, returning why verification
+    /// failed.
+    /// PublicKeys must all be verified via Proof of Possession before running this function.
+    pub fn try_fast_aggregate_verify(
+        &self,
+        msg: &[u8],
+        public_keys: &[&PublicKey],
+    ) -> Result<(), VerifyError> {
         // Require at least one PublicKey
         if public_keys.is_empty() {
-            return false;
+            return Err(VerifyError::EmptyInput);
         }
 
-        // Subgroup check for signature
-        if !subgroup_check_g2(&self.point) {
-            return false;
-        }
+        // Aggregate PublicKeys, which must not be infinity
+        let aggregate_public_key = AggregatePublicKey::aggregate(public_keys)
+            .map_err(|_| VerifyError::InvalidPublicKey)?;
 
-        // Aggregate PublicKeys
-        let aggregate_public_key = AggregatePublicKey::aggregate(public_keys);
-        if aggregate_public_key.is_err() {
-            return false;
-        }
-        let aggregate_public_key = aggregate_public_key.unwrap();
+        self.try_fast_aggregate_verify_pre_aggregated_with_dst(msg, &aggregate_public_key, DST_G2)
+    }
 
-        // Ensure AggregatePublicKey is not infinity
-        if aggregate_public_key.point.is_infinity() {
-            return false;
+    /// eth_fast_aggregate_verify
+    ///
+    /// FastAggregateVerify with the Ethereum consensus exception that the infinity
+    /// AggregateSignature is valid for an empty list of PublicKeys.
+    /// PublicKeys must all be verified via Proof of Possession before running this function.
+    /// https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/bls.md#eth_fast_aggregate_verify
+    pub fn eth_fast_aggregate_verify(&self, msg: &[u8], public_keys: &[&PublicKey]) -> bool {
+        if public_keys.is_empty() && self.point.is_infinity() {
+            return true;
         }
+        self.fast_aggregate_verify(msg, public_keys)
+    }
 
-        // Hash message to curve
-        let mut msg_hash = hash_to_curve_g2(msg);
-
-        // Points must be affine for pairing
-        let mut sig_point = self.point.clone();
-        let mut key_point = aggregate_public_key.point;
-        sig_point.affine();
-        key_point.affine();
-        msg_hash.affine();
-
-        let mut generator_g1_negative = amcl_utils::GroupG1::generator();
-        generator_g1_negative.neg(); // already affine
+    /// FastAggregateVerify - PublicKeys selected by a bitfield
+    ///
+    /// Verifies an AggregateSignature against the PublicKeys whose bits are set in the bitfield,
+    /// see `AggregatePublicKey::from_pubkeys_filtered()`.
+    /// PublicKeys must all be verified via Proof of Possession before running this function.
+    pub fn fast_aggregate_verify_filtered(
+        &self,
+        msg: &[u8],
+        public_keys: &[PublicKey],
+        bitfield: &[u8],
+    ) -> bool {
+        // Require at least one PublicKey
+        let aggregate_public_key =
+            match AggregatePublicKey::from_pubkeys_filtered(public_keys, bitfield) {
+                Ok(aggregate_public_key) => aggregate_public_key,
+                Err(_) => return false,
+            };
 
-        // Faster ate2 evaualtion checks e(S, -G1) * e(H, PK) == 1
-        ate2_evaluation(&sig_point, &generator_g1_negative, &msg_hash, &key_point)
+        self.fast_aggregate_verify_pre_aggregated(msg, &aggregate_public_key)
     }
 
     /// FastAggregateVerify - pre-aggregated PublicKeys
@@ -225,18 +492,60 @@ impl AggregateSignature {
         msg: &[u8],
         aggregate_public_key: &AggregatePublicKey,
     ) -> bool {
+        self.fast_aggregate_verify_pre_aggregated_with_dst(msg, aggregate_public_key, DST_G2)
+    }
+
+    /// FastAggregateVerify - pre-aggregated PublicKeys and custom domain separation tag
+    ///
+    /// Verifies an AggregateSignature against an AggregatePublicKey where the message was
+    /// hashed with the given domain separation tag.
+    /// PublicKeys must all be verified before being aggregated.
+    pub fn fast_aggregate_verify_pre_aggregated_with_dst(
+        &self,
+        msg: &[u8],
+        aggregate_public_key: &AggregatePublicKey,
+        dst: &[u8],
+    ) -> bool {
+        self.try_fast_aggregate_verify_pre_aggregated_with_dst(msg, aggregate_public_key, dst)
+            .is_ok()
+    }
+
+    /// FastAggregateVerify - pre-aggregated PublicKeys, with the reason for failure
+    ///
+    /// Verifies an AggregateSignature against an AggregatePublicKey, returning why
+    /// verification failed.
+    /// PublicKeys must all be verified before being aggregated.
+    pub fn try_fast_aggregate_verify_pre_aggregated(
+        &self,
+        msg: &[u8],
+        aggregate_public_key: &AggregatePublicKey,
+    ) -> Result<(), VerifyError> {
+        self.try_fast_aggregate_verify_pre_aggregated_with_dst(msg, aggregate_public_key, DST_G2)
+    }
+
+    fn try_fast_aggregate_verify_pre_aggregated_with_dst(
+        &self,
+        msg: &[u8],
+        aggregate_public_key: &AggregatePublicKey,
+        dst: &[u8],
+    ) -> Result<(), VerifyError> {
         // Subgroup check for signature
         if !subgroup_check_g2(&self.point) {
-            return false;
+            return Err(VerifyError::SignatureNotInSubgroup);
         }
 
         // Ensure AggregatePublicKey is not infinity
         if aggregate_public_key.point.is_infinity() {
-            return false;
+            return Err(VerifyError::InvalidPublicKey);
+        }
+
+        // Infinity is only valid for the infinity AggregatePublicKey
+        if self.point.is_infinity() {
+            return Err(VerifyError::InvalidSignature);
         }
 
         // Hash message to curve
-        let mut msg_hash = hash_to_curve_g2(msg);
+        let mut msg_hash = hash_to_curve_g2_with_dst(msg, dst);
 
         // Points must be affine for pairing
         let mut sig_point = self.point.clone();
@@ -245,11 +554,11 @@ impl AggregateSignature {
         key_point.affine();
         msg_hash.affine();
 
-        let mut generator_g1_negative = amcl_utils::GroupG1::generator();
-        generator_g1_negative.neg(); // already affine
-
         // Faster ate2 evaualtion checks e(S, -G1) * e(H, PK) == 1
-        ate2_evaluation(&sig_point, &generator_g1_negative, &msg_hash, &key_point)
+        if !ate2_evaluation(&sig_point, &NEGATIVE_GENERATORG1, &msg_hash, &key_point) {
+            return Err(VerifyError::InvalidSignature);
+        }
+        Ok(())
     }
 
     /// Verify Multiple AggregateSignatures
@@ -258,9 +567,13 @@ impl AggregateSignature {
     /// Checks that each AggregateSignature is valid with a reduced number of pairings.
     /// https://ethresear.ch/t/fast-verification-of-multiple-bls-signatures/5407
     /// Note: Assumes Proof of Possession of public keys.
+    ///
+    /// The random offsets are drawn from `rng` alone, so a seeded RNG reproduces verification
+    /// exactly, e.g. in deterministic simulation tests. In production it must be unpredictable
+    /// to whoever produced the signatures.
     pub fn verify_multiple_aggregate_signatures<'a, R, I>(rng: &mut R, signature_sets: I) -> bool
     where
-        R: Rng + ?Sized,
+        R: RngCore + CryptoRng + ?Sized,
         I: Iterator<Item = (&'a AggregateSignature, &'a AggregatePublicKey, &'a [u8])>,
     {
         // Sum of (AggregateSignature[i] * rand[i]) for all AggregateSignatures - S'
@@ -275,16 +588,17 @@ impl AggregateSignature {
                 return false;
             }
 
-            // TODO: Consider increasing rand security from 2^63 to 2^128
-            // Create random offset - rand[i]
-            let mut rand = 0;
-            while rand == 0 {
-                // Require: rand > 0
-                let mut rand_bytes = [0u8; 8]; // bytes
-                rng.fill(&mut rand_bytes);
-                rand = i64::from_be_bytes(rand_bytes).abs();
+            // AggregatePublicKey must not be infinity
+            if !ALLOW_INFINITY_PUBLIC_KEY && aggregate_public_key.point.is_infinity() {
+                return false;
             }
-            let rand = Big::new_int(rand as isize);
+
+            // Create random offset - rand[i], uniform over the non-zero scalars
+            let mut rand = Scalar::random(rng);
+            while rand.is_zero() {
+                rand = Scalar::random(rng);
+            }
+            let rand = rand.0;
 
             // Hash message to curve - H(message[i])
             let mut msg_hash = hash_to_curve_g2(message);
@@ -303,11 +617,9 @@ impl AggregateSignature {
             final_agg_sig.add(&g2mul(&aggregate_signature.point, &rand));
         }
 
-        // Pairing for LHS - e(As', G1)
-        let mut negative_g1 = GroupG1::generator();
-        negative_g1.neg(); // will be affine
+        // Pairing for LHS - e(As', -G1)
         final_agg_sig.affine();
-        pair::another(&mut pairing, &final_agg_sig, &negative_g1);
+        pair::another(&mut pairing, &final_agg_sig, &NEGATIVE_GENERATORG1);
 
         // Complete pairing and verify output is 1.
         let mut v = pair::miller(&pairing);
@@ -316,15 +628,46 @@ impl AggregateSignature {
     }
 
     /// Instatiate an AggregateSignature from some bytes.
-    pub fn from_bytes(bytes: &[u8]) -> Result<AggregateSignature, AmclError> {
+    pub fn from_bytes(bytes: &[u8]) -> Result<AggregateSignature, DecodeError> {
         let point = decompress_g2(bytes)?;
         Ok(Self { point })
     }
 
+    /// Instantiate an AggregateSignature from bytes under the given encoding rules.
+    pub fn from_bytes_with_strictness(
+        bytes: &[u8],
+        strictness: &Strictness,
+    ) -> Result<AggregateSignature, DecodeError> {
+        let point = strictness::decode_g2(bytes, strictness)?;
+        Ok(Self { point })
+    }
+
     /// Export (serialize) the AggregateSignature to bytes.
     pub fn as_bytes(&self) -> [u8; G2_BYTES] {
         compress_g2(&self.point)
     }
+
+    /// Export (serialize) the AggregateSignature to uncompressed (x, y) bytes.
+    pub fn to_uncompressed_bytes(&self) -> [u8; G2_BYTES * 2] {
+        serialize_uncompressed_g2(&self.point)
+    }
+
+    /// Export the AggregateSignature to the 256 byte encoding used by the EIP-2537 precompiles.
+    pub fn to_eip2537_bytes(&self) -> [u8; EIP2537_G2_BYTES] {
+        g2_to_eip2537(&self.point)
+    }
+
+    /// Returns true if the AggregateSignature is on the curve E2.
+    pub fn is_on_curve(&self) -> bool {
+        on_curve_check_g2(&self.point)
+    }
+
+    /// Returns true if the AggregateSignature is in the G2 subgroup.
+    ///
+    /// An AggregateSignature of Signatures which are each in G2 is in G2.
+    pub fn is_in_subgroup(&self) -> bool {
+        subgroup_check_g2(&self.point)
+    }
 }
 
 impl Default for AggregateSignature {
@@ -333,11 +676,24 @@ impl Default for AggregateSignature {
     }
 }
 
+impl<'a> AddAssign<&'a Signature> for AggregateSignature {
+    fn add_assign(&mut self, signature: &'a Signature) {
+        self.add(signature);
+    }
+}
+
+impl<'a> AddAssign<&'a AggregateSignature> for AggregateSignature {
+    fn add_assign(&mut self, aggregate_signature: &'a AggregateSignature) {
+        self.add_aggregate(aggregate_signature);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     extern crate hex;
     extern crate rand;
 
+    use super::super::amcl_utils::{self, Big};
     use super::super::keys::{Keypair, SecretKey};
     use super::*;
 
@@ -388,6 +744,34 @@ mod tests {
         assert!(!agg_sig.fast_aggregate_verify(&[0; 32], &[]));
     }
 
+    #[test]
+    fn test_infinity_fast_aggregate_verify() {
+        let agg_sig = AggregateSignature::new();
+        let keypair = Keypair::random(&mut rand::thread_rng());
+
+        // Infinity AggregateSignature should fail
+        assert!(!agg_sig.fast_aggregate_verify(&[0; 32], &[&keypair.pk]));
+        let agg_pub_key = AggregatePublicKey::from_public_key(&keypair.pk);
+        assert!(!agg_sig.fast_aggregate_verify_pre_aggregated(&[0; 32], &agg_pub_key));
+    }
+
+    #[test]
+    fn test_eth_fast_aggregate_verify() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let msg = [1u8; 32];
+        let agg_sig = AggregateSignature::from_signature(&Signature::new(&msg, &keypair.sk));
+        let infinity_sig = AggregateSignature::new();
+
+        // Infinity AggregateSignature is only valid without PublicKeys
+        assert!(infinity_sig.eth_fast_aggregate_verify(&msg, &[]));
+        assert!(!infinity_sig.eth_fast_aggregate_verify(&msg, &[&keypair.pk]));
+        assert!(!agg_sig.eth_fast_aggregate_verify(&msg, &[]));
+
+        // Otherwise the same as FastAggregateVerify
+        assert!(agg_sig.eth_fast_aggregate_verify(&msg, &[&keypair.pk]));
+        assert!(!agg_sig.eth_fast_aggregate_verify(&[2u8; 32], &[&keypair.pk]));
+    }
+
     #[test]
     fn test_split_zero_fast_aggregate_verify() {
         let agg_sig = AggregateSignature::new();
@@ -637,6 +1021,69 @@ mod tests {
         assert_eq!(add_aggregate_public_key, aggregate_public_key1234);
     }
 
+    #[test]
+    pub fn test_duplicate_public_keys() {
+        let keypair_1 = Keypair::random(&mut rand::thread_rng());
+        let keypair_2 = Keypair::random(&mut rand::thread_rng());
+        let keypair_3 = Keypair::random(&mut rand::thread_rng());
+
+        let keys = [&keypair_1.pk, &keypair_2.pk, &keypair_1.pk, &keypair_3.pk, &keypair_1.pk];
+        assert_eq!(AggregatePublicKey::duplicates(&keys), vec![(0, 2), (0, 4)]);
+
+        let keys = [&keypair_3.pk, &keypair_2.pk, &keypair_1.pk, &keypair_2.pk];
+        assert_eq!(AggregatePublicKey::duplicates(&keys), vec![(1, 3)]);
+
+        let keys = [&keypair_1.pk, &keypair_2.pk, &keypair_3.pk];
+        assert!(AggregatePublicKey::duplicates(&keys).is_empty());
+        assert!(AggregatePublicKey::duplicates(&[]).is_empty());
+    }
+
+    #[test]
+    pub fn test_aggregate_deduplicated() {
+        let keypair_1 = Keypair::random(&mut rand::thread_rng());
+        let keypair_2 = Keypair::random(&mut rand::thread_rng());
+
+        let (aggregate_public_key, duplicates) = AggregatePublicKey::aggregate_deduplicated(&[
+            &keypair_1.pk,
+            &keypair_2.pk,
+            &keypair_2.pk,
+            &keypair_1.pk,
+        ])
+        .unwrap();
+
+        assert_eq!(duplicates, vec![(1, 2), (0, 3)]);
+        assert_eq!(
+            aggregate_public_key,
+            AggregatePublicKey::aggregate(&[&keypair_1.pk, &keypair_2.pk]).unwrap()
+        );
+
+        assert_eq!(
+            AggregatePublicKey::aggregate_deduplicated(&[]),
+            Err(AmclError::AggregateEmptyPoints)
+        );
+    }
+
+    #[test]
+    pub fn remove_public_key() {
+        let keypair_1 = Keypair::random(&mut rand::thread_rng());
+        let keypair_2 = Keypair::random(&mut rand::thread_rng());
+        let keypair_3 = Keypair::random(&mut rand::thread_rng());
+
+        let aggregate_public_key13 =
+            AggregatePublicKey::aggregate(&[&keypair_1.pk, &keypair_3.pk]).unwrap();
+
+        // Removing a key should be the same as never adding it
+        let mut aggregate_public_key =
+            AggregatePublicKey::aggregate(&[&keypair_1.pk, &keypair_2.pk, &keypair_3.pk]).unwrap();
+        aggregate_public_key.remove(&keypair_2.pk);
+        assert_eq!(aggregate_public_key, aggregate_public_key13);
+
+        // Removing every key results in infinity
+        aggregate_public_key.remove(&keypair_1.pk);
+        aggregate_public_key.remove(&keypair_3.pk);
+        assert!(aggregate_public_key.point.is_infinity());
+    }
+
     #[test]
     pub fn add_aggregate_signature() {
         let msg: Vec<u8> = vec![1; 32];
@@ -684,6 +1131,55 @@ mod tests {
             .fast_aggregate_verify_pre_aggregated(&msg, &aggregate_public_key));
     }
 
+    #[test]
+    pub fn remove_signature() {
+        let msg: Vec<u8> = vec![1; 32];
+
+        let keypair_1 = Keypair::random(&mut rand::thread_rng());
+        let keypair_2 = Keypair::random(&mut rand::thread_rng());
+        let keypair_3 = Keypair::random(&mut rand::thread_rng());
+
+        let sig_1 = Signature::new(&msg, &keypair_1.sk);
+        let sig_2 = Signature::new(&msg, &keypair_2.sk);
+        let bad_sig = Signature::new(&[2; 32], &keypair_3.sk);
+
+        let aggregate_public_key =
+            AggregatePublicKey::aggregate(&[&keypair_1.pk, &keypair_2.pk]).unwrap();
+
+        let mut aggregate_signature = AggregateSignature::aggregate(&[&sig_1, &sig_2, &bad_sig]);
+        assert!(
+            !aggregate_signature.fast_aggregate_verify_pre_aggregated(&msg, &aggregate_public_key)
+        );
+
+        // Stripping the invalid signature should restore the aggregate
+        aggregate_signature.remove(&bad_sig);
+        assert!(
+            aggregate_signature.fast_aggregate_verify_pre_aggregated(&msg, &aggregate_public_key)
+        );
+
+        let mut expected = AggregateSignature::aggregate(&[&sig_1, &sig_2]);
+        expected.point.affine();
+        aggregate_signature.point.affine();
+        assert_eq!(aggregate_signature, expected);
+    }
+
+    #[test]
+    pub fn test_staged_subgroup_checks() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let aggregate_public_key = AggregatePublicKey::from_public_key(&keypair.pk);
+        assert!(aggregate_public_key.is_on_curve() && aggregate_public_key.is_in_subgroup());
+
+        // A Signature decoded without the subgroup check taints the aggregate
+        let mut aggregate_signature =
+            AggregateSignature::from_signature(&Signature::new(b"cats", &keypair.sk));
+        assert!(aggregate_signature.is_on_curve() && aggregate_signature.is_in_subgroup());
+        let point = GroupG2::map2point(&amcl_utils::FP2::new_ints(1, 2));
+        let unchecked = Signature::from_bytes_unchecked(&compress_g2(&point)).unwrap();
+        aggregate_signature.add(&unchecked);
+        assert!(aggregate_signature.is_on_curve());
+        assert!(!aggregate_signature.is_in_subgroup());
+    }
+
     #[test]
     pub fn test_verify_multiple_signatures() {
         let mut rng = &mut rand::thread_rng();
@@ -742,6 +1238,107 @@ mod tests {
         assert!(valid);
     }
 
+    #[test]
+    pub fn test_verify_multiple_signatures_seeded_rng() {
+        use self::rand::rngs::StdRng;
+        use self::rand::SeedableRng;
+
+        // Keys and batch verification are reproducible from a seed
+        let keypairs: Vec<Keypair> =
+            (0..4).map(|_| Keypair::random(&mut StdRng::seed_from_u64(7))).collect();
+        assert!(keypairs.iter().all(|keypair| keypair.sk == keypairs[0].sk));
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let keypairs: Vec<Keypair> = (0..4).map(|_| Keypair::random(&mut rng)).collect();
+        let msgs: Vec<Vec<u8>> = (0..4).map(|i| vec![i as u8; 32]).collect();
+        let aggregate_public_keys: Vec<AggregatePublicKey> = keypairs
+            .iter()
+            .map(|keypair| AggregatePublicKey::from_public_key(&keypair.pk))
+            .collect();
+        let mut aggregate_signatures: Vec<AggregateSignature> = keypairs
+            .iter()
+            .zip(&msgs)
+            .map(|(keypair, msg)| {
+                AggregateSignature::from_signature(&Signature::new(msg, &keypair.sk))
+            })
+            .collect();
+
+        let verify = |seed: u64, aggregate_signatures: &[AggregateSignature]| {
+            let signature_sets = aggregate_signatures
+                .iter()
+                .zip(&aggregate_public_keys)
+                .zip(&msgs)
+                .map(|((signature, public_key), msg)| (signature, public_key, msg.as_slice()));
+            AggregateSignature::verify_multiple_aggregate_signatures(
+                &mut StdRng::seed_from_u64(seed),
+                signature_sets,
+            )
+        };
+        assert!(verify(1, &aggregate_signatures));
+        assert!(verify(1, &aggregate_signatures));
+
+        aggregate_signatures.swap(0, 1);
+        assert!(!verify(1, &aggregate_signatures));
+        assert!(!verify(1, &aggregate_signatures));
+    }
+
+    // Fills with the bytes of i64::MIN, which has no absolute value.
+    struct I64MinRng;
+
+    impl RngCore for I64MinRng {
+        fn next_u32(&mut self) -> u32 {
+            0x8000_0000
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            i64::MIN as u64
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for chunk in dest.chunks_mut(8) {
+                chunk.copy_from_slice(&i64::MIN.to_be_bytes()[..chunk.len()]);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for I64MinRng {}
+
+    #[test]
+    pub fn test_verify_multiple_signatures_i64_min_rng() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let msg = [7u8; 32];
+        let aggregate_public_key = AggregatePublicKey::from_public_key(&keypair.pk);
+        let aggregate_signature =
+            AggregateSignature::from_signature(&Signature::new(&msg, &keypair.sk));
+
+        let signature_sets = vec![(&aggregate_signature, &aggregate_public_key, &msg[..])];
+        assert!(AggregateSignature::verify_multiple_aggregate_signatures(
+            &mut I64MinRng,
+            signature_sets.into_iter()
+        ));
+    }
+
+    #[test]
+    pub fn test_verify_multiple_signatures_infinity_public_key() {
+        let aggregate_signature = AggregateSignature::new();
+        let aggregate_public_key = AggregatePublicKey { point: GroupG1::new() };
+        let msg = [3u8; 32];
+
+        let signature_sets = vec![(&aggregate_signature, &aggregate_public_key, &msg[..])];
+        assert_eq!(
+            AggregateSignature::verify_multiple_aggregate_signatures(
+                &mut rand::thread_rng(),
+                signature_sets.into_iter()
+            ),
+            ALLOW_INFINITY_PUBLIC_KEY
+        );
+    }
+
     #[test]
     pub fn test_verify_multiple_signatures_invalid() {
         let mut rng = &mut rand::thread_rng();
@@ -829,6 +1426,133 @@ mod tests {
         assert!(aggregate_signature.aggregate_verify(&msgs_refs, &public_keys_refs));
     }
 
+    #[test]
+    fn test_to_public_key_and_signature() {
+        let keypairs: Vec<Keypair> =
+            (0..3).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let msg = [3u8; 32];
+        let pks: Vec<&PublicKey> = keypairs.iter().map(|k| &k.pk).collect();
+        let sigs: Vec<Signature> = keypairs.iter().map(|k| Signature::new(&msg, &k.sk)).collect();
+        let sig_refs: Vec<&Signature> = sigs.iter().collect();
+
+        let agg_pk = AggregatePublicKey::aggregate(&pks).unwrap();
+        let agg_sig = AggregateSignature::aggregate(&sig_refs);
+        let pk = agg_pk.to_public_key();
+        let sig = agg_sig.to_signature();
+        assert_eq!(pk.as_bytes(), agg_pk.as_bytes());
+        assert_eq!(sig.as_bytes(), agg_sig.as_bytes());
+
+        // FastAggregateVerify is Verify against the aggregated PublicKey
+        assert!(sig.verify(&msg, &pk));
+        assert_eq!(AggregatePublicKey::from_public_key(&pk), agg_pk);
+        assert_eq!(AggregateSignature::from_signature(&sig), agg_sig);
+    }
+
+    #[test]
+    fn test_try_aggregate_verify() {
+        let keypairs: Vec<Keypair> =
+            (0..2).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let pks: Vec<&PublicKey> = keypairs.iter().map(|k| &k.pk).collect();
+        let msgs: Vec<&[u8]> = vec![&[1u8; 32], &[2u8; 32]];
+        let sigs: Vec<Signature> =
+            keypairs.iter().zip(&msgs).map(|(k, msg)| Signature::new(msg, &k.sk)).collect();
+        let agg_sig = AggregateSignature::aggregate(&sigs.iter().collect::<Vec<_>>());
+
+        assert_eq!(agg_sig.try_aggregate_verify(&msgs, &pks), Ok(()));
+        assert_eq!(
+            agg_sig.try_aggregate_verify(&msgs[..1], &pks),
+            Err(VerifyError::LengthMismatch)
+        );
+        assert_eq!(agg_sig.try_aggregate_verify(&[], &[]), Err(VerifyError::EmptyInput));
+        assert_eq!(
+            agg_sig.try_aggregate_verify(&[msgs[1], msgs[0]], &pks),
+            Err(VerifyError::InvalidSignature)
+        );
+
+        let not_in_subgroup =
+            AggregateSignature { point: GroupG2::map2point(&amcl_utils::FP2::new_ints(1, 2)) };
+        assert_eq!(
+            not_in_subgroup.try_aggregate_verify(&msgs, &pks),
+            Err(VerifyError::SignatureNotInSubgroup)
+        );
+    }
+
+    #[test]
+    fn test_try_fast_aggregate_verify() {
+        let keypairs: Vec<Keypair> =
+            (0..2).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let pks: Vec<&PublicKey> = keypairs.iter().map(|k| &k.pk).collect();
+        let msg = [1u8; 32];
+        let sigs: Vec<Signature> = keypairs.iter().map(|k| Signature::new(&msg, &k.sk)).collect();
+        let agg_sig = AggregateSignature::aggregate(&sigs.iter().collect::<Vec<_>>());
+
+        assert_eq!(agg_sig.try_fast_aggregate_verify(&msg, &pks), Ok(()));
+        assert_eq!(agg_sig.try_fast_aggregate_verify(&msg, &[]), Err(VerifyError::EmptyInput));
+        assert_eq!(
+            agg_sig.try_fast_aggregate_verify(&[2u8; 32], &pks),
+            Err(VerifyError::InvalidSignature)
+        );
+        assert_eq!(
+            AggregateSignature::new().try_fast_aggregate_verify(&msg, &pks),
+            Err(VerifyError::InvalidSignature)
+        );
+
+        // PublicKeys summing to infinity
+        let mut negated = keypairs[0].pk.clone();
+        negated.point.neg();
+        assert_eq!(
+            agg_sig.try_fast_aggregate_verify(&msg, &[&keypairs[0].pk, &negated]),
+            Err(VerifyError::InvalidPublicKey)
+        );
+        let agg_pk = AggregatePublicKey::aggregate(&pks).unwrap();
+        assert_eq!(agg_sig.try_fast_aggregate_verify_pre_aggregated(&msg, &agg_pk), Ok(()));
+    }
+
+    #[test]
+    fn test_aggregate_public_key_errors() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let infinity = PublicKey { point: GroupG1::new() };
+
+        assert_eq!(AggregatePublicKey::aggregate(&[]), Err(AmclError::AggregateEmptyPoints));
+        assert_eq!(AggregatePublicKey::into_aggregate(&[]), Err(AmclError::AggregateEmptyPoints));
+
+        let aggregate = AggregatePublicKey::aggregate(&[&keypair.pk, &infinity]);
+        let into_aggregate = AggregatePublicKey::into_aggregate(&[keypair.pk.clone(), infinity]);
+        if ALLOW_INFINITY_PUBLIC_KEY {
+            let expected = AggregatePublicKey::from_public_key(&keypair.pk);
+            assert_eq!(aggregate, Ok(expected.clone()));
+            assert_eq!(into_aggregate, Ok(expected));
+        } else {
+            assert_eq!(aggregate, Err(AmclError::InvalidPoint));
+            assert_eq!(into_aggregate, Err(AmclError::InvalidPoint));
+        }
+
+        // Infinity PublicKeys are rejected by FastAggregateVerify
+        let sig = Signature::new(&[1u8; 32], &keypair.sk);
+        let agg_sig = AggregateSignature::from_signature(&sig);
+        let infinity = PublicKey { point: GroupG1::new() };
+        assert_eq!(
+            agg_sig.fast_aggregate_verify(&[1u8; 32], &[&keypair.pk, &infinity]),
+            ALLOW_INFINITY_PUBLIC_KEY
+        );
+    }
+
+    #[test]
+    fn test_aggregate_verify_infinity_public_key() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let infinity = PublicKey { point: GroupG1::new() };
+        let msgs: Vec<&[u8]> = vec![&[1u8; 32], &[2u8; 32]];
+
+        // The infinity PublicKey contributes e(H(msg), infinity) = 1
+        let aggregate_signature =
+            AggregateSignature::from_signature(&Signature::new(msgs[0], &keypair.sk));
+
+        assert_eq!(
+            aggregate_signature.aggregate_verify(&msgs, &[&keypair.pk, &infinity]),
+            ALLOW_INFINITY_PUBLIC_KEY
+        );
+    }
+
     #[test]
     fn test_aggregate_verify_msg_repeat() {
         let mut rng = &mut rand::thread_rng();
@@ -939,6 +1663,36 @@ mod tests {
         assert_eq!(public_key.point, aggregate_public_key.point);
     }
 
+    #[test]
+    fn test_as_bytes() {
+        let keypairs: Vec<Keypair> =
+            (0..3).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let public_keys: Vec<&PublicKey> = keypairs.iter().map(|kp| &kp.pk).collect();
+        let signatures: Vec<Signature> =
+            keypairs.iter().map(|kp| Signature::new(&[1; 32], &kp.sk)).collect();
+        let signatures: Vec<&Signature> = signatures.iter().collect();
+
+        let aggregate_public_key = AggregatePublicKey::aggregate(&public_keys).unwrap();
+        let bytes = aggregate_public_key.as_bytes();
+        assert_eq!(PublicKey::from_bytes(&bytes).unwrap().point, aggregate_public_key.point);
+        assert_eq!(AggregatePublicKey::from_bytes(&bytes).unwrap(), aggregate_public_key);
+        let mut infinity = [0u8; G1_BYTES];
+        infinity[0] = 0xc0;
+        assert!(AggregatePublicKey::from_bytes(&infinity).unwrap().point.is_infinity());
+        let bytes = aggregate_public_key.to_uncompressed_bytes();
+        assert_eq!(
+            PublicKey::from_uncompressed_bytes(&bytes).unwrap().point,
+            aggregate_public_key.point
+        );
+
+        let aggregate_signature = AggregateSignature::aggregate(&signatures);
+        let bytes = aggregate_signature.to_uncompressed_bytes();
+        assert_eq!(
+            Signature::from_uncompressed_bytes(&bytes).unwrap().point,
+            aggregate_signature.point
+        );
+    }
+
     #[test]
     fn test_from_signature() {
         let multiplier = Big::new_int(5);
@@ -950,6 +1704,85 @@ mod tests {
         assert_eq!(signature.point, aggregate_signature.point);
     }
 
+    #[test]
+    fn test_from_pubkeys_filtered() {
+        let keypairs: Vec<Keypair> =
+            (0..10).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let public_keys: Vec<PublicKey> = keypairs.iter().map(|kp| kp.pk.clone()).collect();
+
+        // Select keys 1, 2 and 8
+        let bitfield = [0b0000_0110, 0b0000_0001];
+        let filtered = AggregatePublicKey::from_pubkeys_filtered(&public_keys, &bitfield).unwrap();
+        let expected =
+            AggregatePublicKey::aggregate(&[&public_keys[1], &public_keys[2], &public_keys[8]])
+                .unwrap();
+        assert_eq!(filtered, expected);
+
+        // Missing bytes are unset
+        let filtered = AggregatePublicKey::from_pubkeys_filtered(&public_keys, &bitfield[..1]);
+        let expected = AggregatePublicKey::aggregate(&[&public_keys[1], &public_keys[2]]);
+        assert_eq!(filtered, expected);
+
+        // No keys selected
+        assert_eq!(
+            AggregatePublicKey::from_pubkeys_filtered(&public_keys, &[0, 0]),
+            Err(AmclError::AggregateEmptyPoints)
+        );
+        assert_eq!(
+            AggregatePublicKey::from_pubkeys_filtered(&public_keys, &[]),
+            Err(AmclError::AggregateEmptyPoints)
+        );
+
+        // Set bits without a PublicKey, including in the padding of the last byte
+        assert_eq!(
+            AggregatePublicKey::from_pubkeys_filtered(&public_keys, &[0b0000_0001, 0b0000_0100]),
+            Err(AmclError::InvalidPoint)
+        );
+        assert_eq!(
+            AggregatePublicKey::from_pubkeys_filtered(&public_keys, &[0b0000_0001, 0, 0b0000_0001]),
+            Err(AmclError::InvalidPoint)
+        );
+
+        // The infinity PublicKey is rejected as in aggregate()
+        let mut with_infinity = public_keys.clone();
+        with_infinity[3] = PublicKey { point: GroupG1::new() };
+        let filtered = AggregatePublicKey::from_pubkeys_filtered(&with_infinity, &[0b0000_1000]);
+        if ALLOW_INFINITY_PUBLIC_KEY {
+            assert!(filtered.is_ok());
+        } else {
+            assert_eq!(filtered, Err(AmclError::InvalidPoint));
+        }
+        assert_eq!(
+            AggregatePublicKey::from_pubkeys_filtered(&with_infinity, &[0b0000_0010]),
+            AggregatePublicKey::aggregate(&[&public_keys[1]])
+        );
+    }
+
+    #[test]
+    fn test_fast_aggregate_verify_filtered() {
+        let msg = [3u8; 32];
+        let keypairs: Vec<Keypair> =
+            (0..10).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let public_keys: Vec<PublicKey> = keypairs.iter().map(|kp| kp.pk.clone()).collect();
+
+        let mut aggregate_signature = AggregateSignature::new();
+        for i in &[0, 5, 9] {
+            aggregate_signature.add(&Signature::new(&msg, &keypairs[*i].sk));
+        }
+
+        assert!(aggregate_signature.fast_aggregate_verify_filtered(
+            &msg,
+            &public_keys,
+            &[0b0010_0001, 0b0000_0010]
+        ));
+        assert!(!aggregate_signature.fast_aggregate_verify_filtered(
+            &msg,
+            &public_keys,
+            &[0b0010_0001, 0b0000_0000]
+        ));
+        assert!(!aggregate_signature.fast_aggregate_verify_filtered(&msg, &public_keys, &[]));
+    }
+
     #[test]
     fn test_readme() {
         // An exact replica of the README.md at the top level.
@@ -994,4 +1827,36 @@ mod tests {
         // Verify the AggregateSignature against the AggregatePublicKey
         assert!(agg_sig.fast_aggregate_verify_pre_aggregated(&message, &agg_pub_key));
     }
+
+    #[test]
+    fn test_aggregate_signature_point() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let sig = Signature::new(b"point", &keypair.sk);
+        let agg_sig = AggregateSignature::aggregate(&[&sig, &sig]);
+
+        let point = agg_sig.as_point();
+        assert_eq!(point, sig.as_point().add(&sig.as_point()));
+        assert_eq!(AggregateSignature::from_point(&point), agg_sig);
+        assert_eq!(AggregateSignature::from_point(&sig.as_point()).as_bytes(), sig.as_bytes());
+    }
+
+    #[test]
+    fn test_aggregate_signature_add_assign() {
+        let keypairs: Vec<Keypair> =
+            (0..4).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let msg = b"add_assign";
+        let sigs: Vec<Signature> = keypairs.iter().map(|k| Signature::new(msg, &k.sk)).collect();
+        let sig_refs: Vec<&Signature> = sigs.iter().collect();
+
+        let mut agg_sig = AggregateSignature::new();
+        for sig in &sigs[..2] {
+            agg_sig += sig;
+        }
+        agg_sig += &AggregateSignature::aggregate(&sig_refs[2..]);
+        assert_eq!(agg_sig, AggregateSignature::aggregate(&sig_refs));
+
+        let pks: Vec<&PublicKey> = keypairs.iter().map(|k| &k.pk).collect();
+        assert!(agg_sig.fast_aggregate_verify(msg, &pks));
+        assert!(AggregateSignature::aggregate(&[]).point.is_infinity());
+    }
 }
@@ -1,19 +1,23 @@
 extern crate amcl;
 #[cfg(feature = "std")]
 extern crate hex;
-extern crate rand;
 
-use BLSCurve::bls381::proof_of_possession::DST_G2;
+#[cfg(feature = "constant-time")]
+use super::constant_time;
+use super::errors::{DecodeError, EncodingFlag};
+use super::hash_to_curve::{self, XmdHash};
+use spin::Lazy;
 use BLSCurve::ecp::ECP;
 use BLSCurve::ecp2::ECP2;
 use BLSCurve::pair::{ate2, fexp};
+use BLSCurve::rom::{CURVE_BNX, FRA, FRB, MODULUS};
 
 pub use amcl::errors::AmclError;
 pub use BLSCurve::big::{Big, MODBYTES};
-pub use BLSCurve::bls381::proof_of_possession::{G1_BYTES, G2_BYTES, SECRET_KEY_BYTES};
+pub use BLSCurve::bls381::proof_of_possession::{DST_G2, G1_BYTES, G2_BYTES, SECRET_KEY_BYTES};
 pub use BLSCurve::bls381::utils::{
-    self, deserialize_g1, deserialize_g2, serialize_g1, serialize_g2, subgroup_check_g1,
-    subgroup_check_g2,
+    deserialize_g1, deserialize_g2, serialize_g1, serialize_g2, serialize_uncompressed_g1,
+    serialize_uncompressed_g2,
 };
 pub use BLSCurve::fp12::FP12;
 pub use BLSCurve::fp2::FP2;
@@ -23,15 +27,136 @@ pub use BLSCurve::rom::CURVE_ORDER;
 pub type GroupG1 = ECP;
 pub type GroupG2 = ECP2;
 
-#[cfg(feature = "std")]
-lazy_static! {
-    pub static ref GENERATORG1: GroupG1 = GroupG1::generator();
-    pub static ref GENERATORG2: GroupG2 = GroupG2::generator();
-}
+/// The length of a compressed PublicKey.
+pub const PUBLIC_KEY_BYTES: usize = G1_BYTES;
+/// The length of an uncompressed PublicKey, the (x, y) coordinates.
+pub const PUBLIC_KEY_UNCOMPRESSED_BYTES: usize = G1_BYTES * 2;
+/// The length of a compressed Signature.
+pub const SIGNATURE_BYTES: usize = G2_BYTES;
+/// The length of an uncompressed Signature, the (x, y) coordinates.
+pub const SIGNATURE_UNCOMPRESSED_BYTES: usize = G2_BYTES * 2;
+/// The length of a G1 point as encoded by the EIP-2537 precompiles.
+pub const EIP2537_G1_BYTES: usize = EIP2537_FP_BYTES * 2;
+/// The length of a G2 point as encoded by the EIP-2537 precompiles.
+pub const EIP2537_G2_BYTES: usize = EIP2537_FP_BYTES * 4;
+
+// EIP-2537 field elements are big-endian and left padded with zeros to 64 bytes.
+const EIP2537_FP_BYTES: usize = 64;
+const EIP2537_PADDING_BYTES: usize = EIP2537_FP_BYTES - MODBYTES;
+
+// Cube root of unity in Fp, such that phi(x, y) = (beta * x, y) is the multiplication by -x^2
+// on G1, where x is the curve parameter.
+const BETA: [u8; MODBYTES] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 95, 25, 103, 47, 223, 118, 206, 81, 186, 105, 198, 7, 106, 15, 119,
+    234, 221, 179, 169, 59, 230, 248, 150, 136, 222, 23, 216, 19, 98, 10, 0, 2, 46, 1, 255, 255,
+    255, 254, 255, 254,
+];
+
+// The field modulus p as big-endian bytes.
+pub(crate) const MODULUS_BYTES: [u8; MODBYTES] = [
+    26, 1, 17, 234, 57, 127, 230, 154, 75, 27, 167, 182, 67, 75, 172, 215, 100, 119, 75, 132, 243,
+    133, 18, 191, 103, 48, 210, 160, 246, 176, 246, 36, 30, 171, 255, 254, 177, 83, 255, 255, 185,
+    254, 255, 255, 255, 255, 170, 171,
+];
+// Flags in the first byte of an encoded point.
+pub(crate) const COMPRESSION_FLAG: u8 = 0b1000_0000;
+pub(crate) const INFINITY_FLAG: u8 = 0b0100_0000;
+pub(crate) const SORT_FLAG: u8 = 0b0010_0000;
+pub(crate) const FLAGS_MASK: u8 = COMPRESSION_FLAG | INFINITY_FLAG | SORT_FLAG;
+
+// Generators are computed once on first use, `spin::Lazy` does not require std.
+pub static GENERATORG1: Lazy<GroupG1> = Lazy::new(GroupG1::generator);
+pub static GENERATORG2: Lazy<GroupG2> = Lazy::new(GroupG2::generator);
+
+// The negated generators of the verification equations, affine as the Miller loop requires.
+pub static NEGATIVE_GENERATORG1: Lazy<GroupG1> = Lazy::new(|| {
+    let mut point = GroupG1::generator();
+    point.neg();
+    point.affine();
+    point
+});
+pub static NEGATIVE_GENERATORG2: Lazy<GroupG2> = Lazy::new(|| {
+    let mut point = GroupG2::generator();
+    point.neg();
+    point.affine();
+    point
+});
 
 // Take given message convert it to GroupG2 point
 pub fn hash_to_curve_g2(msg: &[u8]) -> GroupG2 {
-    utils::hash_to_curve_g2(msg, DST_G2)
+    hash_to_curve_g2_with_dst(msg, DST_G2)
+}
+
+// Take given message and domain separation tag and convert it to GroupG2 point
+//
+// Hashes on the stack with the generic expand_message_xmd, which cannot fail for SHA-256.
+pub fn hash_to_curve_g2_with_dst(msg: &[u8], dst: &[u8]) -> GroupG2 {
+    hash_to_curve::hash_to_curve_g2(msg, dst, XmdHash::Sha256).unwrap_or_else(|_| GroupG2::new())
+}
+
+// Take given message and domain separation tag and convert it to GroupG1 point
+//
+// Hashes on the stack with the generic expand_message_xmd, which cannot fail for SHA-256.
+pub fn hash_to_curve_g1_with_dst(msg: &[u8], dst: &[u8]) -> GroupG1 {
+    hash_to_curve::hash_to_curve_g1(msg, dst, XmdHash::Sha256).unwrap_or_else(|_| GroupG1::new())
+}
+
+// Check a point satisfies y^2 = x^3 + 4, the point at infinity is on the curve.
+pub fn on_curve_check_g1(point: &GroupG1) -> bool {
+    // new_bigs gives the point at infinity for coordinates not on the curve
+    point.is_infinity() || !GroupG1::new_bigs(&point.getx(), &point.gety()).is_infinity()
+}
+
+// Check a point satisfies y^2 = x^3 + 4(u + 1), the point at infinity is on the curve.
+pub fn on_curve_check_g2(point: &GroupG2) -> bool {
+    // new_fp2s gives the point at infinity for coordinates not on the curve
+    point.is_infinity() || !GroupG2::new_fp2s(&point.getx(), &point.gety()).is_infinity()
+}
+
+// Check a point is in G1 using the endomorphism phi(P) == -x^2 * P.
+// See https://eprint.iacr.org/2021/1130
+pub fn subgroup_check_g1(point: &GroupG1) -> bool {
+    if point.is_infinity() {
+        return true;
+    }
+
+    // phi(P) = (beta * x, y)
+    let mut point = point.clone();
+    point.affine();
+    let phi_x = Big::modmul(&point.getx(), &Big::from_bytes(&BETA), &Big::new_ints(&MODULUS));
+    let phi = GroupG1::new_bigs(&phi_x, &point.gety());
+
+    // -x^2 * P
+    let x = Big::new_ints(&CURVE_BNX);
+    let mut x2_point = point.mul(&x).mul(&x);
+    x2_point.neg();
+
+    phi.equals(&x2_point)
+}
+
+// Check a point is in G2 using the endomorphism psi(P) == x * P.
+// See https://eprint.iacr.org/2021/1130
+pub fn subgroup_check_g2(point: &GroupG2) -> bool {
+    if point.is_infinity() {
+        return true;
+    }
+
+    // x * P, where x is negative
+    let mut x_point = point.mul(&Big::new_ints(&CURVE_BNX));
+    x_point.neg();
+
+    psi(point).equals(&x_point)
+}
+
+/// The endomorphism psi = untwist-Frobenius-twist on E2, which is multiplication by the curve
+/// parameter x on G2.
+///
+/// The point is not converted to affine coordinates.
+/// See https://eprint.iacr.org/2017/419
+pub fn psi(point: &GroupG2) -> GroupG2 {
+    let mut psi = point.clone();
+    psi.frob(&FP2::new_bigs(&Big::new_ints(&FRA), &Big::new_ints(&FRB)));
+    psi
 }
 
 // Evaluation of e(A, B) * e(C, D) == 1
@@ -47,14 +172,37 @@ pub fn compress_g1(g1: &GroupG1) -> [u8; G1_BYTES] {
     serialize_g1(g1)
 }
 
-// Take a 384 bit array and convert to GroupG1 point (x, y)
+// Take a 384 bit array and convert to GroupG1 point (x, y) in the G1 subgroup
 // See https://github.com/zkcrypto/pairing/blob/master/src/bls12_381/README.md#serialization
-pub fn decompress_g1(g1_bytes: &[u8]) -> Result<GroupG1, AmclError> {
-    // Ensure it is compressed
-    if g1_bytes.len() != G1_BYTES {
-        return Err(AmclError::InvalidG1Size);
+pub fn decompress_g1(g1_bytes: &[u8]) -> Result<GroupG1, DecodeError> {
+    let point = decompress_g1_unchecked(g1_bytes)?;
+    if !subgroup_check_g1(&point) {
+        return Err(DecodeError::NotInSubgroup);
+    }
+    Ok(point)
+}
+
+// Take a 384 bit array and convert to GroupG1 point (x, y) without a subgroup check.
+// MUST only be used on trusted data.
+// With the `constant-time` feature the decompression does not branch on the bytes.
+pub fn decompress_g1_unchecked(g1_bytes: &[u8]) -> Result<GroupG1, DecodeError> {
+    #[cfg(feature = "constant-time")]
+    {
+        constant_time::decompress_g1(g1_bytes)
+    }
+    #[cfg(not(feature = "constant-time"))]
+    {
+        // Ensure it is compressed
+        if g1_bytes.len() != G1_BYTES {
+            return Err(DecodeError::InvalidLength { expected: G1_BYTES, actual: g1_bytes.len() });
+        }
+        check_flags(g1_bytes, true)?;
+        // Ensure x < p
+        if !is_canonical_fp(g1_bytes, true) {
+            return Err(DecodeError::FieldElementTooLarge);
+        }
+        Ok(deserialize_g1(g1_bytes)?)
     }
-    deserialize_g1(g1_bytes)
 }
 
 // Take a GroupG2 point (x, y) and compress it to a 384*2 bit array.
@@ -63,53 +211,570 @@ pub fn compress_g2(g2: &GroupG2) -> [u8; G2_BYTES] {
     serialize_g2(g2)
 }
 
-// Take a 384*2 bit array and convert to GroupG2 point (x, y)
+// Take a 384*2 bit array and convert to GroupG2 point (x, y) in the G2 subgroup
 // See https://github.com/zkcrypto/pairing/blob/master/src/bls12_381/README.md#serialization
-pub fn decompress_g2(g2_bytes: &[u8]) -> Result<GroupG2, AmclError> {
-    // Ensure it is compressed
-    if g2_bytes.len() != G2_BYTES {
-        return Err(AmclError::InvalidG2Size);
+pub fn decompress_g2(g2_bytes: &[u8]) -> Result<GroupG2, DecodeError> {
+    let point = decompress_g2_unchecked(g2_bytes)?;
+    if !subgroup_check_g2(&point) {
+        return Err(DecodeError::NotInSubgroup);
+    }
+    Ok(point)
+}
+
+// Take a 384*2 bit array and convert to GroupG2 point (x, y) without a subgroup check.
+// MUST only be used on trusted data.
+// With the `constant-time` feature the decompression does not branch on the bytes.
+pub fn decompress_g2_unchecked(g2_bytes: &[u8]) -> Result<GroupG2, DecodeError> {
+    #[cfg(feature = "constant-time")]
+    {
+        constant_time::decompress_g2(g2_bytes)
+    }
+    #[cfg(not(feature = "constant-time"))]
+    {
+        // Ensure it is compressed
+        if g2_bytes.len() != G2_BYTES {
+            return Err(DecodeError::InvalidLength { expected: G2_BYTES, actual: g2_bytes.len() });
+        }
+        check_flags(g2_bytes, true)?;
+        // Ensure x = (x1, x0) has x1 < p and x0 < p
+        if !is_canonical_fp(&g2_bytes[..MODBYTES], true)
+            || !is_canonical_fp(&g2_bytes[MODBYTES..], false)
+        {
+            return Err(DecodeError::FieldElementTooLarge);
+        }
+        Ok(deserialize_g2(g2_bytes)?)
+    }
+}
+
+// Take a 384*2 bit array of uncompressed (x, y) and convert to GroupG1 point without a subgroup
+// check.
+pub fn deserialize_uncompressed_g1(g1_bytes: &[u8]) -> Result<GroupG1, DecodeError> {
+    if g1_bytes.len() != G1_BYTES * 2 {
+        return Err(DecodeError::InvalidLength { expected: G1_BYTES * 2, actual: g1_bytes.len() });
+    }
+    check_flags(g1_bytes, false)?;
+    // Ensure x < p and y < p
+    if !is_canonical_fp(&g1_bytes[..MODBYTES], true)
+        || !is_canonical_fp(&g1_bytes[MODBYTES..], false)
+    {
+        return Err(DecodeError::FieldElementTooLarge);
+    }
+    Ok(deserialize_g1(g1_bytes)?)
+}
+
+// Take a 384*4 bit array of uncompressed (x, y) and convert to GroupG2 point without a subgroup
+// check.
+pub fn deserialize_uncompressed_g2(g2_bytes: &[u8]) -> Result<GroupG2, DecodeError> {
+    if g2_bytes.len() != G2_BYTES * 2 {
+        return Err(DecodeError::InvalidLength { expected: G2_BYTES * 2, actual: g2_bytes.len() });
+    }
+    check_flags(g2_bytes, false)?;
+    // Ensure x = (x1, x0) and y = (y1, y0) have all parts < p
+    if !is_canonical_fp(&g2_bytes[..MODBYTES], true)
+        || g2_bytes[MODBYTES..].chunks(MODBYTES).any(|part| !is_canonical_fp(part, false))
+    {
+        return Err(DecodeError::FieldElementTooLarge);
+    }
+    Ok(deserialize_g2(g2_bytes)?)
+}
+
+// Check the flags of an encoded point are consistent with its encoding.
+pub(crate) fn check_flags(bytes: &[u8], compressed: bool) -> Result<(), DecodeError> {
+    let (first, rest) = match bytes.split_first() {
+        Some(split) => split,
+        None => return Err(DecodeError::InvalidFlag(EncodingFlag::Compression)),
+    };
+    if (first & COMPRESSION_FLAG != 0) != compressed {
+        return Err(DecodeError::InvalidFlag(EncodingFlag::Compression));
+    }
+    if first & INFINITY_FLAG != 0 {
+        if first & SORT_FLAG != 0 {
+            return Err(DecodeError::InvalidFlag(EncodingFlag::Sort));
+        }
+        // All other bits of infinity must be zero
+        if first & !FLAGS_MASK != 0 || rest.iter().any(|byte| *byte != 0) {
+            return Err(DecodeError::InvalidFlag(EncodingFlag::Infinity));
+        }
+    } else if !compressed && first & SORT_FLAG != 0 {
+        return Err(DecodeError::InvalidFlag(EncodingFlag::Sort));
+    }
+    Ok(())
+}
+
+// Take big-endian affine coordinates (x, y) and convert to GroupG1 point without a subgroup
+// check. (0, 0) is the point at infinity.
+pub fn g1_from_coordinates(x: &[u8; MODBYTES], y: &[u8; MODBYTES]) -> Result<GroupG1, DecodeError> {
+    if !is_canonical_fp(x, false) || !is_canonical_fp(y, false) {
+        return Err(DecodeError::FieldElementTooLarge);
+    }
+    if x.iter().chain(y.iter()).all(|byte| *byte == 0) {
+        return Ok(GroupG1::new());
+    }
+
+    // Point is set to infinity if (x, y) is not on the curve
+    let point = GroupG1::new_bigs(&Big::from_bytes(x), &Big::from_bytes(y));
+    if point.is_infinity() {
+        return Err(DecodeError::NotOnCurve);
+    }
+    Ok(point)
+}
+
+// Take a GroupG1 point and convert to big-endian affine coordinates (x, y).
+// The point at infinity is (0, 0).
+pub fn g1_to_coordinates(point: &GroupG1) -> ([u8; MODBYTES], [u8; MODBYTES]) {
+    let mut x = [0u8; MODBYTES];
+    let mut y = [0u8; MODBYTES];
+    if !point.is_infinity() {
+        point.getx().to_bytes(&mut x);
+        point.gety().to_bytes(&mut y);
+    }
+    (x, y)
+}
+
+// Take big-endian affine coordinates (x, y), each encoded as (c1, c0), and convert to GroupG2
+// point without a subgroup check. (0, 0) is the point at infinity.
+pub fn g2_from_coordinates(
+    x: &[u8; MODBYTES * 2],
+    y: &[u8; MODBYTES * 2],
+) -> Result<GroupG2, DecodeError> {
+    if x.chunks(MODBYTES).chain(y.chunks(MODBYTES)).any(|part| !is_canonical_fp(part, false)) {
+        return Err(DecodeError::FieldElementTooLarge);
+    }
+    if x.iter().chain(y.iter()).all(|byte| *byte == 0) {
+        return Ok(GroupG2::new());
+    }
+
+    // Point is set to infinity if (x, y) is not on the curve
+    let point = GroupG2::new_fp2s(&fp2_from_bytes(x), &fp2_from_bytes(y));
+    if point.is_infinity() {
+        return Err(DecodeError::NotOnCurve);
+    }
+    Ok(point)
+}
+
+// Take a GroupG2 point and convert to big-endian affine coordinates (x, y), each encoded as
+// (c1, c0). The point at infinity is (0, 0).
+pub fn g2_to_coordinates(point: &GroupG2) -> ([u8; MODBYTES * 2], [u8; MODBYTES * 2]) {
+    let mut x = [0u8; MODBYTES * 2];
+    let mut y = [0u8; MODBYTES * 2];
+    if !point.is_infinity() {
+        fp2_to_bytes(&point.getx(), &mut x);
+        fp2_to_bytes(&point.gety(), &mut y);
+    }
+    (x, y)
+}
+
+// Take a GroupG1 point and convert to the EIP-2537 encoding of (x, y).
+// The point at infinity is all zeros.
+// See https://eips.ethereum.org/EIPS/eip-2537#fine-points-and-encoding-of-base-elements
+pub fn g1_to_eip2537(point: &GroupG1) -> [u8; EIP2537_G1_BYTES] {
+    let (x, y) = g1_to_coordinates(point);
+    let mut bytes = [0u8; EIP2537_G1_BYTES];
+    for (chunk, element) in bytes.chunks_mut(EIP2537_FP_BYTES).zip(&[x, y]) {
+        chunk[EIP2537_PADDING_BYTES..].copy_from_slice(element);
+    }
+    bytes
+}
+
+// Take the EIP-2537 encoding of (x, y) and convert to GroupG1 point without a subgroup check.
+pub fn g1_from_eip2537(bytes: &[u8]) -> Result<GroupG1, DecodeError> {
+    if bytes.len() != EIP2537_G1_BYTES {
+        return Err(DecodeError::InvalidLength { expected: EIP2537_G1_BYTES, actual: bytes.len() });
+    }
+    let x = eip2537_unpad(&bytes[..EIP2537_FP_BYTES])?;
+    let y = eip2537_unpad(&bytes[EIP2537_FP_BYTES..])?;
+    g1_from_coordinates(&x, &y)
+}
+
+// Take a GroupG2 point and convert to the EIP-2537 encoding of (x, y), with each element
+// encoded as (c0, c1). The point at infinity is all zeros.
+pub fn g2_to_eip2537(point: &GroupG2) -> [u8; EIP2537_G2_BYTES] {
+    let (x, y) = g2_to_coordinates(point);
+    let mut bytes = [0u8; EIP2537_G2_BYTES];
+    // Coordinates are encoded as (c1, c0)
+    let elements = [&x[MODBYTES..], &x[..MODBYTES], &y[MODBYTES..], &y[..MODBYTES]];
+    for (chunk, element) in bytes.chunks_mut(EIP2537_FP_BYTES).zip(elements.iter()) {
+        chunk[EIP2537_PADDING_BYTES..].copy_from_slice(element);
+    }
+    bytes
+}
+
+// Take the EIP-2537 encoding of (x, y), with each element encoded as (c0, c1), and convert to
+// GroupG2 point without a subgroup check.
+pub fn g2_from_eip2537(bytes: &[u8]) -> Result<GroupG2, DecodeError> {
+    if bytes.len() != EIP2537_G2_BYTES {
+        return Err(DecodeError::InvalidLength { expected: EIP2537_G2_BYTES, actual: bytes.len() });
+    }
+    let mut x = [0u8; MODBYTES * 2];
+    let mut y = [0u8; MODBYTES * 2];
+    let elements = bytes.chunks(EIP2537_FP_BYTES).map(eip2537_unpad);
+    let parts = x.chunks_mut(MODBYTES).rev().chain(y.chunks_mut(MODBYTES).rev());
+    for (part, element) in parts.zip(elements) {
+        part.copy_from_slice(&element?);
+    }
+    g2_from_coordinates(&x, &y)
+}
+
+// Remove the padding from an EIP-2537 field element, which must be zero.
+fn eip2537_unpad(bytes: &[u8]) -> Result<[u8; MODBYTES], DecodeError> {
+    if bytes[..EIP2537_PADDING_BYTES].iter().any(|byte| *byte != 0) {
+        return Err(DecodeError::FieldElementTooLarge);
     }
-    deserialize_g2(g2_bytes)
+    let mut element = [0u8; MODBYTES];
+    element.copy_from_slice(&bytes[EIP2537_PADDING_BYTES..]);
+    Ok(element)
+}
+
+// Convert (c1, c0) big-endian bytes to an FP2 element.
+pub(crate) fn fp2_from_bytes(bytes: &[u8]) -> FP2 {
+    FP2::new_bigs(&Big::from_bytes(&bytes[MODBYTES..]), &Big::from_bytes(&bytes[..MODBYTES]))
+}
+
+// Convert an FP2 element to (c1, c0) big-endian bytes.
+pub(crate) fn fp2_to_bytes(element: &FP2, bytes: &mut [u8]) {
+    element.getb().to_bytes(&mut bytes[..MODBYTES]);
+    element.geta().to_bytes(&mut bytes[MODBYTES..]);
+}
+
+// Check that a big-endian field element is less than p, ignoring the flag bits if present.
+pub(crate) fn is_canonical_fp(bytes: &[u8], has_flags: bool) -> bool {
+    let mut element = [0u8; MODBYTES];
+    element.copy_from_slice(bytes);
+    if has_flags {
+        element[0] &= !FLAGS_MASK;
+    }
+    element < MODULUS_BYTES
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use BLSCurve::fp::FP;
+
+    #[test]
+    fn test_byte_lengths() {
+        assert_eq!(SECRET_KEY_BYTES, 32);
+        assert_eq!(PUBLIC_KEY_BYTES, 48);
+        assert_eq!(PUBLIC_KEY_UNCOMPRESSED_BYTES, 96);
+        assert_eq!(SIGNATURE_BYTES, 96);
+        assert_eq!(SIGNATURE_UNCOMPRESSED_BYTES, 192);
+
+        let g1 = GroupG1::generator();
+        let g2 = GroupG2::generator();
+        assert_eq!(compress_g1(&g1).len(), PUBLIC_KEY_BYTES);
+        assert_eq!(serialize_uncompressed_g1(&g1).len(), PUBLIC_KEY_UNCOMPRESSED_BYTES);
+        assert_eq!(compress_g2(&g2).len(), SIGNATURE_BYTES);
+        assert_eq!(serialize_uncompressed_g2(&g2).len(), SIGNATURE_UNCOMPRESSED_BYTES);
+    }
+
+    #[test]
+    fn test_generators() {
+        assert!(GENERATORG1.equals(&GroupG1::generator()));
+        assert!(GENERATORG2.equals(&GroupG2::generator()));
+
+        let mut sum = NEGATIVE_GENERATORG1.clone();
+        sum.add(&GENERATORG1);
+        assert!(sum.is_infinity());
+        let mut sum = NEGATIVE_GENERATORG2.clone();
+        sum.add(&GENERATORG2);
+        assert!(sum.is_infinity());
+    }
+
+    #[test]
+    fn test_subgroup_check_g1() {
+        let r = Big::new_ints(&CURVE_ORDER);
+        assert!(subgroup_check_g1(&GroupG1::new()));
+        assert!(subgroup_check_g1(&GroupG1::generator()));
+
+        for i in 1..20 {
+            // Points on the curve, without clearing the cofactor
+            let mut point = GroupG1::map2point(&FP::new_int(i));
+            assert_eq!(subgroup_check_g1(&point), point.mul(&r).is_infinity());
+            assert!(!subgroup_check_g1(&point));
+
+            point.cfp();
+            assert!(subgroup_check_g1(&point));
+        }
+
+        // The point (0, 2) is on the curve but not in the subgroup
+        let mut bytes = [0u8; G1_BYTES];
+        bytes[0] = 128;
+        assert!(!subgroup_check_g1(&deserialize_g1(&bytes).unwrap()));
+    }
+
+    #[test]
+    fn test_on_curve_check() {
+        assert!(on_curve_check_g1(&GroupG1::new()));
+        assert!(on_curve_check_g1(&GroupG1::generator().mul(&Big::new_int(5))));
+        assert!(on_curve_check_g2(&GroupG2::new()));
+        assert!(on_curve_check_g2(&GroupG2::generator().mul(&Big::new_int(5))));
+
+        // Points outside of the subgroups are on the curves
+        let mut bytes = [0u8; G1_BYTES];
+        bytes[0] = 128;
+        assert!(on_curve_check_g1(&deserialize_g1(&bytes).unwrap()));
+        assert!(on_curve_check_g2(&GroupG2::map2point(&FP2::new_ints(1, 2))));
+    }
+
+    #[test]
+    fn test_subgroup_check_g2() {
+        let r = Big::new_ints(&CURVE_ORDER);
+        assert!(subgroup_check_g2(&GroupG2::new()));
+        assert!(subgroup_check_g2(&GroupG2::generator()));
+
+        for i in 1..20 {
+            // Points on the curve, without clearing the cofactor
+            let mut point = GroupG2::map2point(&FP2::new_ints(i, i + 1));
+            assert_eq!(subgroup_check_g2(&point), point.mul(&r).is_infinity());
+            assert!(!subgroup_check_g2(&point));
+
+            point.cfp();
+            assert!(subgroup_check_g2(&point));
+        }
+
+        // psi^2(P) - t * psi(P) + p * P == 0 for every point on E2, where the trace t = x + 1
+        let point = GroupG2::map2point(&FP2::new_ints(3, 4));
+        let mut lhs = psi(&psi(&point));
+        lhs.add(&point.mul(&Big::new_ints(&MODULUS)));
+        let mut t_psi = psi(&point).mul(&Big::new_ints(&CURVE_BNX));
+        t_psi.neg();
+        t_psi.add(&psi(&point));
+        assert!(lhs.equals(&t_psi));
+
+        // The point (2, y) is on the curve but not in the subgroup
+        let bytes = hex::decode("000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000002172e93db764a8400a7d5071b6b6f5de0da2f0f4a063119abca014006b7c40a2cfe291a1924e65db0d6d0fcfbf3bf3d5c18c6b864ae17dc9da64203ffefb966306425a7bc6aeb7c75247438372716284a4173830420cd476ba1a365b95bfcec38").unwrap();
+        assert!(!subgroup_check_g2(&deserialize_g2(&bytes).unwrap()));
+    }
+
+    #[test]
+    fn test_decompress_not_in_subgroup() {
+        // The point (0, 2) is on the curve but not in the subgroup
+        let mut g1_bytes = [0u8; G1_BYTES];
+        g1_bytes[0] = 128;
+        assert_eq!(decompress_g1(&g1_bytes), Err(DecodeError::NotInSubgroup));
+        assert!(decompress_g1_unchecked(&g1_bytes).is_ok());
+
+        let mut point = GroupG2::map2point(&FP2::new_ints(1, 2));
+        point.affine();
+        let g2_bytes = compress_g2(&point);
+        assert_eq!(decompress_g2(&g2_bytes), Err(DecodeError::NotInSubgroup));
+        assert!(decompress_g2_unchecked(&g2_bytes).unwrap().equals(&point));
+
+        assert_eq!(
+            decompress_g1(&g1_bytes[1..]),
+            Err(DecodeError::InvalidLength { expected: 48, actual: 47 })
+        );
+        assert_eq!(
+            decompress_g2(&g2_bytes[1..]),
+            Err(DecodeError::InvalidLength { expected: 96, actual: 95 })
+        );
+    }
+
+    #[test]
+    fn test_decompress_invalid_flags() {
+        let g1_bytes = compress_g1(&GroupG1::generator());
+
+        // Compression flag unset
+        let mut bytes = g1_bytes;
+        bytes[0] &= !COMPRESSION_FLAG;
+        assert_eq!(decompress_g1(&bytes), Err(DecodeError::InvalidFlag(EncodingFlag::Compression)));
+
+        // Infinity with non-zero bits
+        let mut bytes = [0u8; G1_BYTES];
+        bytes[0] = COMPRESSION_FLAG | INFINITY_FLAG;
+        assert!(decompress_g1(&bytes).unwrap().is_infinity());
+        bytes[G1_BYTES - 1] = 1;
+        assert_eq!(decompress_g1(&bytes), Err(DecodeError::InvalidFlag(EncodingFlag::Infinity)));
+
+        // Infinity with the sort flag
+        let mut bytes = [0u8; G2_BYTES];
+        bytes[0] = COMPRESSION_FLAG | INFINITY_FLAG | SORT_FLAG;
+        assert_eq!(decompress_g2(&bytes), Err(DecodeError::InvalidFlag(EncodingFlag::Sort)));
+
+        // Uncompressed with the sort flag
+        let mut bytes = serialize_uncompressed_g1(&GroupG1::generator());
+        assert!(deserialize_uncompressed_g1(&bytes).is_ok());
+        bytes[0] |= SORT_FLAG;
+        assert_eq!(
+            deserialize_uncompressed_g1(&bytes),
+            Err(DecodeError::InvalidFlag(EncodingFlag::Sort))
+        );
+    }
+
+    #[test]
+    fn test_uncompressed_round_trip() {
+        let point = hash_to_curve_g2(b"uncompressed");
+        let bytes = serialize_uncompressed_g2(&point);
+        assert!(deserialize_uncompressed_g2(&bytes).unwrap().equals(&point));
+
+        let infinity = serialize_uncompressed_g2(&GroupG2::new());
+        assert!(deserialize_uncompressed_g2(&infinity).unwrap().is_infinity());
+
+        // Compressed bytes are rejected
+        let compressed = compress_g2(&point);
+        assert_eq!(
+            deserialize_uncompressed_g2(&compressed),
+            Err(DecodeError::InvalidLength { expected: 192, actual: 96 })
+        );
+
+        // y0 = p
+        let mut non_canonical = bytes;
+        non_canonical[3 * MODBYTES..].copy_from_slice(&MODULUS_BYTES);
+        assert_eq!(
+            deserialize_uncompressed_g2(&non_canonical),
+            Err(DecodeError::FieldElementTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_coordinates_round_trip() {
+        let point = hash_to_curve_g1_with_dst(
+            b"coordinates",
+            b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_",
+        );
+        let (x, y) = g1_to_coordinates(&point);
+        assert!(g1_from_coordinates(&x, &y).unwrap().equals(&point));
+        assert_eq!(&serialize_uncompressed_g1(&point)[..MODBYTES], &x[..]);
+        assert_eq!(&serialize_uncompressed_g1(&point)[MODBYTES..], &y[..]);
+
+        let point = hash_to_curve_g2(b"coordinates");
+        let (x, y) = g2_to_coordinates(&point);
+        assert!(g2_from_coordinates(&x, &y).unwrap().equals(&point));
+        assert_eq!(&serialize_uncompressed_g2(&point)[..MODBYTES * 2], &x[..]);
+        assert_eq!(&serialize_uncompressed_g2(&point)[MODBYTES * 2..], &y[..]);
+
+        // Infinity is (0, 0)
+        let (x, y) = g1_to_coordinates(&GroupG1::new());
+        assert!(g1_from_coordinates(&x, &y).unwrap().is_infinity());
+        let (x, y) = g2_to_coordinates(&GroupG2::new());
+        assert!(g2_from_coordinates(&x, &y).unwrap().is_infinity());
+    }
+
+    #[test]
+    fn test_coordinates_invalid() {
+        // (1, 1) is not on the curve
+        let mut one = [0u8; MODBYTES];
+        one[MODBYTES - 1] = 1;
+        assert_eq!(g1_from_coordinates(&one, &one), Err(DecodeError::NotOnCurve));
+
+        // x = p
+        let (_, y) = g1_to_coordinates(&GroupG1::generator());
+        assert_eq!(g1_from_coordinates(&MODULUS_BYTES, &y), Err(DecodeError::FieldElementTooLarge));
+
+        // y0 = p
+        let (x, mut y) = g2_to_coordinates(&GroupG2::generator());
+        y[MODBYTES..].copy_from_slice(&MODULUS_BYTES);
+        assert_eq!(g2_from_coordinates(&x, &y), Err(DecodeError::FieldElementTooLarge));
+    }
+
+    #[test]
+    fn test_eip2537() {
+        // Generators from https://eips.ethereum.org/EIPS/eip-2537#curve-parameters
+        let g1 = g1_to_eip2537(&GroupG1::generator());
+        assert_eq!(hex::encode(&g1[..]), "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1");
+        assert!(g1_from_eip2537(&g1).unwrap().equals(&GroupG1::generator()));
+
+        let g2 = g2_to_eip2537(&GroupG2::generator());
+        assert_eq!(hex::encode(&g2[..]), "00000000000000000000000000000000024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80000000000000000000000000000000013e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e000000000000000000000000000000000ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801000000000000000000000000000000000606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be");
+        assert!(g2_from_eip2537(&g2).unwrap().equals(&GroupG2::generator()));
+
+        // Infinity is all zeros
+        assert!(g1_to_eip2537(&GroupG1::new()).iter().all(|byte| *byte == 0));
+        assert!(g1_from_eip2537(&[0u8; EIP2537_G1_BYTES]).unwrap().is_infinity());
+        assert!(g2_to_eip2537(&GroupG2::new()).iter().all(|byte| *byte == 0));
+        assert!(g2_from_eip2537(&[0u8; EIP2537_G2_BYTES]).unwrap().is_infinity());
+    }
+
+    #[test]
+    fn test_eip2537_invalid() {
+        let mut g1 = g1_to_eip2537(&GroupG1::generator());
+        assert_eq!(
+            g1_from_eip2537(&g1[1..]),
+            Err(DecodeError::InvalidLength { expected: 128, actual: 127 })
+        );
+
+        // Non-zero padding
+        g1[EIP2537_FP_BYTES] = 1;
+        assert_eq!(g1_from_eip2537(&g1), Err(DecodeError::FieldElementTooLarge));
+        let mut g2 = g2_to_eip2537(&GroupG2::generator());
+        g2[EIP2537_FP_BYTES * 3 + EIP2537_PADDING_BYTES - 1] = 1;
+        assert_eq!(g2_from_eip2537(&g2), Err(DecodeError::FieldElementTooLarge));
+
+        // Swapping c0 and c1 is not on the curve
+        let g2 = g2_to_eip2537(&GroupG2::generator());
+        let mut swapped = [0u8; EIP2537_G2_BYTES];
+        for (i, chunk) in swapped.chunks_mut(EIP2537_FP_BYTES).enumerate() {
+            chunk
+                .copy_from_slice(&g2[(i ^ 1) * EIP2537_FP_BYTES..((i ^ 1) + 1) * EIP2537_FP_BYTES]);
+        }
+        assert_eq!(g2_from_eip2537(&swapped), Err(DecodeError::NotOnCurve));
+    }
+
+    #[test]
+    fn test_decompress_not_on_curve() {
+        // x = 3 has no y on the curve
+        let mut bytes = [0u8; G1_BYTES];
+        bytes[0] = COMPRESSION_FLAG;
+        bytes[G1_BYTES - 1] = 3;
+        assert_eq!(decompress_g1(&bytes), Err(DecodeError::NotOnCurve));
+    }
+
+    #[test]
+    fn test_decompress_non_canonical() {
+        // x = p, with the compression flag set
+        let mut g1_bytes = MODULUS_BYTES;
+        g1_bytes[0] |= 128;
+        assert_eq!(decompress_g1(&g1_bytes), Err(DecodeError::FieldElementTooLarge));
+        assert_eq!(decompress_g1_unchecked(&g1_bytes), Err(DecodeError::FieldElementTooLarge));
+
+        // x = p + 1, a non-canonical encoding of x = 1
+        g1_bytes[MODBYTES - 1] += 1;
+        assert_eq!(decompress_g1(&g1_bytes), Err(DecodeError::FieldElementTooLarge));
+
+        // x1 = p or x0 = p
+        let g2_bytes = compress_g2(&GroupG2::generator());
+        let mut x1_bytes = g2_bytes;
+        x1_bytes[..MODBYTES].copy_from_slice(&MODULUS_BYTES);
+        x1_bytes[0] |= g2_bytes[0] & FLAGS_MASK;
+        assert_eq!(decompress_g2(&x1_bytes), Err(DecodeError::FieldElementTooLarge));
+        let mut x0_bytes = g2_bytes;
+        x0_bytes[MODBYTES..].copy_from_slice(&MODULUS_BYTES);
+        assert_eq!(decompress_g2(&x0_bytes), Err(DecodeError::FieldElementTooLarge));
+    }
 
     #[test]
     fn compression_decompression_g1_round_trip() {
         // Input 1
         let compressed = hex::decode("b53d21a4cfd562c469cc81514d4ce5a6b577d8403d32a394dc265dd190b47fa9f829fdd7963afdf972e5e77854051f6f").unwrap();
-        let mut decompressed = decompress_g1(&compressed).unwrap();
-        let compressed_result = compress_g1(&mut decompressed).to_vec();
+        let decompressed = decompress_g1(&compressed).unwrap();
+        let compressed_result = compress_g1(&decompressed).to_vec();
         assert_eq!(compressed, compressed_result);
 
         // Input 2
         let compressed = hex::decode("b301803f8b5ac4a1133581fc676dfedc60d891dd5fa99028805e5ea5b08d3491af75d0707adab3b70c6a6a580217bf81").unwrap();
-        let mut decompressed = decompress_g1(&compressed).unwrap();
-        let compressed_result = compress_g1(&mut decompressed).to_vec();
+        let decompressed = decompress_g1(&compressed).unwrap();
+        let compressed_result = compress_g1(&decompressed).to_vec();
         assert_eq!(compressed, compressed_result);
 
         // Input 3
         let compressed = hex::decode("a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a").unwrap();
-        let mut decompressed = decompress_g1(&compressed).unwrap();
-        let compressed_result = compress_g1(&mut decompressed).to_vec();
+        let decompressed = decompress_g1(&compressed).unwrap();
+        let compressed_result = compress_g1(&decompressed).to_vec();
         assert_eq!(compressed, compressed_result);
     }
 
     #[test]
     fn test_to_from_infinity_g1() {
-        let mut point = GroupG1::new();
-        let compressed = compress_g1(&mut point);
+        let point = GroupG1::new();
+        let compressed = compress_g1(&point);
         let round_trip_point = decompress_g1(&compressed).unwrap();
         assert_eq!(point.to_string(), round_trip_point.to_string());
     }
 
     #[test]
     fn test_to_from_infinity_g2() {
-        let mut point = GroupG2::new();
-        let compressed = compress_g2(&mut point);
+        let point = GroupG2::new();
+        let compressed = compress_g2(&point);
         let round_trip_point = decompress_g2(&compressed).unwrap();
         assert_eq!(point.to_string(), round_trip_point.to_string());
     }
@@ -121,8 +786,8 @@ mod tests {
         let mut compressed_b = hex::decode("0def2d4be359640e6dae6438119cbdc4f18e5e4496c68a979473a72b72d3badf98464412e9d8f8d2ea9b31953bb24899").unwrap();
         compressed_a.append(&mut compressed_b);
 
-        let mut decompressed = decompress_g2(&compressed_a).unwrap();
-        let compressed_result = compress_g2(&mut decompressed).to_vec();
+        let decompressed = decompress_g2(&compressed_a).unwrap();
+        let compressed_result = compress_g2(&decompressed).to_vec();
         assert_eq!(compressed_a, compressed_result);
 
         // Input 2
@@ -130,8 +795,8 @@ mod tests {
         let mut compressed_b = hex::decode("1181e97fac61e371a22f34a4622f7e343ca0d99846b175a92ad1bf1df6fd4d0800e4edb7c2eb3d8437ed10cbc2d88823").unwrap();
         compressed_a.append(&mut compressed_b);
 
-        let mut decompressed = decompress_g2(&compressed_a).unwrap();
-        let compressed_result = compress_g2(&mut decompressed).to_vec();
+        let decompressed = decompress_g2(&compressed_a).unwrap();
+        let compressed_result = compress_g2(&decompressed).to_vec();
         assert_eq!(compressed_a, compressed_result);
 
         // Input 3
@@ -139,8 +804,19 @@ mod tests {
         let mut compressed_b = hex::decode("18ca20f0b66678c0230e65eb4ebb3d621940984f71eb5481453e4489dafcc7f6ee2c863b76671467002a8f2392063005").unwrap();
         compressed_a.append(&mut compressed_b);
 
-        let mut decompressed = decompress_g2(&compressed_a).unwrap();
-        let compressed_result = compress_g2(&mut decompressed).to_vec();
+        let decompressed = decompress_g2(&compressed_a).unwrap();
+        let compressed_result = compress_g2(&decompressed).to_vec();
         assert_eq!(compressed_a, compressed_result);
     }
+
+    #[test]
+    fn test_empty_encodings() {
+        assert!(check_flags(&[], true).is_err());
+        assert!(decompress_g1(&[]).is_err());
+        assert!(decompress_g2(&[]).is_err());
+        assert!(deserialize_uncompressed_g1(&[]).is_err());
+        assert!(deserialize_uncompressed_g2(&[]).is_err());
+        assert!(g1_from_eip2537(&[]).is_err());
+        assert!(g2_from_eip2537(&[]).is_err());
+    }
 }
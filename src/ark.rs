@@ -0,0 +1,234 @@
+//! Conversions to and from the arkworks `ark_bls12_381` types.
+//!
+//! Points are converted using their affine coordinates. Arkworks points may be constructed
+//! without validation, so conversions into this crate check the point is on the curve, and
+//! PublicKeys and Signatures are additionally checked to be in the prime order subgroup.
+
+extern crate zeroize;
+
+use self::zeroize::Zeroize;
+use super::aggregates::{AggregatePublicKey, AggregateSignature};
+use super::amcl_utils::{
+    g1_from_coordinates, g1_to_coordinates, g2_from_coordinates, g2_to_coordinates, GroupG1,
+    GroupG2, MODBYTES,
+};
+use super::errors::DecodeError;
+use super::keys::{PublicKey, SecretKey};
+use super::signature::Signature;
+use ark_bls12_381::{Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField};
+#[cfg(not(feature = "std"))]
+use core::convert::TryFrom;
+#[cfg(feature = "std")]
+use std::convert::TryFrom;
+
+/// Convert a G1 point to an arkworks G1Affine.
+pub fn g1_to_ark(point: &GroupG1) -> G1Affine {
+    if point.is_infinity() {
+        return G1Affine::identity();
+    }
+    let (x, y) = g1_to_coordinates(point);
+    G1Affine::new_unchecked(Fq::from_be_bytes_mod_order(&x), Fq::from_be_bytes_mod_order(&y))
+}
+
+/// Convert an arkworks G1Affine to a G1 point, without a subgroup check.
+pub fn g1_from_ark(point: &G1Affine) -> Result<GroupG1, DecodeError> {
+    if point.is_zero() {
+        return Ok(GroupG1::new());
+    }
+    let result = g1_from_coordinates(&fq_to_bytes(&point.x), &fq_to_bytes(&point.y))?;
+    // (0, 0) is not on the curve, but is the encoding of infinity for coordinates
+    if result.is_infinity() {
+        return Err(DecodeError::NotOnCurve);
+    }
+    Ok(result)
+}
+
+/// Convert a G2 point to an arkworks G2Affine.
+pub fn g2_to_ark(point: &GroupG2) -> G2Affine {
+    if point.is_infinity() {
+        return G2Affine::identity();
+    }
+    let (x, y) = g2_to_coordinates(point);
+    G2Affine::new_unchecked(fq2_from_bytes(&x), fq2_from_bytes(&y))
+}
+
+/// Convert an arkworks G2Affine to a G2 point, without a subgroup check.
+pub fn g2_from_ark(point: &G2Affine) -> Result<GroupG2, DecodeError> {
+    if point.is_zero() {
+        return Ok(GroupG2::new());
+    }
+    let result = g2_from_coordinates(&fq2_to_bytes(&point.x), &fq2_to_bytes(&point.y))?;
+    if result.is_infinity() {
+        return Err(DecodeError::NotOnCurve);
+    }
+    Ok(result)
+}
+
+fn fq_to_bytes(element: &Fq) -> [u8; MODBYTES] {
+    let mut bytes = [0u8; MODBYTES];
+    bytes.copy_from_slice(&element.into_bigint().to_bytes_be());
+    bytes
+}
+
+// Fq2 elements are encoded as (c1, c0), as for coordinates.
+fn fq2_to_bytes(element: &Fq2) -> [u8; MODBYTES * 2] {
+    let mut bytes = [0u8; MODBYTES * 2];
+    bytes[..MODBYTES].copy_from_slice(&fq_to_bytes(&element.c1));
+    bytes[MODBYTES..].copy_from_slice(&fq_to_bytes(&element.c0));
+    bytes
+}
+
+fn fq2_from_bytes(bytes: &[u8; MODBYTES * 2]) -> Fq2 {
+    Fq2::new(
+        Fq::from_be_bytes_mod_order(&bytes[MODBYTES..]),
+        Fq::from_be_bytes_mod_order(&bytes[..MODBYTES]),
+    )
+}
+
+impl<'a> From<&'a PublicKey> for G1Affine {
+    fn from(public_key: &'a PublicKey) -> Self {
+        g1_to_ark(&public_key.point)
+    }
+}
+
+/// The key is validated as in `PublicKey::from_bytes()`.
+impl TryFrom<G1Affine> for PublicKey {
+    type Error = DecodeError;
+
+    fn try_from(point: G1Affine) -> Result<Self, Self::Error> {
+        let public_key = PublicKey { point: g1_from_ark(&point)? };
+        if public_key.point.is_infinity() {
+            return Err(DecodeError::Infinity);
+        }
+        if !public_key.is_in_subgroup() {
+            return Err(DecodeError::NotInSubgroup);
+        }
+        Ok(public_key)
+    }
+}
+
+impl<'a> From<&'a AggregatePublicKey> for G1Affine {
+    fn from(aggregate_public_key: &'a AggregatePublicKey) -> Self {
+        g1_to_ark(&aggregate_public_key.point)
+    }
+}
+
+impl<'a> From<&'a Signature> for G2Affine {
+    fn from(signature: &'a Signature) -> Self {
+        g2_to_ark(&signature.point)
+    }
+}
+
+/// The Signature is validated as in `Signature::from_bytes()`.
+impl TryFrom<G2Affine> for Signature {
+    type Error = DecodeError;
+
+    fn try_from(point: G2Affine) -> Result<Self, Self::Error> {
+        let signature = Signature { point: g2_from_ark(&point)? };
+        if !signature.is_in_subgroup() {
+            return Err(DecodeError::NotInSubgroup);
+        }
+        Ok(signature)
+    }
+}
+
+impl<'a> From<&'a AggregateSignature> for G2Affine {
+    fn from(aggregate_signature: &'a AggregateSignature) -> Self {
+        g2_to_ark(&aggregate_signature.point)
+    }
+}
+
+/// The AggregateSignature is validated as in `AggregateSignature::from_bytes()`.
+impl TryFrom<G2Affine> for AggregateSignature {
+    type Error = DecodeError;
+
+    fn try_from(point: G2Affine) -> Result<Self, Self::Error> {
+        let signature = Signature::try_from(point)?;
+        Ok(AggregateSignature::from_signature(&signature))
+    }
+}
+
+impl<'a> From<&'a SecretKey> for Fr {
+    fn from(secret_key: &'a SecretKey) -> Self {
+        let mut bytes = secret_key.as_bytes();
+        let scalar = Fr::from_be_bytes_mod_order(&bytes);
+        bytes.zeroize();
+        scalar
+    }
+}
+
+/// The zero scalar is rejected, as in `SecretKey::from_bytes()`.
+impl TryFrom<Fr> for SecretKey {
+    type Error = DecodeError;
+
+    fn try_from(scalar: Fr) -> Result<Self, Self::Error> {
+        let mut bytes = scalar.into_bigint().to_bytes_be();
+        let secret_key = SecretKey::from_bytes(&bytes);
+        bytes.zeroize();
+        secret_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::keys::Keypair;
+    use super::*;
+
+    #[test]
+    fn test_ark_generators() {
+        assert_eq!(g1_to_ark(&GroupG1::generator()), G1Affine::generator());
+        assert_eq!(g1_from_ark(&G1Affine::generator()).unwrap(), GroupG1::generator());
+        assert_eq!(g2_to_ark(&GroupG2::generator()), G2Affine::generator());
+        assert_eq!(g2_from_ark(&G2Affine::generator()).unwrap(), GroupG2::generator());
+
+        // SecretKey of one is the scalar one
+        let mut bytes = [0u8; 32];
+        bytes[31] = 1;
+        let secret_key = SecretKey::from_bytes(&bytes).unwrap();
+        assert_eq!(Fr::from(&secret_key), Fr::from(1u64));
+        assert_eq!(SecretKey::try_from(Fr::from(1u64)).unwrap(), secret_key);
+    }
+
+    #[test]
+    fn test_ark_round_trip() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signature = Signature::new(&[42u8; 32], &keypair.sk);
+        let aggregate_signature = AggregateSignature::from_signature(&signature);
+
+        let point = G1Affine::from(&keypair.pk);
+        assert_eq!(PublicKey::try_from(point).unwrap(), keypair.pk);
+        let aggregate_public_key = AggregatePublicKey::from_public_key(&keypair.pk);
+        assert_eq!(G1Affine::from(&aggregate_public_key), point);
+
+        let point = G2Affine::from(&signature);
+        assert_eq!(Signature::try_from(point).unwrap(), signature);
+        assert_eq!(G2Affine::from(&aggregate_signature), point);
+        assert_eq!(AggregateSignature::try_from(point).unwrap(), aggregate_signature);
+
+        let scalar = Fr::from(&keypair.sk);
+        assert_eq!(SecretKey::try_from(scalar).unwrap(), keypair.sk);
+    }
+
+    #[test]
+    fn test_ark_invalid() {
+        // Identity
+        assert_eq!(PublicKey::try_from(G1Affine::identity()), Err(DecodeError::Infinity));
+        assert!(Signature::try_from(G2Affine::identity()).unwrap().point.is_infinity());
+        assert_eq!(SecretKey::try_from(Fr::from(0u64)), Err(DecodeError::InvalidSecretKey));
+
+        // Not on the curve
+        let point = G1Affine::new_unchecked(Fq::from(1u64), Fq::from(1u64));
+        assert_eq!(PublicKey::try_from(point), Err(DecodeError::NotOnCurve));
+        let point = G1Affine::new_unchecked(Fq::from(0u64), Fq::from(0u64));
+        assert_eq!(g1_from_ark(&point), Err(DecodeError::NotOnCurve));
+
+        // On the curve but not in the subgroup, (0, 2) satisfies y^2 = x^3 + 4
+        let point = G1Affine::new_unchecked(Fq::from(0u64), Fq::from(2u64));
+        assert!(g1_from_ark(&point).is_ok());
+        assert_eq!(PublicKey::try_from(point), Err(DecodeError::NotInSubgroup));
+    }
+}
@@ -0,0 +1,372 @@
+//! A standalone trait for the curve operations used by BLS signatures, with two implementations.
+//!
+//! `Amcl` is always available. `Blst` uses the assembly optimised blst library and is enabled by
+//! the `blst-backend` feature.
+//!
+//! The trait is not used by the rest of the crate: PublicKeys, Signatures, aggregation and
+//! verification always use amcl, whichever implementation is enabled. It is for code generic
+//! over the curve operations, e.g. to cross-check or benchmark the implementations.
+
+use super::amcl_utils::hash_to_curve_g2_with_dst;
+use super::errors::DecodeError;
+use super::points::{multi_miller_loop, G1Point, G2Point, Scalar};
+use super::{G1_BYTES, G2_BYTES};
+
+/// Point arithmetic, pairing and hashing to the curve over BLS12-381.
+///
+/// Encodings are the compressed encodings used by PublicKeys and Signatures, points are in the
+/// prime order subgroups.
+pub trait Backend {
+    /// A point in G1.
+    type G1: Clone;
+    /// A point in G2.
+    type G2: Clone;
+
+    /// The fixed generator of G1.
+    fn g1_generator() -> Self::G1;
+    /// The sum of two G1 points.
+    fn g1_add(a: &Self::G1, b: &Self::G1) -> Self::G1;
+    /// The negation of a G1 point.
+    fn g1_neg(a: &Self::G1) -> Self::G1;
+    /// A G1 point multiplied by a Scalar.
+    fn g1_mul(a: &Self::G1, scalar: &Scalar) -> Self::G1;
+    /// Decode a G1 point from compressed bytes, checking it is in the subgroup.
+    fn g1_from_bytes(bytes: &[u8]) -> Result<Self::G1, DecodeError>;
+    /// Encode a G1 point as compressed bytes.
+    fn g1_to_bytes(a: &Self::G1) -> [u8; G1_BYTES];
+
+    /// The fixed generator of G2.
+    fn g2_generator() -> Self::G2;
+    /// The sum of two G2 points.
+    fn g2_add(a: &Self::G2, b: &Self::G2) -> Self::G2;
+    /// The negation of a G2 point.
+    fn g2_neg(a: &Self::G2) -> Self::G2;
+    /// A G2 point multiplied by a Scalar.
+    fn g2_mul(a: &Self::G2, scalar: &Scalar) -> Self::G2;
+    /// Decode a G2 point from compressed bytes, checking it is in the subgroup.
+    fn g2_from_bytes(bytes: &[u8]) -> Result<Self::G2, DecodeError>;
+    /// Encode a G2 point as compressed bytes.
+    fn g2_to_bytes(a: &Self::G2) -> [u8; G2_BYTES];
+
+    /// hash_to_curve with the SSWU random oracle suite using SHA-256 and the given DST.
+    fn hash_to_g2(msg: &[u8], dst: &[u8]) -> Self::G2;
+
+    /// Returns true if the product of the pairings e(P, Q) of each pair is one.
+    fn pairing_check(pairs: &[(&Self::G1, &Self::G2)]) -> bool;
+}
+
+/// The amcl implementation used throughout this crate.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Amcl;
+
+impl Backend for Amcl {
+    type G1 = G1Point;
+    type G2 = G2Point;
+
+    fn g1_generator() -> G1Point {
+        G1Point::generator()
+    }
+
+    fn g1_add(a: &G1Point, b: &G1Point) -> G1Point {
+        a.add(b)
+    }
+
+    fn g1_neg(a: &G1Point) -> G1Point {
+        a.neg()
+    }
+
+    fn g1_mul(a: &G1Point, scalar: &Scalar) -> G1Point {
+        a.mul(scalar)
+    }
+
+    fn g1_from_bytes(bytes: &[u8]) -> Result<G1Point, DecodeError> {
+        G1Point::from_bytes(bytes)
+    }
+
+    fn g1_to_bytes(a: &G1Point) -> [u8; G1_BYTES] {
+        a.as_bytes()
+    }
+
+    fn g2_generator() -> G2Point {
+        G2Point::generator()
+    }
+
+    fn g2_add(a: &G2Point, b: &G2Point) -> G2Point {
+        a.add(b)
+    }
+
+    fn g2_neg(a: &G2Point) -> G2Point {
+        a.neg()
+    }
+
+    fn g2_mul(a: &G2Point, scalar: &Scalar) -> G2Point {
+        a.mul(scalar)
+    }
+
+    fn g2_from_bytes(bytes: &[u8]) -> Result<G2Point, DecodeError> {
+        G2Point::from_bytes(bytes)
+    }
+
+    fn g2_to_bytes(a: &G2Point) -> [u8; G2_BYTES] {
+        a.as_bytes()
+    }
+
+    fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2Point {
+        let mut point = hash_to_curve_g2_with_dst(msg, dst);
+        point.affine();
+        G2Point(point)
+    }
+
+    fn pairing_check(pairs: &[(&G1Point, &G2Point)]) -> bool {
+        multi_miller_loop(pairs).final_exponentiation().is_identity()
+    }
+}
+
+#[cfg(feature = "blst-backend")]
+pub use self::blst_backend::Blst;
+
+#[cfg(feature = "blst-backend")]
+mod blst_backend {
+    use super::super::amcl_utils::check_flags;
+    use super::super::errors::DecodeError;
+    use super::super::points::Scalar;
+    use super::super::{G1_BYTES, G2_BYTES};
+    use super::Backend;
+    use blst::*;
+
+    // Bits in the curve order r.
+    const SCALAR_BITS: usize = 255;
+
+    /// The blst implementation, enabled by the `blst-backend` feature.
+    #[derive(Clone, Copy)]
+    #[cfg_attr(feature = "std", derive(Debug))]
+    pub struct Blst;
+
+    fn to_blst_scalar(scalar: &Scalar) -> blst_scalar {
+        let mut out = blst_scalar::default();
+        // Safety: the input is 32 bytes as required.
+        unsafe { blst_scalar_from_bendian(&mut out, scalar.as_bytes().as_ptr()) };
+        out
+    }
+
+    // Maps blst decoding errors to those of this crate, after lengths and flags are checked.
+    fn decode_error(error: BLST_ERROR) -> DecodeError {
+        match error {
+            BLST_ERROR::BLST_POINT_NOT_IN_GROUP => DecodeError::NotInSubgroup,
+            BLST_ERROR::BLST_BAD_ENCODING => DecodeError::FieldElementTooLarge,
+            _ => DecodeError::NotOnCurve,
+        }
+    }
+
+    // All pointers passed to blst below are to initialised values or arrays of the lengths blst
+    // reads and writes.
+    impl Backend for Blst {
+        type G1 = blst_p1;
+        type G2 = blst_p2;
+
+        fn g1_generator() -> blst_p1 {
+            unsafe { *blst_p1_generator() }
+        }
+
+        fn g1_add(a: &blst_p1, b: &blst_p1) -> blst_p1 {
+            let mut out = blst_p1::default();
+            unsafe { blst_p1_add_or_double(&mut out, a, b) };
+            out
+        }
+
+        fn g1_neg(a: &blst_p1) -> blst_p1 {
+            let mut out = *a;
+            unsafe { blst_p1_cneg(&mut out, true) };
+            out
+        }
+
+        fn g1_mul(a: &blst_p1, scalar: &Scalar) -> blst_p1 {
+            let scalar = to_blst_scalar(scalar);
+            let mut out = blst_p1::default();
+            unsafe { blst_p1_mult(&mut out, a, scalar.b.as_ptr(), SCALAR_BITS) };
+            out
+        }
+
+        fn g1_from_bytes(bytes: &[u8]) -> Result<blst_p1, DecodeError> {
+            if bytes.len() != G1_BYTES {
+                return Err(DecodeError::InvalidLength { expected: G1_BYTES, actual: bytes.len() });
+            }
+            check_flags(bytes, true)?;
+
+            let mut affine = blst_p1_affine::default();
+            match unsafe { blst_p1_uncompress(&mut affine, bytes.as_ptr()) } {
+                BLST_ERROR::BLST_SUCCESS => {}
+                error => return Err(decode_error(error)),
+            }
+            if !unsafe { blst_p1_affine_in_g1(&affine) } {
+                return Err(DecodeError::NotInSubgroup);
+            }
+            let mut out = blst_p1::default();
+            unsafe { blst_p1_from_affine(&mut out, &affine) };
+            Ok(out)
+        }
+
+        fn g1_to_bytes(a: &blst_p1) -> [u8; G1_BYTES] {
+            let mut out = [0u8; G1_BYTES];
+            unsafe { blst_p1_compress(out.as_mut_ptr(), a) };
+            out
+        }
+
+        fn g2_generator() -> blst_p2 {
+            unsafe { *blst_p2_generator() }
+        }
+
+        fn g2_add(a: &blst_p2, b: &blst_p2) -> blst_p2 {
+            let mut out = blst_p2::default();
+            unsafe { blst_p2_add_or_double(&mut out, a, b) };
+            out
+        }
+
+        fn g2_neg(a: &blst_p2) -> blst_p2 {
+            let mut out = *a;
+            unsafe { blst_p2_cneg(&mut out, true) };
+            out
+        }
+
+        fn g2_mul(a: &blst_p2, scalar: &Scalar) -> blst_p2 {
+            let scalar = to_blst_scalar(scalar);
+            let mut out = blst_p2::default();
+            unsafe { blst_p2_mult(&mut out, a, scalar.b.as_ptr(), SCALAR_BITS) };
+            out
+        }
+
+        fn g2_from_bytes(bytes: &[u8]) -> Result<blst_p2, DecodeError> {
+            if bytes.len() != G2_BYTES {
+                return Err(DecodeError::InvalidLength { expected: G2_BYTES, actual: bytes.len() });
+            }
+            check_flags(bytes, true)?;
+
+            let mut affine = blst_p2_affine::default();
+            match unsafe { blst_p2_uncompress(&mut affine, bytes.as_ptr()) } {
+                BLST_ERROR::BLST_SUCCESS => {}
+                error => return Err(decode_error(error)),
+            }
+            if !unsafe { blst_p2_affine_in_g2(&affine) } {
+                return Err(DecodeError::NotInSubgroup);
+            }
+            let mut out = blst_p2::default();
+            unsafe { blst_p2_from_affine(&mut out, &affine) };
+            Ok(out)
+        }
+
+        fn g2_to_bytes(a: &blst_p2) -> [u8; G2_BYTES] {
+            let mut out = [0u8; G2_BYTES];
+            unsafe { blst_p2_compress(out.as_mut_ptr(), a) };
+            out
+        }
+
+        fn hash_to_g2(msg: &[u8], dst: &[u8]) -> blst_p2 {
+            let mut out = blst_p2::default();
+            unsafe {
+                blst_hash_to_g2(
+                    &mut out,
+                    msg.as_ptr(),
+                    msg.len(),
+                    dst.as_ptr(),
+                    dst.len(),
+                    core::ptr::null(),
+                    0,
+                )
+            };
+            out
+        }
+
+        fn pairing_check(pairs: &[(&blst_p1, &blst_p2)]) -> bool {
+            let mut product = unsafe { *blst_fp12_one() };
+            for (p, q) in pairs {
+                // e(P, Q) is one if either point is the identity
+                if unsafe { blst_p1_is_inf(*p) || blst_p2_is_inf(*q) } {
+                    continue;
+                }
+                let mut p_affine = blst_p1_affine::default();
+                let mut q_affine = blst_p2_affine::default();
+                let mut miller_loop = blst_fp12::default();
+                unsafe {
+                    blst_p1_to_affine(&mut p_affine, *p);
+                    blst_p2_to_affine(&mut q_affine, *q);
+                    blst_miller_loop(&mut miller_loop, &q_affine, &p_affine);
+                }
+                let mut next = blst_fp12::default();
+                unsafe { blst_fp12_mul(&mut next, &product, &miller_loop) };
+                product = next;
+            }
+            let mut result = blst_fp12::default();
+            unsafe {
+                blst_final_exp(&mut result, &product);
+                blst_fp12_is_one(&result)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::amcl_utils::{compress_g2, hash_to_curve_g2, DST_G2};
+    use super::*;
+
+    // Checks the operations of a Backend against each other and the encodings of this crate.
+    fn check_backend<B: Backend>() {
+        let a = Scalar::from_u64(1234);
+        let b = Scalar::from_u64(5678);
+        let g1 = B::g1_generator();
+        let g2 = B::g2_generator();
+        let p = B::g1_mul(&g1, &a);
+        let q = B::g2_mul(&g2, &b);
+
+        // Encodings match this crate and round trip
+        assert_eq!(B::g1_to_bytes(&p), G1Point::generator().mul(&a).as_bytes());
+        assert_eq!(B::g2_to_bytes(&q), G2Point::generator().mul(&b).as_bytes());
+        assert_eq!(
+            B::g1_to_bytes(&B::g1_from_bytes(&B::g1_to_bytes(&p)).unwrap()),
+            B::g1_to_bytes(&p)
+        );
+        assert_eq!(
+            B::g2_to_bytes(&B::g2_from_bytes(&B::g2_to_bytes(&q)).unwrap()),
+            B::g2_to_bytes(&q)
+        );
+        assert_eq!(
+            B::g1_from_bytes(&[0u8; G1_BYTES - 1]).err(),
+            Some(DecodeError::InvalidLength { expected: G1_BYTES, actual: G1_BYTES - 1 })
+        );
+
+        // Arithmetic
+        let two = Scalar::from_u64(2);
+        assert_eq!(B::g1_to_bytes(&B::g1_add(&p, &p)), B::g1_to_bytes(&B::g1_mul(&p, &two)));
+        assert_eq!(B::g2_to_bytes(&B::g2_add(&q, &q)), B::g2_to_bytes(&B::g2_mul(&q, &two)));
+        let identity = B::g1_add(&p, &B::g1_neg(&p));
+        assert_eq!(B::g1_to_bytes(&identity), G1Point::identity().as_bytes());
+        let identity = B::g2_add(&q, &B::g2_neg(&q));
+        assert_eq!(B::g2_to_bytes(&identity), G2Point::identity().as_bytes());
+
+        // Bilinearity, e(aG1, bG2) * e(-abG1, G2) == 1
+        let ab = B::g1_mul(&g1, &a.mul(&b));
+        assert!(B::pairing_check(&[(&p, &q), (&B::g1_neg(&ab), &g2)]));
+        assert!(!B::pairing_check(&[(&p, &q), (&B::g1_neg(&p), &g2)]));
+        assert!(B::pairing_check(&[]));
+
+        // Sign and verify, e(pk, H(m)) * e(-G1, sk * H(m)) == 1
+        let hash = B::hash_to_g2(b"message", DST_G2);
+        assert_eq!(B::g2_to_bytes(&hash), compress_g2(&hash_to_curve_g2(b"message")));
+        let signature = B::g2_mul(&hash, &a);
+        assert!(B::pairing_check(&[(&p, &hash), (&B::g1_neg(&g1), &signature)]));
+        let other = B::hash_to_g2(b"other message", DST_G2);
+        assert!(!B::pairing_check(&[(&p, &other), (&B::g1_neg(&g1), &signature)]));
+    }
+
+    #[test]
+    fn test_amcl_backend() {
+        check_backend::<Amcl>();
+    }
+
+    #[test]
+    #[cfg(feature = "blst-backend")]
+    fn test_blst_backend() {
+        check_backend::<Blst>();
+    }
+}
@@ -0,0 +1,99 @@
+use super::aggregates::AggregateSignature;
+use super::errors::DecodeError;
+use super::keys::PublicKey;
+use super::signature::Signature;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+
+// Implements base64 constructors and formatters using the compressed bytes of the point.
+macro_rules! impl_base64 {
+    ($type: ty) => {
+        impl $type {
+            /// Instantiate from the standard (RFC 4648 section 4, padded) base64 encoding.
+            pub fn from_base64(s: &str) -> Result<Self, DecodeError> {
+                let bytes = STANDARD.decode(s).map_err(|_| DecodeError::InvalidBase64)?;
+                Self::from_bytes(&bytes)
+            }
+
+            /// Instantiate from the URL-safe (RFC 4648 section 5, unpadded) base64 encoding.
+            pub fn from_base64_url(s: &str) -> Result<Self, DecodeError> {
+                let bytes = URL_SAFE_NO_PAD.decode(s).map_err(|_| DecodeError::InvalidBase64)?;
+                Self::from_bytes(&bytes)
+            }
+
+            /// Export to the standard (RFC 4648 section 4, padded) base64 encoding.
+            pub fn to_base64(&self) -> String {
+                STANDARD.encode(self.as_bytes())
+            }
+
+            /// Export to the URL-safe (RFC 4648 section 5, unpadded) base64 encoding, as used
+            /// in JWTs.
+            pub fn to_base64_url(&self) -> String {
+                URL_SAFE_NO_PAD.encode(self.as_bytes())
+            }
+        }
+    };
+}
+
+impl_base64!(PublicKey);
+impl_base64!(Signature);
+impl_base64!(AggregateSignature);
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::keys::Keypair;
+    use super::*;
+
+    #[test]
+    fn test_base64_round_trip() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signature = Signature::new(&[42u8; 32], &keypair.sk);
+        let aggregate_signature = AggregateSignature::from_signature(&signature);
+
+        // 48 and 96 bytes are multiples of 3, so neither encoding is padded
+        let s = keypair.pk.to_base64();
+        assert_eq!(s.len(), 64);
+        assert_eq!(PublicKey::from_base64(&s).unwrap(), keypair.pk);
+        let s = keypair.pk.to_base64_url();
+        assert_eq!(s.len(), 64);
+        assert_eq!(PublicKey::from_base64_url(&s).unwrap(), keypair.pk);
+
+        let s = signature.to_base64();
+        assert_eq!(s.len(), 128);
+        assert_eq!(Signature::from_base64(&s).unwrap(), signature);
+        let s = signature.to_base64_url();
+        assert_eq!(Signature::from_base64_url(&s).unwrap(), signature);
+
+        let s = aggregate_signature.to_base64_url();
+        assert_eq!(AggregateSignature::from_base64_url(&s).unwrap(), aggregate_signature);
+    }
+
+    #[test]
+    fn test_base64_alphabets() {
+        let s = "pJHRsOzZu5F5ifDnTw3qBCLqxKhz5eJkTzaN/7mm4g/W4Qwbd2VNBnwGGPblp/ea";
+        let public_key = PublicKey::from_base64(s).unwrap();
+        assert_eq!(public_key.to_base64(), s);
+        assert_eq!(public_key.to_base64_url(), s.replace('/', "_"));
+
+        // Each alphabet is rejected by the other
+        assert_eq!(PublicKey::from_base64_url(s), Err(DecodeError::InvalidBase64));
+        assert_eq!(PublicKey::from_base64(&s.replace('/', "_")), Err(DecodeError::InvalidBase64));
+    }
+
+    #[test]
+    fn test_base64_invalid() {
+        assert_eq!(PublicKey::from_base64("not base64!"), Err(DecodeError::InvalidBase64));
+        assert_eq!(
+            PublicKey::from_base64("AAAA"),
+            Err(DecodeError::InvalidLength { expected: 48, actual: 3 })
+        );
+        assert_eq!(
+            Signature::from_base64_url(""),
+            Err(DecodeError::InvalidLength { expected: 96, actual: 0 })
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! A worker which coalesces signature sets submitted from many tasks into randomized batches.
+//!
+//! Each submission returns a `PendingVerification`, which may be waited on from a thread or
+//! awaited as a future. The worker verifies whatever has queued up, up to the maximum batch
+//! size, with a single `verify_multiple_aggregate_signatures()`. If a batch fails each set is
+//! verified alone so that only the invalid sets are rejected.
+//!
+//! `GossipVerifier` deduplicates signatures in front of the worker, as gossip delivers the same
+//! signature many times.
+
+extern crate amcl;
+
+use super::aggregates::{AggregatePublicKey, AggregateSignature};
+use super::keys::PublicKey;
+use super::signature::Signature;
+use amcl::hash256::HASH256;
+use rand_core::{CryptoRng, RngCore};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// Submits signature sets to a batch verification worker thread.
+///
+/// Handles may be cloned and shared between tasks, the worker stops once every handle is
+/// dropped and the queue is drained.
+#[derive(Clone)]
+pub struct BatchVerifier {
+    sender: Sender<SignatureSet>,
+}
+
+impl BatchVerifier {
+    /// Spawn a worker which verifies batches of at most `max_batch_size` signature sets,
+    /// drawing the random batch coefficients from `rng`.
+    pub fn spawn<R>(mut rng: R, max_batch_size: usize) -> Self
+    where
+        R: RngCore + CryptoRng + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let max_batch_size = max_batch_size.max(1);
+        thread::spawn(move || run(&mut rng, &receiver, max_batch_size));
+        BatchVerifier { sender }
+    }
+
+    /// Queue a signature set for verification.
+    ///
+    /// PublicKeys must all be verified via Proof of Possession before being aggregated.
+    pub fn submit(
+        &self,
+        signature: AggregateSignature,
+        public_key: AggregatePublicKey,
+        message: Vec<u8>,
+    ) -> PendingVerification {
+        let completion = Completion(Arc::new(Shared::default()));
+        let pending = PendingVerification(completion.0.clone());
+        // If the worker has stopped the set is dropped, completing it as invalid
+        let _ = self.sender.send(SignatureSet { signature, public_key, message, completion });
+        pending
+    }
+}
+
+/// Verifies gossiped (message, PublicKey, Signature) triples with a BatchVerifier, verifying
+/// each distinct triple only once within a window of the most recently submitted triples.
+///
+/// Duplicates share the result of the first submission, whether or not it has completed.
+pub struct GossipVerifier {
+    verifier: BatchVerifier,
+    seen: Mutex<SeenWindow>,
+}
+
+impl GossipVerifier {
+    /// Deduplicate in front of a BatchVerifier, remembering the last `window` distinct
+    /// triples.
+    pub fn new(verifier: BatchVerifier, window: usize) -> Self {
+        let seen =
+            SeenWindow { window: window.max(1), order: VecDeque::new(), entries: HashMap::new() };
+        GossipVerifier { verifier, seen: Mutex::new(seen) }
+    }
+
+    /// Queue a triple for verification, unless it was seen within the window.
+    ///
+    /// PublicKeys must be verified via Proof of Possession.
+    pub fn submit(
+        &self,
+        message: &[u8],
+        public_key: &PublicKey,
+        signature: &Signature,
+    ) -> PendingVerification {
+        let key = triple_key(message, public_key, signature);
+        let mut seen = self.seen.lock().unwrap_or_else(|error| error.into_inner());
+        if let Some(shared) = seen.entries.get(&key) {
+            return PendingVerification(shared.clone());
+        }
+
+        let pending = self.verifier.submit(
+            AggregateSignature::from_signature(signature),
+            AggregatePublicKey::from_public_key(public_key),
+            message.to_vec(),
+        );
+        seen.insert(key, pending.0.clone());
+        pending
+    }
+
+    /// The number of distinct triples currently remembered.
+    pub fn seen(&self) -> usize {
+        self.seen.lock().unwrap_or_else(|error| error.into_inner()).entries.len()
+    }
+}
+
+// The most recently submitted distinct triples, oldest first.
+struct SeenWindow {
+    window: usize,
+    order: VecDeque<[u8; 32]>,
+    entries: HashMap<[u8; 32], Arc<Shared>>,
+}
+
+impl SeenWindow {
+    fn insert(&mut self, key: [u8; 32], shared: Arc<Shared>) {
+        if self.order.len() == self.window {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.push_back(key);
+        self.entries.insert(key, shared);
+    }
+}
+
+// SHA-256 of the triple, the message is length prefixed so distinct triples cannot collide.
+fn triple_key(message: &[u8], public_key: &PublicKey, signature: &Signature) -> [u8; 32] {
+    let mut hash256 = HASH256::new();
+    hash256.init();
+    hash256.process_array(&public_key.as_bytes());
+    hash256.process_array(&signature.as_bytes());
+    hash256.process_array(&(message.len() as u64).to_be_bytes());
+    hash256.process_array(message);
+    hash256.hash()
+}
+
+/// The result of a submitted signature set, which may be waited on or awaited.
+pub struct PendingVerification(Arc<Shared>);
+
+impl PendingVerification {
+    /// Block until the signature set has been verified.
+    pub fn wait(self) -> bool {
+        let mut state = self.0.state.lock().unwrap_or_else(|error| error.into_inner());
+        loop {
+            if let Some(valid) = state.result {
+                return valid;
+            }
+            state = self.0.condvar.wait(state).unwrap_or_else(|error| error.into_inner());
+        }
+    }
+
+    /// The result if the signature set has been verified.
+    pub fn try_result(&self) -> Option<bool> {
+        self.0.state.lock().unwrap_or_else(|error| error.into_inner()).result
+    }
+}
+
+impl Future for PendingVerification {
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<bool> {
+        let mut state = self.0.state.lock().unwrap_or_else(|error| error.into_inner());
+        match state.result {
+            Some(valid) => Poll::Ready(valid),
+            None => {
+                // Duplicate submissions share the state, so each keeps its own waker
+                if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                    state.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    result: Option<bool>,
+    wakers: Vec<Waker>,
+}
+
+// Completes a PendingVerification, as invalid if dropped before a result is set.
+struct Completion(Arc<Shared>);
+
+impl Completion {
+    fn complete(&self, valid: bool) {
+        let wakers = {
+            let mut state = self.0.state.lock().unwrap_or_else(|error| error.into_inner());
+            if state.result.is_some() {
+                return;
+            }
+            state.result = Some(valid);
+            self.0.condvar.notify_all();
+            std::mem::take(&mut state.wakers)
+        };
+        // Wake outside of the lock, the tasks may poll immediately
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl Drop for Completion {
+    fn drop(&mut self) {
+        self.complete(false);
+    }
+}
+
+struct SignatureSet {
+    signature: AggregateSignature,
+    public_key: AggregatePublicKey,
+    message: Vec<u8>,
+    completion: Completion,
+}
+
+fn run<R: RngCore + CryptoRng>(rng: &mut R, receiver: &Receiver<SignatureSet>, max: usize) {
+    while let Ok(first) = receiver.recv() {
+        let mut batch = vec![first];
+        while batch.len() < max {
+            match receiver.try_recv() {
+                Ok(set) => batch.push(set),
+                Err(_) => break,
+            }
+        }
+        verify_batch(rng, &batch);
+    }
+}
+
+fn verify_batch<R: RngCore + CryptoRng>(rng: &mut R, batch: &[SignatureSet]) {
+    if verify_sets(rng, batch) {
+        batch.iter().for_each(|set| set.completion.complete(true));
+        return;
+    }
+    if batch.len() == 1 {
+        batch[0].completion.complete(false);
+        return;
+    }
+    // Find the invalid sets
+    for set in batch {
+        let valid = verify_sets(rng, core::slice::from_ref(set));
+        set.completion.complete(valid);
+    }
+}
+
+fn verify_sets<R: RngCore + CryptoRng>(rng: &mut R, sets: &[SignatureSet]) -> bool {
+    AggregateSignature::verify_multiple_aggregate_signatures(
+        rng,
+        sets.iter().map(|set| (&set.signature, &set.public_key, set.message.as_slice())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use self::rand::rngs::StdRng;
+    use self::rand::{Rng, SeedableRng};
+    use super::super::keys::Keypair;
+    use super::super::signature::Signature;
+    use super::*;
+
+    fn signature_set(msg: &[u8], valid: bool) -> (AggregateSignature, AggregatePublicKey, Vec<u8>) {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signed = if valid { msg.to_vec() } else { b"other".to_vec() };
+        let signature = AggregateSignature::from_signature(&Signature::new(&signed, &keypair.sk));
+        (signature, AggregatePublicKey::from_public_key(&keypair.pk), msg.to_vec())
+    }
+
+    // ThreadRng is not Send, so the worker is given its own seeded RNG
+    fn worker_rng() -> StdRng {
+        StdRng::from_seed(rand::thread_rng().gen())
+    }
+
+    #[test]
+    fn test_batch_verifier() {
+        let verifier = BatchVerifier::spawn(worker_rng(), 4);
+
+        let pending: Vec<(PendingVerification, bool)> = (0..10)
+            .map(|i| {
+                let valid = i % 3 != 0;
+                let (signature, public_key, message) = signature_set(&[i as u8; 32], valid);
+                (verifier.submit(signature, public_key, message), valid)
+            })
+            .collect();
+
+        for (pending, valid) in pending {
+            assert_eq!(pending.wait(), valid);
+        }
+    }
+
+    #[test]
+    fn test_batch_verifier_many_tasks() {
+        let verifier = BatchVerifier::spawn(worker_rng(), 16);
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let verifier = verifier.clone();
+                thread::spawn(move || {
+                    let (signature, public_key, message) = signature_set(&[i as u8; 32], true);
+                    verifier.submit(signature, public_key, message).wait()
+                })
+            })
+            .collect();
+        assert!(handles.into_iter().all(|handle| handle.join().unwrap()));
+    }
+
+    #[test]
+    fn test_gossip_verifier_deduplicates() {
+        let gossip = GossipVerifier::new(BatchVerifier::spawn(worker_rng(), 8), 2);
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signature = Signature::new(b"cats", &keypair.sk);
+
+        let first = gossip.submit(b"cats", &keypair.pk, &signature);
+        let duplicate = gossip.submit(b"cats", &keypair.pk, &signature);
+        assert!(Arc::ptr_eq(&first.0, &duplicate.0));
+        assert_eq!(gossip.seen(), 1);
+        assert!(first.wait());
+        assert!(duplicate.wait());
+
+        // Invalid triples are remembered as invalid
+        let invalid = gossip.submit(b"dogs", &keypair.pk, &signature);
+        assert!(!invalid.wait());
+        assert_eq!(gossip.submit(b"dogs", &keypair.pk, &signature).try_result(), Some(false));
+
+        // The oldest triple is forgotten once the window is full
+        let other = Signature::new(b"mice", &keypair.sk);
+        assert!(gossip.submit(b"mice", &keypair.pk, &other).wait());
+        assert_eq!(gossip.seen(), 2);
+        let resubmitted = gossip.submit(b"cats", &keypair.pk, &signature);
+        assert!(resubmitted.wait());
+    }
+
+    #[test]
+    fn test_completion_dropped() {
+        let completion = Completion(Arc::new(Shared::default()));
+        let pending = PendingVerification(completion.0.clone());
+        assert_eq!(pending.try_result(), None);
+        drop(completion);
+        assert_eq!(pending.try_result(), Some(false));
+        assert!(!pending.wait());
+    }
+}
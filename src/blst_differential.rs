@@ -0,0 +1,196 @@
+//! Differential tests against the blst crate, enabled by the `blst-differential` feature.
+//!
+//! Randomized inputs are run through both libraries and any difference in outputs fails.
+
+extern crate rand;
+
+use self::rand::{CryptoRng, Rng};
+use super::aggregates::{AggregatePublicKey, AggregateSignature};
+use super::amcl_utils::{compress_g2, hash_to_curve_g2_with_dst, DST_G2, SECRET_KEY_BYTES};
+use super::keys::{PublicKey, SecretKey};
+use super::signature::Signature;
+use blst::min_pk;
+use blst::BLST_ERROR;
+
+const ITERATIONS: usize = 64;
+
+fn random_bytes<R: Rng>(rng: &mut R, min_len: usize, max_len: usize) -> Vec<u8> {
+    let len = rng.gen_range(min_len..=max_len);
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+// A random SecretKey in both libraries.
+fn random_secret_key<R: Rng + CryptoRng>(rng: &mut R) -> (SecretKey, min_pk::SecretKey) {
+    let secret_key = SecretKey::random(rng);
+    let blst_secret_key = min_pk::SecretKey::from_bytes(&secret_key.as_bytes()).unwrap();
+    (secret_key, blst_secret_key)
+}
+
+#[test]
+fn test_blst_key_generate() {
+    let mut rng = rand::thread_rng();
+    for _ in 0..ITERATIONS {
+        let ikm = random_bytes(&mut rng, 32, 64);
+        let key_info = random_bytes(&mut rng, 0, 32);
+
+        let secret_key = SecretKey::key_generate(&ikm, &key_info).unwrap();
+        let blst_secret_key = min_pk::SecretKey::key_gen(&ikm, &key_info).unwrap();
+        assert_eq!(secret_key.as_bytes(), blst_secret_key.to_bytes());
+        assert_eq!(
+            PublicKey::from_secret_key(&secret_key).as_bytes(),
+            blst_secret_key.sk_to_pk().compress()
+        );
+    }
+}
+
+#[test]
+fn test_blst_hash_to_curve() {
+    // Signing with a SecretKey of one outputs the hash of the message
+    let mut one = [0u8; SECRET_KEY_BYTES];
+    one[SECRET_KEY_BYTES - 1] = 1;
+    let blst_one = min_pk::SecretKey::from_bytes(&one).unwrap();
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..ITERATIONS {
+        let msg = random_bytes(&mut rng, 0, 256);
+        let dst = random_bytes(&mut rng, 1, 64);
+
+        assert_eq!(
+            compress_g2(&hash_to_curve_g2_with_dst(&msg, &dst)),
+            blst_one.sign(&msg, &dst, &[]).compress()
+        );
+    }
+}
+
+#[test]
+fn test_blst_sign_verify() {
+    let mut rng = rand::thread_rng();
+    for _ in 0..ITERATIONS {
+        let (secret_key, blst_secret_key) = random_secret_key(&mut rng);
+        let public_key = PublicKey::from_secret_key(&secret_key);
+        let blst_public_key = blst_secret_key.sk_to_pk();
+        let msg = random_bytes(&mut rng, 0, 256);
+        let other_msg = random_bytes(&mut rng, 0, 256);
+
+        let signature = Signature::new(&msg, &secret_key);
+        let blst_signature = blst_secret_key.sign(&msg, DST_G2, &[]);
+        assert_eq!(signature.as_bytes(), blst_signature.compress());
+
+        for msg in &[&msg, &other_msg] {
+            assert_eq!(
+                signature.verify(msg, &public_key),
+                blst_signature.verify(true, msg, DST_G2, &[], &blst_public_key, true)
+                    == BLST_ERROR::BLST_SUCCESS
+            );
+        }
+    }
+}
+
+#[test]
+fn test_blst_aggregate() {
+    let mut rng = rand::thread_rng();
+    for _ in 0..ITERATIONS / 8 {
+        let n = rng.gen_range(1..=16);
+        let secret_keys: Vec<(SecretKey, min_pk::SecretKey)> =
+            (0..n).map(|_| random_secret_key(&mut rng)).collect();
+        let public_keys: Vec<PublicKey> =
+            secret_keys.iter().map(|(sk, _)| PublicKey::from_secret_key(sk)).collect();
+        let public_keys: Vec<&PublicKey> = public_keys.iter().collect();
+        let blst_public_keys: Vec<min_pk::PublicKey> =
+            secret_keys.iter().map(|(_, sk)| sk.sk_to_pk()).collect();
+        let blst_public_keys: Vec<&min_pk::PublicKey> = blst_public_keys.iter().collect();
+
+        // FastAggregateVerify over a common message
+        let msg = random_bytes(&mut rng, 0, 256);
+        let signatures: Vec<Signature> =
+            secret_keys.iter().map(|(sk, _)| Signature::new(&msg, sk)).collect();
+        let signatures: Vec<&Signature> = signatures.iter().collect();
+        let blst_signatures: Vec<min_pk::Signature> =
+            secret_keys.iter().map(|(_, sk)| sk.sign(&msg, DST_G2, &[])).collect();
+        let blst_signatures: Vec<&min_pk::Signature> = blst_signatures.iter().collect();
+
+        let aggregate_public_key = AggregatePublicKey::aggregate(&public_keys).unwrap();
+        let blst_aggregate_public_key =
+            min_pk::AggregatePublicKey::aggregate(&blst_public_keys, false).unwrap();
+        assert_eq!(
+            aggregate_public_key.as_bytes(),
+            blst_aggregate_public_key.to_public_key().compress()
+        );
+
+        let aggregate_signature = AggregateSignature::aggregate(&signatures);
+        let blst_aggregate_signature =
+            min_pk::AggregateSignature::aggregate(&blst_signatures, false).unwrap().to_signature();
+        assert_eq!(aggregate_signature.as_bytes(), blst_aggregate_signature.compress());
+
+        let other_msg = random_bytes(&mut rng, 0, 256);
+        for msg in &[&msg, &other_msg] {
+            assert_eq!(
+                aggregate_signature.fast_aggregate_verify(msg, &public_keys),
+                blst_aggregate_signature.fast_aggregate_verify(
+                    true,
+                    msg,
+                    DST_G2,
+                    &blst_public_keys
+                ) == BLST_ERROR::BLST_SUCCESS
+            );
+        }
+
+        // AggregateVerify over distinct messages
+        let msgs: Vec<Vec<u8>> = (0..n).map(|i| [&[i as u8][..], &msg].concat()).collect();
+        let msgs: Vec<&[u8]> = msgs.iter().map(|msg| msg.as_slice()).collect();
+        let signatures: Vec<Signature> =
+            secret_keys.iter().zip(&msgs).map(|((sk, _), msg)| Signature::new(msg, sk)).collect();
+        let aggregate_signature =
+            AggregateSignature::aggregate(&signatures.iter().collect::<Vec<_>>());
+        let blst_aggregate_signature =
+            min_pk::Signature::from_bytes(&aggregate_signature.as_bytes()).unwrap();
+
+        assert!(aggregate_signature.aggregate_verify(&msgs, &public_keys));
+        assert_eq!(
+            blst_aggregate_signature.aggregate_verify(true, &msgs, DST_G2, &blst_public_keys, true),
+            BLST_ERROR::BLST_SUCCESS
+        );
+    }
+}
+
+#[test]
+fn test_blst_decode() {
+    let mut rng = rand::thread_rng();
+    for _ in 0..ITERATIONS * 4 {
+        // Random bytes with the compression flag set, about half of which are on the curve
+        let mut bytes = random_bytes(&mut rng, 48, 48);
+        bytes[0] = bytes[0] & 0x3f | 0x80;
+        assert_eq!(
+            PublicKey::from_bytes(&bytes).is_ok(),
+            min_pk::PublicKey::key_validate(&bytes).is_ok()
+        );
+
+        let mut bytes = random_bytes(&mut rng, 96, 96);
+        bytes[0] = bytes[0] & 0x3f | 0x80;
+        assert_eq!(
+            Signature::from_bytes(&bytes).is_ok(),
+            min_pk::Signature::sig_validate(&bytes, false).is_ok()
+        );
+    }
+
+    // Valid encodings with a single bit flipped
+    let (secret_key, _) = random_secret_key(&mut rng);
+    let public_key = PublicKey::from_secret_key(&secret_key).as_bytes();
+    let signature = Signature::new(&[42u8; 32], &secret_key).as_bytes();
+    for bit in 0..48 * 8 {
+        let mut bytes = public_key;
+        bytes[bit / 8] ^= 1 << (bit % 8);
+        assert_eq!(
+            PublicKey::from_bytes(&bytes).is_ok(),
+            min_pk::PublicKey::key_validate(&bytes).is_ok()
+        );
+    }
+    for bit in 0..96 * 8 {
+        let mut bytes = signature;
+        bytes[bit / 8] ^= 1 << (bit % 8);
+        assert_eq!(
+            Signature::from_bytes(&bytes).is_ok(),
+            min_pk::Signature::sig_validate(&bytes, false).is_ok()
+        );
+    }
+}
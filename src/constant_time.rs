@@ -0,0 +1,351 @@
+// Constant-time decompression of G1 and G2 points.
+//
+// The square root, the choice of y by the sort flag and the flag checks neither branch on nor
+// index by the encoded bytes, only whether the encoding is valid (and whether it is the point
+// at infinity, which is never a sensitive key) is revealed. Field arithmetic is amcl's, which
+// has no data dependent branches, comparisons and selections are done on the canonical bytes.
+use super::amcl_utils::{
+    check_flags, fp2_from_bytes, fp2_to_bytes, is_canonical_fp, Big, GroupG1, GroupG2,
+    COMPRESSION_FLAG, FLAGS_MASK, FP2, G1_BYTES, G2_BYTES, INFINITY_FLAG, MODBYTES, MODULUS_BYTES,
+    SORT_FLAG,
+};
+use super::errors::DecodeError;
+use spin::Lazy;
+use BLSCurve::fp::FP;
+
+// (p + 1) / 4, the square root of a square x in Fp is x^((p + 1) / 4) as p = 3 mod 4.
+const SQRT_EXPONENT_FP: [u8; MODBYTES] = [
+    6, 128, 68, 122, 142, 95, 249, 166, 146, 198, 233, 237, 144, 210, 235, 53, 217, 29, 210, 225,
+    60, 225, 68, 175, 217, 204, 52, 168, 61, 172, 61, 137, 7, 170, 255, 255, 172, 84, 255, 255,
+    238, 127, 191, 255, 255, 255, 234, 171,
+];
+
+// (p^2 + 7) / 16, from which the square root in Fp2 is found as p^2 = 9 mod 16.
+// See https://www.rfc-editor.org/rfc/rfc9380.html#appendix-I.3
+const SQRT_EXPONENT_FP2: [u8; MODBYTES * 2] = [
+    0, 42, 67, 122, 75, 140, 53, 252, 116, 189, 39, 142, 170, 34, 242, 94, 158, 45, 201, 14, 80,
+    231, 4, 107, 70, 110, 89, 228, 147, 73, 232, 189, 5, 10, 98, 207, 209, 109, 220, 166, 239, 83,
+    20, 147, 48, 151, 142, 240, 17, 214, 134, 25, 200, 97, 133, 199, 178, 146, 232, 90, 135, 9, 26,
+    4, 150, 107, 249, 30, 211, 231, 27, 116, 49, 98, 195, 56, 54, 33, 19, 207, 215, 206, 214, 177,
+    215, 99, 130, 234, 178, 106, 160, 0, 1, 199, 24, 228,
+];
+
+// The roots of unity u, sqrt(u) and sqrt(-u) which correct the candidate square root in Fp2.
+// Computed once from public constants, so the variable time square root is fine.
+static SQRT_CORRECTIONS_FP2: Lazy<[FP2; 3]> = Lazy::new(|| {
+    let u = FP2::new_ints(0, 1);
+    let mut sqrt_u = u.clone();
+    sqrt_u.sqrt();
+    let mut sqrt_minus_u = u.clone();
+    sqrt_minus_u.neg();
+    sqrt_minus_u.sqrt();
+    [u, sqrt_u, sqrt_minus_u]
+});
+
+// Take a 384 bit array and convert to GroupG1 point (x, y) without a subgroup check.
+pub fn decompress_g1(g1_bytes: &[u8]) -> Result<GroupG1, DecodeError> {
+    if g1_bytes.len() != G1_BYTES {
+        return Err(DecodeError::InvalidLength { expected: G1_BYTES, actual: g1_bytes.len() });
+    }
+    let (flags, x_bytes) = split_flags::<MODBYTES>(g1_bytes);
+
+    // y^2 = x^3 + 4
+    let x = FP::new_big(&Big::from_bytes(&x_bytes));
+    let mut rhs = x.clone();
+    rhs.sqr();
+    rhs.mul(&x);
+    rhs.add(&FP::new_int(4));
+    rhs.reduce();
+
+    let y = fp_pow(&rhs, &SQRT_EXPONENT_FP);
+    let mut y_squared = y.clone();
+    y_squared.sqr();
+    let is_square = ct_eq(&fp_to_bytes(&y_squared), &fp_to_bytes(&rhs));
+
+    // Take the larger of y and -y if the sort flag is set, otherwise the smaller
+    let mut neg_y = y.clone();
+    neg_y.neg();
+    let mut y_bytes = fp_to_bytes(&y);
+    let neg_y_bytes = fp_to_bytes(&neg_y);
+    let y_is_larger = ct_lt(&neg_y_bytes, &y_bytes);
+    ct_select(&mut y_bytes, &neg_y_bytes, y_is_larger ^ flag(flags, SORT_FLAG));
+
+    let infinity = flag(flags, INFINITY_FLAG);
+    let valid =
+        valid_flags(flags, &x_bytes) & ct_lt(&x_bytes, &MODULUS_BYTES) & (infinity | is_square);
+    if valid == 0 {
+        return Err(decompress_error(g1_bytes, &x_bytes));
+    }
+    if infinity == 1 {
+        return Ok(GroupG1::new());
+    }
+    Ok(GroupG1::new_bigs(&Big::from_bytes(&x_bytes), &Big::from_bytes(&y_bytes)))
+}
+
+// Take a 384*2 bit array and convert to GroupG2 point (x, y) without a subgroup check.
+pub fn decompress_g2(g2_bytes: &[u8]) -> Result<GroupG2, DecodeError> {
+    if g2_bytes.len() != G2_BYTES {
+        return Err(DecodeError::InvalidLength { expected: G2_BYTES, actual: g2_bytes.len() });
+    }
+    let (flags, x_bytes) = split_flags::<{ MODBYTES * 2 }>(g2_bytes);
+
+    // y^2 = x^3 + 4(1 + u)
+    let x = fp2_from_bytes(&x_bytes);
+    let mut rhs = x.clone();
+    rhs.sqr();
+    rhs.mul(&x);
+    rhs.add(&FP2::new_ints(4, 4));
+    rhs.reduce();
+    let rhs_bytes = fp2_bytes(&rhs);
+
+    // The candidate root is corrected by the root of unity which squares to rhs, if any
+    let candidate = fp2_pow(&rhs, &SQRT_EXPONENT_FP2);
+    let mut y_bytes = fp2_bytes(&candidate);
+    for correction in SQRT_CORRECTIONS_FP2.iter() {
+        let mut corrected = candidate.clone();
+        corrected.mul(correction);
+        let mut corrected_squared = corrected.clone();
+        corrected_squared.sqr();
+        let is_root = ct_eq(&fp2_bytes(&corrected_squared), &rhs_bytes);
+        ct_select(&mut y_bytes, &fp2_bytes(&corrected), is_root);
+    }
+    let y = fp2_from_bytes(&y_bytes);
+    let mut y_squared = y.clone();
+    y_squared.sqr();
+    let is_square = ct_eq(&fp2_bytes(&y_squared), &rhs_bytes);
+
+    // Take the larger of y and -y if the sort flag is set, otherwise the smaller, comparing
+    // (c1, c0) lexicographically
+    let mut neg_y = y;
+    neg_y.neg();
+    let neg_y_bytes = fp2_bytes(&neg_y);
+    let y_is_larger = ct_lt(&neg_y_bytes, &y_bytes);
+    ct_select(&mut y_bytes, &neg_y_bytes, y_is_larger ^ flag(flags, SORT_FLAG));
+
+    let infinity = flag(flags, INFINITY_FLAG);
+    let valid = valid_flags(flags, &x_bytes)
+        & ct_lt(&x_bytes[..MODBYTES], &MODULUS_BYTES)
+        & ct_lt(&x_bytes[MODBYTES..], &MODULUS_BYTES)
+        & (infinity | is_square);
+    if valid == 0 {
+        return Err(decompress_error(g2_bytes, &x_bytes));
+    }
+    if infinity == 1 {
+        return Ok(GroupG2::new());
+    }
+    Ok(GroupG2::new_fp2s(&fp2_from_bytes(&x_bytes), &fp2_from_bytes(&y_bytes)))
+}
+
+// Split the flags from the encoded x coordinate.
+fn split_flags<const N: usize>(bytes: &[u8]) -> (u8, [u8; N]) {
+    let mut x_bytes = [0u8; N];
+    x_bytes.copy_from_slice(bytes);
+    let flags = x_bytes[0] & FLAGS_MASK;
+    x_bytes[0] &= !FLAGS_MASK;
+    (flags, x_bytes)
+}
+
+// Returns 1 if the flag is set, otherwise 0.
+fn flag(flags: u8, flag: u8) -> u8 {
+    ((flags & flag) != 0) as u8
+}
+
+// Returns 1 if the flags are valid for a compressed encoding, as in `check_flags()`.
+fn valid_flags(flags: u8, x_bytes: &[u8]) -> u8 {
+    // Infinity must have the sort flag unset and all other bits zero
+    let infinity_valid = (1 ^ flag(flags, SORT_FLAG)) & ct_is_zero(x_bytes);
+    flag(flags, COMPRESSION_FLAG) & ((1 ^ flag(flags, INFINITY_FLAG)) | infinity_valid)
+}
+
+// Only reached for invalid encodings, so finding the reason may take variable time.
+fn decompress_error(bytes: &[u8], x_bytes: &[u8]) -> DecodeError {
+    if let Err(error) = check_flags(bytes, true) {
+        return error;
+    }
+    if x_bytes.chunks(MODBYTES).any(|part| !is_canonical_fp(part, false)) {
+        return DecodeError::FieldElementTooLarge;
+    }
+    DecodeError::NotOnCurve
+}
+
+// x^exponent for a public exponent.
+fn fp_pow(x: &FP, exponent: &[u8]) -> FP {
+    let mut result = FP::new_int(1);
+    for byte in exponent {
+        for i in (0..8).rev() {
+            result.sqr();
+            if (byte >> i) & 1 == 1 {
+                result.mul(x);
+            }
+        }
+    }
+    result.reduce();
+    result
+}
+
+// x^exponent for a public exponent.
+fn fp2_pow(x: &FP2, exponent: &[u8]) -> FP2 {
+    let mut result = FP2::new_int(1);
+    for byte in exponent {
+        for i in (0..8).rev() {
+            result.sqr();
+            if (byte >> i) & 1 == 1 {
+                result.mul(x);
+            }
+        }
+    }
+    result.reduce();
+    result
+}
+
+fn fp_to_bytes(x: &FP) -> [u8; MODBYTES] {
+    let mut bytes = [0u8; MODBYTES];
+    x.redc().to_bytes(&mut bytes);
+    bytes
+}
+
+fn fp2_bytes(x: &FP2) -> [u8; MODBYTES * 2] {
+    let mut bytes = [0u8; MODBYTES * 2];
+    fp2_to_bytes(x, &mut bytes);
+    bytes
+}
+
+// Returns 1 if the byte strings are equal, otherwise 0.
+fn ct_eq(a: &[u8], b: &[u8]) -> u8 {
+    let difference = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    ct_is_zero(&[difference])
+}
+
+// Returns 1 if all bytes are zero, otherwise 0.
+fn ct_is_zero(bytes: &[u8]) -> u8 {
+    let bits = bytes.iter().fold(0u8, |acc, x| acc | x);
+    (((bits as u16).wrapping_sub(1) >> 8) & 1) as u8
+}
+
+// Returns 1 if big-endian a < b, otherwise 0.
+fn ct_lt(a: &[u8], b: &[u8]) -> u8 {
+    let mut lt = 0u8;
+    let mut gt = 0u8;
+    for (x, y) in a.iter().zip(b) {
+        let x_lt_y = (((*x as u16).wrapping_sub(*y as u16) >> 8) & 1) as u8;
+        let x_gt_y = (((*y as u16).wrapping_sub(*x as u16) >> 8) & 1) as u8;
+        let undecided = 1 ^ (lt | gt);
+        lt |= x_lt_y & undecided;
+        gt |= x_gt_y & undecided;
+    }
+    lt
+}
+
+// Replace a with b if choice is 1, leave a unchanged if choice is 0.
+fn ct_select(a: &mut [u8], b: &[u8], choice: u8) {
+    let mask = 0u8.wrapping_sub(choice);
+    for (x, y) in a.iter_mut().zip(b) {
+        *x ^= mask & (*x ^ y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::amcl_utils::{
+        compress_g1, compress_g2, deserialize_g1, deserialize_g2, g1mul, g2mul, GENERATORG1,
+    };
+    use super::super::keys::SecretKey;
+    use super::*;
+
+    #[test]
+    fn test_sqrt() {
+        let root = fp_pow(&FP::new_int(9), &SQRT_EXPONENT_FP);
+        assert!(root.equals(&FP::new_int(3)) || root.equals(&FP::new_int(-3)));
+
+        // The corrected candidate root of a square in Fp2
+        let x = FP2::new_ints(3, 5);
+        let mut square = x.clone();
+        square.sqr();
+        let candidate = fp2_pow(&square, &SQRT_EXPONENT_FP2);
+        let mut neg_x = x.clone();
+        neg_x.neg();
+        let found = SQRT_CORRECTIONS_FP2.iter().chain(Some(FP2::new_int(1)).iter()).any(|c| {
+            let mut root = candidate.clone();
+            root.mul(c);
+            root.equals(&x) || root.equals(&neg_x)
+        });
+        assert!(found);
+    }
+
+    #[test]
+    fn test_ct_helpers() {
+        assert_eq!(ct_eq(&[1, 2, 3], &[1, 2, 3]), 1);
+        assert_eq!(ct_eq(&[1, 2, 3], &[1, 2, 4]), 0);
+        assert_eq!(ct_lt(&[1, 2, 3], &[1, 3, 0]), 1);
+        assert_eq!(ct_lt(&[1, 3, 0], &[1, 2, 3]), 0);
+        assert_eq!(ct_lt(&[1, 2, 3], &[1, 2, 3]), 0);
+        assert_eq!(ct_lt(&[0, 255], &[1, 0]), 1);
+
+        let mut a = [1u8, 2];
+        ct_select(&mut a, &[3, 4], 0);
+        assert_eq!(a, [1, 2]);
+        ct_select(&mut a, &[3, 4], 1);
+        assert_eq!(a, [3, 4]);
+    }
+
+    #[test]
+    fn test_decompress_g1() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let point = g1mul(&GENERATORG1, SecretKey::random(&mut rng).as_raw());
+            let bytes = compress_g1(&point);
+            assert!(decompress_g1(&bytes).unwrap().equals(&point));
+            assert!(decompress_g1(&bytes).unwrap().equals(&deserialize_g1(&bytes).unwrap()));
+        }
+
+        let mut infinity = [0u8; G1_BYTES];
+        infinity[0] = 0xc0;
+        assert!(decompress_g1(&infinity).unwrap().is_infinity());
+    }
+
+    #[test]
+    fn test_decompress_g2() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let point = g2mul(&GroupG2::generator(), SecretKey::random(&mut rng).as_raw());
+            let bytes = compress_g2(&point);
+            assert!(decompress_g2(&bytes).unwrap().equals(&point));
+            assert!(decompress_g2(&bytes).unwrap().equals(&deserialize_g2(&bytes).unwrap()));
+        }
+
+        let mut infinity = [0u8; G2_BYTES];
+        infinity[0] = 0xc0;
+        assert!(decompress_g2(&infinity).unwrap().is_infinity());
+    }
+
+    #[test]
+    fn test_decompress_errors() {
+        let mut bytes = compress_g1(&GENERATORG1);
+
+        bytes[0] &= !COMPRESSION_FLAG;
+        assert_eq!(decompress_g1(&bytes).err(), check_flags(&bytes, true).err());
+
+        // Infinity with non-zero x
+        let mut infinity = compress_g1(&GENERATORG1);
+        infinity[0] |= INFINITY_FLAG;
+        assert_eq!(decompress_g1(&infinity).err(), check_flags(&infinity, true).err());
+
+        // x = p
+        let mut too_large = MODULUS_BYTES;
+        too_large[0] |= COMPRESSION_FLAG;
+        assert_eq!(decompress_g1(&too_large), Err(DecodeError::FieldElementTooLarge));
+
+        // x = 1 has x^3 + 4 = 5 which is not a square
+        let mut not_on_curve = [0u8; G1_BYTES];
+        not_on_curve[0] = COMPRESSION_FLAG;
+        not_on_curve[G1_BYTES - 1] = 1;
+        assert_eq!(decompress_g1(&not_on_curve), Err(DecodeError::NotOnCurve));
+
+        assert_eq!(
+            decompress_g2(&[0u8; G1_BYTES]),
+            Err(DecodeError::InvalidLength { expected: G2_BYTES, actual: G1_BYTES })
+        );
+    }
+}
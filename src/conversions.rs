@@ -0,0 +1,106 @@
+extern crate zeroize;
+
+use self::zeroize::Zeroize;
+use super::aggregates::{AggregatePublicKey, AggregateSignature};
+use super::amcl_utils::{G1_BYTES, G2_BYTES, SECRET_KEY_BYTES};
+use super::errors::DecodeError;
+use super::keys::{PublicKey, SecretKey};
+use super::signature::Signature;
+#[cfg(not(feature = "std"))]
+use core::convert::TryFrom;
+#[cfg(feature = "std")]
+use std::convert::TryFrom;
+
+// Implements TryFrom for byte slices and arrays using `from_bytes()`.
+macro_rules! impl_try_from {
+    ($type: ty, $byte_size: expr, $error: ty) => {
+        impl<'a> TryFrom<&'a [u8]> for $type {
+            type Error = $error;
+
+            fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+                <$type>::from_bytes(bytes)
+            }
+        }
+
+        impl TryFrom<[u8; $byte_size]> for $type {
+            type Error = $error;
+
+            fn try_from(bytes: [u8; $byte_size]) -> Result<Self, Self::Error> {
+                <$type>::from_bytes(&bytes)
+            }
+        }
+    };
+}
+
+impl_try_from!(PublicKey, G1_BYTES, DecodeError);
+impl_try_from!(AggregatePublicKey, G1_BYTES, DecodeError);
+impl_try_from!(Signature, G2_BYTES, DecodeError);
+impl_try_from!(AggregateSignature, G2_BYTES, DecodeError);
+
+impl<'a> TryFrom<&'a [u8]> for SecretKey {
+    type Error = DecodeError;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        SecretKey::from_bytes(bytes)
+    }
+}
+
+// The array is consumed, so the copy is zeroized.
+impl TryFrom<[u8; SECRET_KEY_BYTES]> for SecretKey {
+    type Error = DecodeError;
+
+    fn try_from(mut bytes: [u8; SECRET_KEY_BYTES]) -> Result<Self, Self::Error> {
+        let secret_key = SecretKey::from_bytes(&bytes);
+        bytes.zeroize();
+        secret_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::keys::Keypair;
+    use super::*;
+
+    #[test]
+    fn test_try_from() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signature = Signature::new(&[42u8; 32], &keypair.sk);
+
+        let bytes = keypair.sk.as_bytes();
+        assert_eq!(SecretKey::try_from(&bytes[..]).unwrap(), keypair.sk);
+        assert_eq!(SecretKey::try_from(bytes).unwrap(), keypair.sk);
+
+        let bytes = keypair.pk.as_bytes();
+        assert_eq!(PublicKey::try_from(&bytes[..]).unwrap(), keypair.pk);
+        assert_eq!(PublicKey::try_from(bytes).unwrap(), keypair.pk);
+        assert_eq!(AggregatePublicKey::try_from(bytes).unwrap().point, keypair.pk.point);
+
+        let bytes = signature.as_bytes();
+        assert_eq!(Signature::try_from(&bytes[..]).unwrap(), signature);
+        assert_eq!(Signature::try_from(bytes).unwrap(), signature);
+        assert_eq!(AggregateSignature::try_from(&bytes[..]).unwrap().point, signature.point);
+    }
+
+    #[test]
+    fn test_try_from_invalid() {
+        assert_eq!(SecretKey::try_from([0u8; 32]), Err(DecodeError::InvalidSecretKey));
+        assert_eq!(
+            SecretKey::try_from(&[1u8; 31][..]),
+            Err(DecodeError::InvalidLength { expected: 32, actual: 31 })
+        );
+        assert_eq!(
+            PublicKey::try_from(&[0u8; 47][..]),
+            Err(DecodeError::InvalidLength { expected: 48, actual: 47 })
+        );
+        assert!(Signature::try_from([0u8; 96]).is_err());
+
+        // Generic code can use the standard conversion traits
+        fn decode<T: for<'a> TryFrom<&'a [u8]>>(bytes: &[u8]) -> Option<T> {
+            T::try_from(bytes).ok()
+        }
+        assert!(decode::<PublicKey>(&[0u8; 48]).is_none());
+        assert!(decode::<AggregateSignature>(&[0u8; 96]).is_none());
+    }
+}
@@ -0,0 +1,165 @@
+//! Verification of drand randomness beacons signed by a threshold group key.
+//!
+//! Supports the `pedersen-bls-chained` and `pedersen-bls-unchained` schemes, which sign on G2,
+//! and the `bls-unchained-g1-rfc9380` scheme (quicknet), which signs on G1.
+
+extern crate amcl;
+
+use super::amcl_utils::{
+    ate2_evaluation, decompress_g1, decompress_g2, hash_to_curve_g1_with_dst, NEGATIVE_GENERATORG2,
+};
+use super::keys::PublicKey;
+use super::signature::Signature;
+use amcl::hash256::HASH256;
+
+/// Domain separation tag of drand beacons which sign on G2 (`pedersen-bls-chained` and
+/// `pedersen-bls-unchained`).
+pub const DST_G2: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+/// Domain separation tag of drand beacons which sign on G1 (`bls-unchained-g1-rfc9380`).
+pub const DST_G1: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// The message signed by a chained beacon: `sha256(previous_signature || round)`.
+pub fn chained_message(previous_signature: &[u8], round: u64) -> [u8; 32] {
+    let mut hash256 = HASH256::new();
+    hash256.init();
+    hash256.process_array(previous_signature);
+    hash256.process_array(&round.to_be_bytes());
+    hash256.hash()
+}
+
+/// The message signed by an unchained beacon: `sha256(round)`.
+pub fn unchained_message(round: u64) -> [u8; 32] {
+    let mut hash256 = HASH256::new();
+    hash256.init();
+    hash256.process_array(&round.to_be_bytes());
+    hash256.hash()
+}
+
+/// Verifies a chained beacon, where the signature of each round covers the signature of the
+/// previous round.
+///
+/// The group key is on G1 and the signature on G2 (`pedersen-bls-chained`).
+pub fn verify_chained(
+    group_key: &PublicKey,
+    round: u64,
+    previous_signature: &[u8],
+    signature: &Signature,
+) -> bool {
+    let message = chained_message(previous_signature, round);
+    signature.verify_with_dst(&message, group_key, DST_G2)
+}
+
+/// Verifies an unchained beacon, where the signature only covers the round number.
+///
+/// The group key is on G1 and the signature on G2 (`pedersen-bls-unchained`).
+pub fn verify_unchained(group_key: &PublicKey, round: u64, signature: &Signature) -> bool {
+    let message = unchained_message(round);
+    signature.verify_with_dst(&message, group_key, DST_G2)
+}
+
+/// Verifies an unchained beacon with swapped groups, where the group key is a compressed G2
+/// point and the signature a compressed G1 point (`bls-unchained-g1-rfc9380`).
+pub fn verify_unchained_g1(group_key: &[u8], round: u64, signature: &[u8]) -> bool {
+    let mut group_key = match decompress_g2(group_key) {
+        Ok(point) => point,
+        Err(_) => return false,
+    };
+    let mut signature = match decompress_g1(signature) {
+        Ok(point) => point,
+        Err(_) => return false,
+    };
+
+    // Group key must not be infinity
+    if group_key.is_infinity() {
+        return false;
+    }
+
+    let mut msg_hash_point = hash_to_curve_g1_with_dst(&unchained_message(round), DST_G1);
+
+    // Points must be affine for pairing
+    msg_hash_point.affine();
+    group_key.affine();
+    signature.affine();
+
+    // Faster ate2 evaualtion checks e(-G2, S) * e(PK, H) == 1
+    ate2_evaluation(&NEGATIVE_GENERATORG2, &signature, &group_key, &msg_hash_point)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate hex;
+    extern crate rand;
+
+    use super::super::amcl_utils::{compress_g1, compress_g2, g1mul, g2mul, GroupG2};
+    use super::super::keys::Keypair;
+    use super::*;
+
+    #[test]
+    fn test_verify_chained() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+
+        let previous_signature = Signature::new_with_dst(b"genesis", &keypair.sk, DST_G2);
+        let previous_signature_bytes = previous_signature.as_bytes();
+        let message = chained_message(&previous_signature_bytes, 2);
+        let signature = Signature::new_with_dst(&message, &keypair.sk, DST_G2);
+
+        assert!(verify_chained(&keypair.pk, 2, &previous_signature_bytes, &signature));
+
+        // Wrong round or previous signature should fail
+        assert!(!verify_chained(&keypair.pk, 3, &previous_signature_bytes, &signature));
+        assert!(!verify_chained(&keypair.pk, 2, &[0; 96], &signature));
+        // Chained signatures do not verify as unchained
+        assert!(!verify_unchained(&keypair.pk, 2, &signature));
+    }
+
+    #[test]
+    fn test_verify_unchained() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+
+        let signature = Signature::new_with_dst(&unchained_message(7), &keypair.sk, DST_G2);
+
+        assert!(verify_unchained(&keypair.pk, 7, &signature));
+        assert!(!verify_unchained(&keypair.pk, 8, &signature));
+
+        let other_keypair = Keypair::random(&mut rand::thread_rng());
+        assert!(!verify_unchained(&other_keypair.pk, 7, &signature));
+    }
+
+    #[test]
+    fn test_verify_unchained_g1() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let group_key = compress_g2(&g2mul(&GroupG2::generator(), keypair.sk.as_raw()));
+
+        let msg_hash_point = hash_to_curve_g1_with_dst(&unchained_message(7), DST_G1);
+        let signature = compress_g1(&g1mul(&msg_hash_point, keypair.sk.as_raw()));
+
+        assert!(verify_unchained_g1(&group_key, 7, &signature));
+        assert!(!verify_unchained_g1(&group_key, 8, &signature));
+
+        // Malformed inputs
+        assert!(!verify_unchained_g1(&group_key[1..], 7, &signature));
+        assert!(!verify_unchained_g1(&group_key, 7, &signature[1..]));
+    }
+
+    // drand mainnet (`pedersen-bls-chained`), https://api.drand.sh/public/72785
+    #[test]
+    fn test_verify_chained_mainnet() {
+        let group_key = PublicKey::from_bytes(&hex::decode("868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31").unwrap()).unwrap();
+        let previous_signature = hex::decode("a609e19a03c2fcc559e8dae14900aaefe517cb55c840f6e69bc8e4f66c8d18e8a609685d9917efbfb0c37f058c2de88f13d297c7e19e0ab24813079efe57a182554ff054c7638153f9b26a60e7111f71a0ff63d9571704905d3ca6df0b031747").unwrap();
+        let signature = Signature::from_bytes(&hex::decode("82f5d3d2de4db19d40a6980e8aa37842a0e55d1df06bd68bddc8d60002e8e959eb9cfa368b3c1b77d18f02a54fe047b80f0989315f83b12a74fd8679c4f12aae86eaf6ab5690b34f1fddd50ee3cc6f6cdf59e95526d5a5d82aaa84fa6f181e42").unwrap()).unwrap();
+
+        assert!(verify_chained(&group_key, 72785, &previous_signature, &signature));
+        assert!(!verify_chained(&group_key, 72786, &previous_signature, &signature));
+    }
+
+    // drand quicknet (`bls-unchained-g1-rfc9380`),
+    // https://api.drand.sh/52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971/public/1000
+    #[test]
+    fn test_verify_unchained_g1_quicknet() {
+        let group_key = hex::decode("83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a").unwrap();
+        let signature = hex::decode("b44679b9a59af2ec876b1a6b1ad52ea9b1615fc3982b19576350f93447cb1125e342b73a8dd2bacbe47e4b6b63ed5e39").unwrap();
+
+        assert!(verify_unchained_g1(&group_key, 1000, &signature));
+        assert!(!verify_unchained_g1(&group_key, 1001, &signature));
+    }
+}
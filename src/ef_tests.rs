@@ -0,0 +1,194 @@
+//! Ethereum consensus spec BLS tests, enabled by the `ef_tests` feature.
+//!
+//! The vectors are read from the `general` suite of a consensus-specs release, found at
+//! `EF_TESTS_DIR` or else `ef-tests/tests/general` in the crate root, see the README.
+//! https://github.com/ethereum/consensus-specs/tree/dev/tests/formats/bls
+
+extern crate hex;
+
+use super::aggregates::{AggregatePublicKey, AggregateSignature};
+use super::keys::{PublicKey, SecretKey};
+use super::signature::Signature;
+use serde_yaml::Value;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+// The name and data.yaml of every case of a handler, e.g. ("phase0", "sign").
+fn test_cases(fork: &str, handler: &str) -> Vec<(String, Value)> {
+    let root = env::var_os("EF_TESTS_DIR").map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("ef-tests").join("tests").join("general")
+    });
+    let dir = root.join(fork).join("bls").join(handler).join("bls");
+    let entries = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("missing test vectors at {}: {}", dir.display(), e));
+
+    let mut cases: Vec<(String, Value)> = entries
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let data = fs::read_to_string(path.join("data.yaml")).unwrap();
+            (name, serde_yaml::from_str(&data).unwrap())
+        })
+        .collect();
+    assert!(!cases.is_empty(), "no test vectors at {}", dir.display());
+    cases.sort_by(|a, b| a.0.cmp(&b.0));
+    cases
+}
+
+// Runs every case of a handler, reporting all of the failures together.
+fn run_cases<F: Fn(&Value) -> bool>(fork: &str, handler: &str, case_passes: F) {
+    let failures: Vec<String> = test_cases(fork, handler)
+        .into_iter()
+        .filter(|(_, data)| !case_passes(data))
+        .map(|(name, _)| name)
+        .collect();
+    assert!(failures.is_empty(), "{} {} failed: {:?}", fork, handler, failures);
+}
+
+// A 0x prefixed hex string.
+fn bytes(value: &Value) -> Vec<u8> {
+    let string = value.as_str().expect("hex string");
+    hex::decode(string.trim_start_matches("0x")).expect("valid hex")
+}
+
+fn bytes_list(value: &Value) -> Vec<Vec<u8>> {
+    value.as_sequence().expect("list").iter().map(bytes).collect()
+}
+
+// The expected output, null where the inputs are invalid.
+fn optional_bytes(value: &Value) -> Option<Vec<u8>> {
+    if value.is_null() {
+        None
+    } else {
+        Some(bytes(value))
+    }
+}
+
+fn public_keys(bytes: &[Vec<u8>]) -> Option<Vec<PublicKey>> {
+    bytes.iter().map(|bytes| PublicKey::from_bytes(bytes).ok()).collect()
+}
+
+#[test]
+fn test_ef_sign() {
+    run_cases("phase0", "sign", |data| {
+        let input = &data["input"];
+        let signature = SecretKey::from_bytes(&bytes(&input["privkey"]))
+            .ok()
+            .map(|sk| Signature::new(&bytes(&input["message"]), &sk).as_bytes().to_vec());
+        signature == optional_bytes(&data["output"])
+    });
+}
+
+#[test]
+fn test_ef_verify() {
+    run_cases("phase0", "verify", |data| {
+        let input = &data["input"];
+        let valid = match (
+            PublicKey::from_bytes(&bytes(&input["pubkey"])),
+            Signature::from_bytes(&bytes(&input["signature"])),
+        ) {
+            (Ok(pk), Ok(sig)) => sig.verify(&bytes(&input["message"]), &pk),
+            _ => false,
+        };
+        Some(valid) == data["output"].as_bool()
+    });
+}
+
+#[test]
+fn test_ef_aggregate() {
+    run_cases("phase0", "aggregate", |data| {
+        let signatures: Option<Vec<Signature>> = bytes_list(&data["input"])
+            .iter()
+            .map(|bytes| Signature::from_bytes(bytes).ok())
+            .collect();
+        let aggregate = signatures.filter(|signatures| !signatures.is_empty()).map(|signatures| {
+            let signatures: Vec<&Signature> = signatures.iter().collect();
+            AggregateSignature::aggregate(&signatures).as_bytes().to_vec()
+        });
+        aggregate == optional_bytes(&data["output"])
+    });
+}
+
+#[test]
+fn test_ef_fast_aggregate_verify() {
+    run_cases("phase0", "fast_aggregate_verify", |data| {
+        let input = &data["input"];
+        let valid = match (
+            public_keys(&bytes_list(&input["pubkeys"])),
+            AggregateSignature::from_bytes(&bytes(&input["signature"])),
+        ) {
+            (Some(pks), Ok(sig)) => {
+                let pks: Vec<&PublicKey> = pks.iter().collect();
+                sig.fast_aggregate_verify(&bytes(&input["message"]), &pks)
+            }
+            _ => false,
+        };
+        Some(valid) == data["output"].as_bool()
+    });
+}
+
+#[test]
+fn test_ef_aggregate_verify() {
+    run_cases("phase0", "aggregate_verify", |data| {
+        let input = &data["input"];
+        let messages = bytes_list(&input["messages"]);
+        let valid = match (
+            public_keys(&bytes_list(&input["pubkeys"])),
+            AggregateSignature::from_bytes(&bytes(&input["signature"])),
+        ) {
+            (Some(pks), Ok(sig)) => {
+                let pks: Vec<&PublicKey> = pks.iter().collect();
+                let msgs: Vec<&[u8]> = messages.iter().map(|msg| msg.as_slice()).collect();
+                sig.aggregate_verify(&msgs, &pks)
+            }
+            _ => false,
+        };
+        Some(valid) == data["output"].as_bool()
+    });
+}
+
+#[test]
+fn test_ef_deserialization_g1() {
+    run_cases("phase0", "deserialization_G1", |data| {
+        let valid = PublicKey::from_bytes(&bytes(&data["input"]["pubkey"])).is_ok();
+        Some(valid) == data["output"].as_bool()
+    });
+}
+
+#[test]
+fn test_ef_deserialization_g2() {
+    run_cases("phase0", "deserialization_G2", |data| {
+        let valid = Signature::from_bytes(&bytes(&data["input"]["signature"])).is_ok();
+        Some(valid) == data["output"].as_bool()
+    });
+}
+
+#[test]
+fn test_ef_eth_aggregate_pubkeys() {
+    run_cases("altair", "eth_aggregate_pubkeys", |data| {
+        let aggregate = public_keys(&bytes_list(&data["input"])).and_then(|pks| {
+            let pks: Vec<&PublicKey> = pks.iter().collect();
+            AggregatePublicKey::aggregate(&pks).ok().map(|apk| apk.as_bytes().to_vec())
+        });
+        aggregate == optional_bytes(&data["output"])
+    });
+}
+
+#[test]
+fn test_ef_eth_fast_aggregate_verify() {
+    run_cases("altair", "eth_fast_aggregate_verify", |data| {
+        let input = &data["input"];
+        let valid = match (
+            public_keys(&bytes_list(&input["pubkeys"])),
+            AggregateSignature::from_bytes(&bytes(&input["signature"])),
+        ) {
+            (Some(pks), Ok(sig)) => {
+                let pks: Vec<&PublicKey> = pks.iter().collect();
+                sig.eth_fast_aggregate_verify(&bytes(&input["message"]), &pks)
+            }
+            _ => false,
+        };
+        Some(valid) == data["output"].as_bool()
+    });
+}
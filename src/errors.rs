@@ -0,0 +1,212 @@
+use super::amcl_utils::AmclError;
+#[cfg(not(feature = "std"))]
+use core::fmt;
+#[cfg(feature = "std")]
+use std::{error, fmt};
+
+/// Errors which may occur when decoding PublicKeys and Signatures from bytes or strings.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum DecodeError {
+    /// The input is not of the expected length.
+    InvalidLength { expected: usize, actual: usize },
+    /// A flag in the first byte of the encoding is invalid.
+    InvalidFlag(EncodingFlag),
+    /// A coordinate of the point is not less than the field modulus.
+    FieldElementTooLarge,
+    /// The point does not satisfy the curve equation.
+    NotOnCurve,
+    /// The point is on the curve but not in the prime order subgroup.
+    NotInSubgroup,
+    /// The point is the point at infinity, which is not a valid PublicKey.
+    Infinity,
+    /// The input is not a valid hex string.
+    InvalidHex,
+    /// The input is not a valid base64 string.
+    InvalidBase64,
+    /// The SecretKey is zero or not less than the curve order.
+    InvalidSecretKey,
+}
+
+/// The flags stored in the three most significant bits of an encoded point.
+/// See https://github.com/zkcrypto/pairing/blob/master/src/bls12_381/README.md#serialization
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum EncodingFlag {
+    /// Set for compressed encodings and unset for uncompressed encodings.
+    Compression,
+    /// Set for the point at infinity, in which case all other bits must be zero.
+    Infinity,
+    /// Selects the larger y coordinate, only allowed in compressed encodings.
+    Sort,
+}
+
+impl From<AmclError> for DecodeError {
+    // Lengths and flags are checked before deserializing, remaining errors are invalid points.
+    fn from(error: AmclError) -> Self {
+        match error {
+            AmclError::InvalidYFlag => DecodeError::InvalidFlag(EncodingFlag::Sort),
+            _ => DecodeError::NotOnCurve,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength { expected, actual } => {
+                write!(f, "invalid length: expected {} bytes, got {}", expected, actual)
+            }
+            DecodeError::InvalidFlag(flag) => write!(f, "invalid {} flag", flag),
+            DecodeError::FieldElementTooLarge => {
+                write!(f, "field element is not less than the modulus")
+            }
+            DecodeError::NotOnCurve => write!(f, "point is not on the curve"),
+            DecodeError::NotInSubgroup => write!(f, "point is not in the prime order subgroup"),
+            DecodeError::Infinity => write!(f, "point is the point at infinity"),
+            DecodeError::InvalidHex => write!(f, "invalid hex string"),
+            DecodeError::InvalidBase64 => write!(f, "invalid base64 string"),
+            DecodeError::InvalidSecretKey => {
+                write!(f, "secret key is zero or not less than the curve order")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for DecodeError {}
+
+/// The reasons a Signature may fail verification.
+///
+/// Distinguishes malformed inputs from well formed Signatures which do not verify, e.g. to
+/// score peers differently.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum VerifyError {
+    /// The Signature is well formed but does not verify.
+    InvalidSignature,
+    /// The Signature is not in the prime order subgroup.
+    SignatureNotInSubgroup,
+    /// A PublicKey, or the aggregate of the PublicKeys, is the point at infinity.
+    InvalidPublicKey,
+    /// No PublicKeys were provided.
+    EmptyInput,
+    /// The number of messages differs from the number of PublicKeys.
+    LengthMismatch,
+    /// A Signature or PublicKey could not be decoded.
+    Decode(DecodeError),
+}
+
+impl From<DecodeError> for VerifyError {
+    fn from(error: DecodeError) -> Self {
+        VerifyError::Decode(error)
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerifyError::InvalidSignature => write!(f, "signature does not verify"),
+            VerifyError::SignatureNotInSubgroup => {
+                write!(f, "signature is not in the prime order subgroup")
+            }
+            VerifyError::InvalidPublicKey => write!(f, "public key is the point at infinity"),
+            VerifyError::EmptyInput => write!(f, "no public keys"),
+            VerifyError::LengthMismatch => {
+                write!(f, "number of messages differs from number of public keys")
+            }
+            VerifyError::Decode(error) => write!(f, "decoding failed: {}", error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for VerifyError {}
+
+/// Errors which may occur when committing to, opening or verifying KZG polynomial commitments.
+#[cfg(feature = "kzg")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum KzgError {
+    /// A commitment or proof is not a valid point.
+    InvalidPoint(DecodeError),
+    /// A field element is not less than the curve order.
+    InvalidFieldElement,
+    /// The blob is not of the expected length.
+    InvalidBlobLength { expected: usize, actual: usize },
+    /// The trusted setup is malformed or inconsistent.
+    InvalidTrustedSetup,
+    /// The batched inputs are not all of the same length.
+    LengthMismatch,
+}
+
+#[cfg(feature = "kzg")]
+impl From<DecodeError> for KzgError {
+    fn from(error: DecodeError) -> Self {
+        KzgError::InvalidPoint(error)
+    }
+}
+
+#[cfg(feature = "kzg")]
+impl fmt::Display for KzgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KzgError::InvalidPoint(error) => write!(f, "invalid point: {}", error),
+            KzgError::InvalidFieldElement => {
+                write!(f, "field element is not less than the curve order")
+            }
+            KzgError::InvalidBlobLength { expected, actual } => {
+                write!(f, "invalid blob length: expected {} bytes, got {}", expected, actual)
+            }
+            KzgError::InvalidTrustedSetup => write!(f, "invalid trusted setup"),
+            KzgError::LengthMismatch => write!(f, "inputs are not of the same length"),
+        }
+    }
+}
+
+#[cfg(all(feature = "kzg", feature = "std"))]
+impl error::Error for KzgError {}
+
+impl fmt::Display for EncodingFlag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodingFlag::Compression => write!(f, "compression"),
+            EncodingFlag::Infinity => write!(f, "infinity"),
+            EncodingFlag::Sort => write!(f, "sort"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_error_display() {
+        assert_eq!(
+            DecodeError::InvalidLength { expected: 48, actual: 47 }.to_string(),
+            "invalid length: expected 48 bytes, got 47"
+        );
+        assert_eq!(
+            DecodeError::InvalidFlag(EncodingFlag::Compression).to_string(),
+            "invalid compression flag"
+        );
+        assert_eq!(
+            DecodeError::NotInSubgroup.to_string(),
+            "point is not in the prime order subgroup"
+        );
+
+        // Usable as a boxed std Error
+        let error: Box<dyn error::Error> = Box::new(DecodeError::NotOnCurve);
+        assert_eq!(error.to_string(), "point is not on the curve");
+    }
+
+    #[test]
+    fn test_verify_error_display() {
+        assert_eq!(VerifyError::InvalidSignature.to_string(), "signature does not verify");
+        assert_eq!(
+            VerifyError::from(DecodeError::NotOnCurve).to_string(),
+            "decoding failed: point is not on the curve"
+        );
+    }
+}
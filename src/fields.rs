@@ -0,0 +1,476 @@
+//! Wrappers over the base field Fp and its extensions Fp2 and Fp12 of BLS12-381.
+//!
+//! Values are kept fully reduced, so equality and encodings do not depend on amcl's lazy
+//! reduction.
+
+use super::amcl_utils::{Big, FP12, FP2, MODBYTES};
+use super::errors::DecodeError;
+#[cfg(not(feature = "std"))]
+use core::fmt;
+#[cfg(feature = "std")]
+use std::fmt;
+use BLSCurve::fp::FP;
+use BLSCurve::rom::MODULUS;
+
+/// The length of an encoded Fp element.
+pub const FP_BYTES: usize = MODBYTES;
+/// The length of an encoded Fp2 element.
+pub const FP2_BYTES: usize = FP_BYTES * 2;
+/// The length of an encoded Fp12 element.
+pub const FP12_BYTES: usize = FP_BYTES * 12;
+
+// Decodes big-endian bytes, which must be less than the modulus p.
+fn big_from_bytes(bytes: &[u8]) -> Result<Big, DecodeError> {
+    let x = Big::from_bytes(bytes);
+    if Big::comp(&x, &Big::new_ints(&MODULUS)) >= 0 {
+        return Err(DecodeError::FieldElementTooLarge);
+    }
+    Ok(x)
+}
+
+fn check_length(bytes: &[u8], expected: usize) -> Result<(), DecodeError> {
+    if bytes.len() != expected {
+        return Err(DecodeError::InvalidLength { expected, actual: bytes.len() });
+    }
+    Ok(())
+}
+
+fn write_hex(f: &mut fmt::Formatter, name: &str, bytes: &[u8]) -> fmt::Result {
+    write!(f, "{}(0x", name)?;
+    for byte in bytes {
+        write!(f, "{:02x}", byte)?;
+    }
+    write!(f, ")")
+}
+
+/// An element of the base field, an integer modulo p.
+#[derive(Clone)]
+pub struct Fp(pub(crate) FP);
+
+impl Fp {
+    pub(crate) fn reduced(mut x: FP) -> Self {
+        x.reduce();
+        Fp(x)
+    }
+
+    /// The element 0.
+    pub fn zero() -> Self {
+        Fp(FP::new())
+    }
+
+    /// The element 1.
+    pub fn one() -> Self {
+        Fp(FP::new_int(1))
+    }
+
+    /// Instantiate an Fp from 48 big-endian bytes, which must be less than p.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        check_length(bytes, FP_BYTES)?;
+        Ok(Fp(FP::new_big(&big_from_bytes(bytes)?)))
+    }
+
+    /// Export the Fp as 48 big-endian bytes.
+    pub fn as_bytes(&self) -> [u8; FP_BYTES] {
+        let mut bytes = [0u8; FP_BYTES];
+        self.0.redc().to_bytes(&mut bytes);
+        bytes
+    }
+
+    /// Returns true if the element is 0.
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zilch()
+    }
+
+    /// self + other.
+    pub fn add(&self, other: &Fp) -> Fp {
+        let mut x = self.0.clone();
+        x.add(&other.0);
+        Fp::reduced(x)
+    }
+
+    /// self - other.
+    pub fn sub(&self, other: &Fp) -> Fp {
+        let mut x = self.0.clone();
+        x.sub(&other.0);
+        Fp::reduced(x)
+    }
+
+    /// self * other.
+    pub fn mul(&self, other: &Fp) -> Fp {
+        let mut x = self.0.clone();
+        x.mul(&other.0);
+        Fp::reduced(x)
+    }
+
+    /// self * self.
+    pub fn square(&self) -> Fp {
+        let mut x = self.0.clone();
+        x.sqr();
+        Fp::reduced(x)
+    }
+
+    /// -self.
+    pub fn neg(&self) -> Fp {
+        let mut x = self.0.clone();
+        x.neg();
+        Fp::reduced(x)
+    }
+
+    /// The multiplicative inverse, or None for 0.
+    pub fn invert(&self) -> Option<Fp> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut x = self.0.clone();
+        x.inverse();
+        Some(Fp::reduced(x))
+    }
+
+    /// A square root, or None if the element is not a square.
+    ///
+    /// Which of the two roots is returned is not specified.
+    pub fn sqrt(&self) -> Option<Fp> {
+        let root = Fp::reduced(self.0.sqrt());
+        if root.square() != *self {
+            return None;
+        }
+        Some(root)
+    }
+}
+
+impl PartialEq for Fp {
+    fn eq(&self, other: &Fp) -> bool {
+        self.0.equals(&other.0)
+    }
+}
+
+impl Eq for Fp {}
+
+impl fmt::Debug for Fp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_hex(f, "Fp", &self.as_bytes())
+    }
+}
+
+/// An element of the quadratic extension Fp2 = Fp[u] / (u^2 + 1), c0 + c1 * u.
+#[derive(Clone)]
+pub struct Fp2(pub(crate) FP2);
+
+impl Fp2 {
+    pub(crate) fn reduced(mut x: FP2) -> Self {
+        x.reduce();
+        Fp2(x)
+    }
+
+    /// The element 0.
+    pub fn zero() -> Self {
+        Fp2(FP2::new())
+    }
+
+    /// The element 1.
+    pub fn one() -> Self {
+        Fp2(FP2::new_int(1))
+    }
+
+    /// Instantiate an Fp2 as c0 + c1 * u.
+    pub fn new(c0: &Fp, c1: &Fp) -> Self {
+        Fp2(FP2::new_fps(&c0.0, &c1.0))
+    }
+
+    /// The coefficient c0.
+    pub fn c0(&self) -> Fp {
+        Fp(FP::new_big(&self.0.geta()))
+    }
+
+    /// The coefficient c1.
+    pub fn c1(&self) -> Fp {
+        Fp(FP::new_big(&self.0.getb()))
+    }
+
+    /// Instantiate an Fp2 from 96 bytes, c1 then c0 as in the encodings of G2 points.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        check_length(bytes, FP2_BYTES)?;
+        let c1 = big_from_bytes(&bytes[..FP_BYTES])?;
+        let c0 = big_from_bytes(&bytes[FP_BYTES..])?;
+        Ok(Fp2(FP2::new_bigs(&c0, &c1)))
+    }
+
+    /// Export the Fp2 as 96 bytes, c1 then c0.
+    pub fn as_bytes(&self) -> [u8; FP2_BYTES] {
+        let mut bytes = [0u8; FP2_BYTES];
+        bytes[..FP_BYTES].copy_from_slice(&self.c1().as_bytes());
+        bytes[FP_BYTES..].copy_from_slice(&self.c0().as_bytes());
+        bytes
+    }
+
+    /// Returns true if the element is 0.
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zilch()
+    }
+
+    /// self + other.
+    pub fn add(&self, other: &Fp2) -> Fp2 {
+        let mut x = self.0.clone();
+        x.add(&other.0);
+        Fp2::reduced(x)
+    }
+
+    /// self - other.
+    pub fn sub(&self, other: &Fp2) -> Fp2 {
+        let mut x = self.0.clone();
+        x.sub(&other.0);
+        Fp2::reduced(x)
+    }
+
+    /// self * other.
+    pub fn mul(&self, other: &Fp2) -> Fp2 {
+        let mut x = self.0.clone();
+        x.mul(&other.0);
+        Fp2::reduced(x)
+    }
+
+    /// self * self.
+    pub fn square(&self) -> Fp2 {
+        let mut x = self.0.clone();
+        x.sqr();
+        Fp2::reduced(x)
+    }
+
+    /// -self.
+    pub fn neg(&self) -> Fp2 {
+        let mut x = self.0.clone();
+        x.neg();
+        Fp2::reduced(x)
+    }
+
+    /// The conjugate c0 - c1 * u.
+    pub fn conjugate(&self) -> Fp2 {
+        let mut x = self.0.clone();
+        x.conj();
+        Fp2::reduced(x)
+    }
+
+    /// The multiplicative inverse, or None for 0.
+    pub fn invert(&self) -> Option<Fp2> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut x = self.0.clone();
+        x.inverse();
+        Some(Fp2::reduced(x))
+    }
+
+    /// A square root, or None if the element is not a square.
+    ///
+    /// Which of the two roots is returned is not specified.
+    pub fn sqrt(&self) -> Option<Fp2> {
+        let mut x = self.0.clone();
+        if !x.sqrt() {
+            return None;
+        }
+        Some(Fp2::reduced(x))
+    }
+}
+
+impl PartialEq for Fp2 {
+    fn eq(&self, other: &Fp2) -> bool {
+        self.0.equals(&other.0)
+    }
+}
+
+impl Eq for Fp2 {}
+
+impl fmt::Debug for Fp2 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_hex(f, "Fp2", &self.as_bytes())
+    }
+}
+
+/// An element of Fp12, the field containing the pairing outputs.
+#[derive(Clone)]
+pub struct Fp12(pub(crate) FP12);
+
+impl Fp12 {
+    fn reduced(mut x: FP12) -> Self {
+        x.reduce();
+        Fp12(x)
+    }
+
+    /// The element 0.
+    pub fn zero() -> Self {
+        Fp12(FP12::new())
+    }
+
+    /// The element 1.
+    pub fn one() -> Self {
+        Fp12(FP12::new_int(1))
+    }
+
+    /// Instantiate an Fp12 from 576 bytes, the big-endian Fp coefficients in amcl's order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        check_length(bytes, FP12_BYTES)?;
+        for coefficient in bytes.chunks(FP_BYTES) {
+            big_from_bytes(coefficient)?;
+        }
+        Ok(Fp12(FP12::from_bytes(bytes)))
+    }
+
+    /// Export the Fp12 as 576 bytes.
+    pub fn as_bytes(&self) -> [u8; FP12_BYTES] {
+        let mut bytes = [0u8; FP12_BYTES];
+        self.0.to_bytes(&mut bytes);
+        bytes
+    }
+
+    /// Returns true if the element is 0.
+    pub fn is_zero(&self) -> bool {
+        self.0.equals(&FP12::new())
+    }
+
+    /// Returns true if the element is 1.
+    pub fn is_one(&self) -> bool {
+        self.0.is_unity()
+    }
+
+    /// self * other.
+    pub fn mul(&self, other: &Fp12) -> Fp12 {
+        let mut x = self.0.clone();
+        x.mul(&other.0);
+        Fp12::reduced(x)
+    }
+
+    /// self * self.
+    pub fn square(&self) -> Fp12 {
+        let mut x = self.0.clone();
+        x.sqr();
+        Fp12::reduced(x)
+    }
+
+    /// The conjugate over Fp6, which is the inverse of pairing outputs.
+    pub fn conjugate(&self) -> Fp12 {
+        let mut x = self.0.clone();
+        x.conj();
+        Fp12::reduced(x)
+    }
+
+    /// The multiplicative inverse, or None for 0.
+    pub fn invert(&self) -> Option<Fp12> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut x = self.0.clone();
+        x.inverse();
+        Some(Fp12::reduced(x))
+    }
+}
+
+impl PartialEq for Fp12 {
+    fn eq(&self, other: &Fp12) -> bool {
+        self.0.equals(&other.0)
+    }
+}
+
+impl Eq for Fp12 {}
+
+impl fmt::Debug for Fp12 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_hex(f, "Fp12", &self.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate hex;
+
+    use super::super::amcl_utils::{GENERATORG1, GENERATORG2};
+    use super::*;
+    use BLSCurve::pair::{ate, fexp};
+
+    // p - 1
+    const P_MINUS_ONE: &str = "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaaa";
+
+    fn fp(x: isize) -> Fp {
+        Fp(FP::new_int(x))
+    }
+
+    #[test]
+    fn test_fp_arithmetic() {
+        let a = fp(1234);
+        let b = fp(5678);
+        assert_eq!(a.add(&b).sub(&b), a);
+        assert_eq!(a.mul(&b), fp(1234 * 5678));
+        assert_eq!(a.square(), a.mul(&a));
+        assert_eq!(a.add(&a.neg()), Fp::zero());
+        assert_eq!(a.mul(&a.invert().unwrap()), Fp::one());
+        assert!(Fp::zero().invert().is_none());
+        assert!(Fp::zero().is_zero());
+
+        // p = 3 mod 4 so -1 is not a square
+        let root = fp(4).sqrt().unwrap();
+        assert!(root == fp(2) || root == fp(2).neg());
+        assert!(Fp::one().neg().sqrt().is_none());
+    }
+
+    #[test]
+    fn test_fp_bytes() {
+        let a = fp(0x0102);
+        let bytes = a.as_bytes();
+        assert_eq!(&bytes[46..], &[1, 2]);
+        assert_eq!(Fp::from_bytes(&bytes).unwrap(), a);
+
+        let p_minus_one = hex::decode(P_MINUS_ONE).unwrap();
+        assert_eq!(Fp::from_bytes(&p_minus_one).unwrap(), Fp::one().neg());
+        assert_eq!(Fp::one().neg().as_bytes().to_vec(), p_minus_one);
+        let mut p = p_minus_one.clone();
+        p[47] += 1;
+        assert_eq!(Fp::from_bytes(&p), Err(DecodeError::FieldElementTooLarge));
+        assert_eq!(
+            Fp::from_bytes(&p[1..]),
+            Err(DecodeError::InvalidLength { expected: 48, actual: 47 })
+        );
+    }
+
+    #[test]
+    fn test_fp2() {
+        let u = Fp2::new(&Fp::zero(), &Fp::one());
+        assert_eq!(u.square(), Fp2::one().neg());
+        assert_eq!(u.conjugate(), u.neg());
+
+        let a = Fp2::new(&fp(3), &fp(4));
+        assert_eq!(a.c0(), fp(3));
+        assert_eq!(a.c1(), fp(4));
+        assert_eq!(a.mul(&a.conjugate()), Fp2::new(&fp(25), &Fp::zero()));
+        assert_eq!(a.mul(&a.invert().unwrap()), Fp2::one());
+        assert!(Fp2::zero().invert().is_none());
+        assert_eq!(a.sub(&a), Fp2::zero());
+
+        let root = a.square().sqrt().unwrap();
+        assert!(root == a || root == a.neg());
+
+        let bytes = a.as_bytes();
+        assert_eq!(bytes[47], 4);
+        assert_eq!(bytes[95], 3);
+        assert_eq!(Fp2::from_bytes(&bytes).unwrap(), a);
+        let mut bytes = [0u8; FP2_BYTES];
+        bytes[FP_BYTES..].copy_from_slice(&hex::decode(P_MINUS_ONE).unwrap());
+        bytes[FP2_BYTES - 1] += 1;
+        assert_eq!(Fp2::from_bytes(&bytes), Err(DecodeError::FieldElementTooLarge));
+    }
+
+    #[test]
+    fn test_fp12() {
+        let pairing = Fp12(fexp(&ate(&GENERATORG2, &GENERATORG1)));
+        assert!(!pairing.is_one());
+        assert!(Fp12::one().is_one());
+        assert_eq!(pairing.mul(&pairing.invert().unwrap()), Fp12::one());
+        assert_eq!(pairing.conjugate(), pairing.invert().unwrap());
+        assert_eq!(pairing.square(), pairing.mul(&pairing));
+        assert!(Fp12::zero().invert().is_none());
+
+        let bytes = pairing.as_bytes();
+        assert_eq!(Fp12::from_bytes(&bytes).unwrap(), pairing);
+        let mut bytes = [0u8; FP12_BYTES];
+        bytes[..FP_BYTES].copy_from_slice(&[0xff; FP_BYTES]);
+        assert_eq!(Fp12::from_bytes(&bytes), Err(DecodeError::FieldElementTooLarge));
+    }
+}
@@ -0,0 +1,716 @@
+//! Hashing messages to G1 and G2 with a choice of hash function.
+//!
+//! The ciphersuites in this crate hash to the curve using expand_message_xmd with SHA-256,
+//! through this module and without heap allocation. The same construction is provided for other
+//! hash functions, for protocols which standardised on them, see
+//! https://tools.ietf.org/html/draft-irtf-cfrg-hash-to-curve-09#section-5.4.1
+//!
+//! BLAKE2b-512 and Keccak-256 are available behind the `blake2b` and `keccak` features.
+//!
+//! Both the random oracle `hash_to_curve` and the nonuniform `encode_to_curve` are provided.
+//!
+//! With `XmdHash::Sha256` the results are identical to the default ciphersuites.
+
+extern crate amcl;
+
+use super::amcl_utils::{AmclError, Big, GroupG1, GroupG2, FP2};
+use super::fields::{Fp, Fp2};
+pub use super::isogeny::{iso_map_g1, iso_map_g2, sswu_g1, sswu_g2};
+use super::points::{G1Point, G2Point};
+use amcl::hash256::HASH256;
+use amcl::hash512::HASH512;
+#[cfg(feature = "blake2b")]
+use blake2::Blake2b512;
+#[cfg(feature = "keccak")]
+use sha3::{Digest, Keccak256};
+use spin::Lazy;
+use BLSCurve::dbig::DBig;
+use BLSCurve::fp::FP;
+use BLSCurve::rom::MODULUS;
+
+/// Largest digest length, `b_in_bytes`, supported by expand_message_xmd.
+pub const MAX_OUTPUT_BYTES: usize = 64;
+/// Largest input block length, `s_in_bytes`, supported by expand_message_xmd.
+pub const MAX_BLOCK_BYTES: usize = 136;
+
+/// The effective cofactor of G1, h_eff = 1 - x for the curve parameter x = -0xd201000000010000.
+pub const H_EFF_G1: u64 = 0xd201_0000_0001_0001;
+
+// Bytes per field element, L = ceil((ceil(log2(p)) + k) / 8) for k = 128.
+const FIELD_ELEMENT_BYTES: usize = 64;
+
+// The field modulus p, built once rather than for every element hashed.
+static MODULUS_BIG: Lazy<Big> = Lazy::new(|| Big::new_ints(&MODULUS));
+
+/// A hash function which expand_message_xmd can be built on.
+///
+/// Implement this to hash to the curve with a digest not provided by `XmdHash`.
+pub trait HashToCurveHasher {
+    /// The digest length in bytes, `b_in_bytes`, at most `MAX_OUTPUT_BYTES`.
+    fn output_bytes(&self) -> usize;
+
+    /// The input block length in bytes, `s_in_bytes`, at most `MAX_BLOCK_BYTES`.
+    fn block_bytes(&self) -> usize;
+
+    /// Writes the hash of the concatenated parts to `output`, which is `output_bytes()` long.
+    fn digest(&self, parts: &[&[u8]], output: &mut [u8]);
+}
+
+impl<H: HashToCurveHasher + ?Sized> HashToCurveHasher for &H {
+    fn output_bytes(&self) -> usize {
+        (**self).output_bytes()
+    }
+
+    fn block_bytes(&self) -> usize {
+        (**self).block_bytes()
+    }
+
+    fn digest(&self, parts: &[&[u8]], output: &mut [u8]) {
+        (**self).digest(parts, output)
+    }
+}
+
+/// The hash functions provided by this crate.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum XmdHash {
+    /// SHA-256, as used by the default ciphersuites.
+    Sha256,
+    /// SHA-512, where a single digest covers the 64 bytes of each field element.
+    Sha512,
+    /// BLAKE2b-512, as used by Substrate based chains.
+    #[cfg(feature = "blake2b")]
+    Blake2b512,
+    /// Keccak-256, the pre-standard SHA-3 padding used by Ethereum.
+    #[cfg(feature = "keccak")]
+    Keccak256,
+}
+
+impl HashToCurveHasher for XmdHash {
+    fn output_bytes(&self) -> usize {
+        match self {
+            XmdHash::Sha256 => 32,
+            XmdHash::Sha512 => 64,
+            #[cfg(feature = "blake2b")]
+            XmdHash::Blake2b512 => 64,
+            #[cfg(feature = "keccak")]
+            XmdHash::Keccak256 => 32,
+        }
+    }
+
+    fn block_bytes(&self) -> usize {
+        match self {
+            XmdHash::Sha256 => 64,
+            XmdHash::Sha512 => 128,
+            #[cfg(feature = "blake2b")]
+            XmdHash::Blake2b512 => 128,
+            #[cfg(feature = "keccak")]
+            XmdHash::Keccak256 => 136,
+        }
+    }
+
+    fn digest(&self, parts: &[&[u8]], output: &mut [u8]) {
+        match self {
+            XmdHash::Sha256 => {
+                let mut hash256 = HASH256::new();
+                hash256.init();
+                for part in parts {
+                    hash256.process_array(part);
+                }
+                output.copy_from_slice(&hash256.hash());
+            }
+            XmdHash::Sha512 => {
+                let mut hash512 = HASH512::new();
+                hash512.init();
+                for part in parts {
+                    hash512.process_array(part);
+                }
+                output.copy_from_slice(&hash512.hash());
+            }
+            #[cfg(feature = "blake2b")]
+            XmdHash::Blake2b512 => {
+                use blake2::Digest;
+                let mut blake2b = Blake2b512::new();
+                for part in parts {
+                    blake2b.update(part);
+                }
+                output.copy_from_slice(&blake2b.finalize());
+            }
+            #[cfg(feature = "keccak")]
+            XmdHash::Keccak256 => {
+                let mut keccak = Keccak256::new();
+                for part in parts {
+                    keccak.update(part);
+                }
+                output.copy_from_slice(&keccak.finalize());
+            }
+        }
+    }
+}
+
+// Hash of the concatenated parts, written to the start of the output.
+fn digest<H: HashToCurveHasher>(hash: &H, parts: &[&[u8]]) -> [u8; MAX_OUTPUT_BYTES] {
+    let mut output = [0u8; MAX_OUTPUT_BYTES];
+    hash.digest(parts, &mut output[..hash.output_bytes()]);
+    output
+}
+
+/// expand_message_xmd, filling `output` with uniformly random bytes derived from the message.
+///
+/// Domain separation tags longer than 255 bytes are first hashed, as specified.
+/// Errors if the output is longer than 255 digests or 65535 bytes, or if the hash function's
+/// sizes exceed `MAX_OUTPUT_BYTES` or `MAX_BLOCK_BYTES`.
+pub fn expand_message_xmd<H: HashToCurveHasher>(
+    msg: &[u8],
+    dst: &[u8],
+    hash: H,
+    output: &mut [u8],
+) -> Result<(), AmclError> {
+    let b_in_bytes = hash.output_bytes();
+    if b_in_bytes == 0 || b_in_bytes > MAX_OUTPUT_BYTES || hash.block_bytes() > MAX_BLOCK_BYTES {
+        return Err(AmclError::HashToFieldError);
+    }
+    let ell = output.len().div_ceil(b_in_bytes);
+    if ell > 255 || output.len() > 65535 {
+        return Err(AmclError::HashToFieldError);
+    }
+
+    // DST = H("H2C-OVERSIZE-DST-" || DST) for long tags
+    let long_dst;
+    let dst = if dst.len() > 255 {
+        long_dst = digest(&hash, &[b"H2C-OVERSIZE-DST-", dst]);
+        &long_dst[..b_in_bytes]
+    } else {
+        dst
+    };
+    let dst_len = [dst.len() as u8];
+
+    // b_0 = H(Z_pad || msg || l_i_b_str || I2OSP(0, 1) || DST_prime)
+    let z_pad = [0u8; MAX_BLOCK_BYTES];
+    let l_i_b_str = (output.len() as u16).to_be_bytes();
+    let b_0 = digest(&hash, &[&z_pad[..hash.block_bytes()], msg, &l_i_b_str, &[0], dst, &dst_len]);
+
+    expand_from_b_0(&b_0, dst, &hash, output);
+    Ok(())
+}
+
+// The uniform bytes b_1 || b_2 || ... following b_0, for a DST of at most 255 bytes.
+fn expand_from_b_0<H: HashToCurveHasher>(
+    b_0: &[u8; MAX_OUTPUT_BYTES],
+    dst: &[u8],
+    hash: &H,
+    output: &mut [u8],
+) {
+    let b_in_bytes = hash.output_bytes();
+    let dst_len = [dst.len() as u8];
+
+    // b_1 = H(b_0 || I2OSP(1, 1) || DST_prime)
+    let mut b_i = digest(hash, &[&b_0[..b_in_bytes], &[1], dst, &dst_len]);
+
+    for (i, chunk) in output.chunks_mut(b_in_bytes).enumerate() {
+        if i > 0 {
+            // b_i = H(strxor(b_0, b_(i - 1)) || I2OSP(i, 1) || DST_prime)
+            let mut xored = [0u8; MAX_OUTPUT_BYTES];
+            for j in 0..b_in_bytes {
+                xored[j] = b_0[j] ^ b_i[j];
+            }
+            b_i = digest(hash, &[&xored[..b_in_bytes], &[i as u8 + 1], dst, &dst_len]);
+        }
+        chunk.copy_from_slice(&b_i[..chunk.len()]);
+    }
+}
+
+// L uniform bytes reduced to an element of Fp.
+fn fp_element(uniform_bytes: &[u8]) -> FP {
+    FP::new_big(&DBig::from_bytes(uniform_bytes).dmod(&MODULUS_BIG))
+}
+
+// 2 * L uniform bytes reduced to an element of Fp2.
+fn fp2_element(uniform_bytes: &[u8]) -> FP2 {
+    let (c0, c1) = uniform_bytes.split_at(FIELD_ELEMENT_BYTES);
+    FP2::new_fps(&fp_element(c0), &fp_element(c1))
+}
+
+// Two elements of Fp from 2 * L uniform bytes.
+fn fp_pair(uniform_bytes: &[u8; 2 * FIELD_ELEMENT_BYTES]) -> [Fp; 2] {
+    let (u0, u1) = uniform_bytes.split_at(FIELD_ELEMENT_BYTES);
+    [Fp(fp_element(u0)), Fp(fp_element(u1))]
+}
+
+// Two elements of Fp2 from 2 * 2 * L uniform bytes.
+fn fp2_pair(uniform_bytes: &[u8; 2 * 2 * FIELD_ELEMENT_BYTES]) -> [Fp2; 2] {
+    let (u0, u1) = uniform_bytes.split_at(2 * FIELD_ELEMENT_BYTES);
+    [Fp2(fp2_element(u0)), Fp2(fp2_element(u1))]
+}
+
+/// hash_to_field for two elements of Fp.
+///
+/// Errors if the hash function's sizes exceed `MAX_OUTPUT_BYTES` or `MAX_BLOCK_BYTES`.
+pub fn hash_to_field_fp<H: HashToCurveHasher>(
+    msg: &[u8],
+    dst: &[u8],
+    hash: H,
+) -> Result<[Fp; 2], AmclError> {
+    let mut uniform_bytes = [0u8; 2 * FIELD_ELEMENT_BYTES];
+    expand_message_xmd(msg, dst, hash, &mut uniform_bytes)?;
+    Ok(fp_pair(&uniform_bytes))
+}
+
+/// hash_to_field for two elements of Fp2.
+///
+/// Errors if the hash function's sizes exceed `MAX_OUTPUT_BYTES` or `MAX_BLOCK_BYTES`.
+pub fn hash_to_field_fp2<H: HashToCurveHasher>(
+    msg: &[u8],
+    dst: &[u8],
+    hash: H,
+) -> Result<[Fp2; 2], AmclError> {
+    let mut uniform_bytes = [0u8; 2 * 2 * FIELD_ELEMENT_BYTES];
+    expand_message_xmd(msg, dst, hash, &mut uniform_bytes)?;
+    Ok(fp2_pair(&uniform_bytes))
+}
+
+/// map_to_curve for G1.
+///
+/// The simplified SWU map onto the 11-isogenous curve E1', followed by the 11-isogeny to E1.
+/// The two steps are fused in amcl, they are available separately as `sswu_g1` and `iso_map_g1`.
+/// The result is on E1 but not necessarily in G1, see `clear_cofactor_g1`.
+/// https://www.rfc-editor.org/rfc/rfc9380#section-8.8.1
+pub fn map_to_curve_g1(u: &Fp) -> G1Point {
+    G1Point(GroupG1::map2point(&u.0))
+}
+
+/// map_to_curve for G2.
+///
+/// The simplified SWU map onto the 3-isogenous curve E2', followed by the 3-isogeny to E2.
+/// The two steps are fused in amcl, they are available separately as `sswu_g2` and `iso_map_g2`.
+/// The result is on E2 but not necessarily in G2, see `clear_cofactor_g2`.
+/// https://www.rfc-editor.org/rfc/rfc9380#section-8.8.2
+pub fn map_to_curve_g2(u: &Fp2) -> G2Point {
+    G2Point(GroupG2::map2point(&u.0))
+}
+
+/// clear_cofactor for G1, multiplying by `H_EFF_G1`.
+///
+/// The result is in G1 for every point on E1, including points of small order which are cleared
+/// to infinity.
+/// https://www.rfc-editor.org/rfc/rfc9380#section-7
+pub fn clear_cofactor_g1(point: &G1Point) -> G1Point {
+    let mut point = point.0.clone();
+    point.cfp();
+    point.affine();
+    G1Point(point)
+}
+
+/// clear_cofactor for G2, the psi based method of Budroni and Pintore.
+pub fn clear_cofactor_g2(point: &G2Point) -> G2Point {
+    let mut point = point.0.clone();
+    point.cfp();
+    point.affine();
+    G2Point(point)
+}
+
+/// hash_to_curve for G1, using the given hash function for expand_message_xmd.
+///
+/// Errors under the same conditions as `hash_to_field_fp()`.
+pub fn hash_to_curve_g1<H: HashToCurveHasher>(
+    msg: &[u8],
+    dst: &[u8],
+    hash: H,
+) -> Result<GroupG1, AmclError> {
+    Ok(map_pair_to_g1(&hash_to_field_fp(msg, dst, hash)?).0)
+}
+
+/// hash_to_curve for G2, using the given hash function for expand_message_xmd.
+///
+/// Errors under the same conditions as `hash_to_field_fp2()`.
+pub fn hash_to_curve_g2<H: HashToCurveHasher>(
+    msg: &[u8],
+    dst: &[u8],
+    hash: H,
+) -> Result<GroupG2, AmclError> {
+    Ok(map_pair_to_g2(&hash_to_field_fp2(msg, dst, hash)?).0)
+}
+
+/// The random oracle construction for G1 following hash_to_field: both elements are mapped to
+/// the curve, summed and the cofactor cleared.
+///
+/// For suites which derive the field elements themselves, e.g. with another expand_message.
+/// Equal to `hash_to_curve_g1` when `u` is the output of `hash_to_field_fp`.
+/// https://www.rfc-editor.org/rfc/rfc9380#section-3
+pub fn map_pair_to_g1(u: &[Fp; 2]) -> G1Point {
+    clear_cofactor_g1(&map_to_curve_g1(&u[0]).add(&map_to_curve_g1(&u[1])))
+}
+
+/// The random oracle construction for G2 following hash_to_field: both elements are mapped to
+/// the curve, summed and the cofactor cleared.
+///
+/// amcl maps each element separately, there is no combined map of two elements. The sum is
+/// taken before the cofactor is cleared, so the cofactor is cleared only once.
+/// Equal to `hash_to_curve_g2` when `u` is the output of `hash_to_field_fp2`.
+/// https://www.rfc-editor.org/rfc/rfc9380#section-3
+pub fn map_pair_to_g2(u: &[Fp2; 2]) -> G2Point {
+    clear_cofactor_g2(&map_to_curve_g2(&u[0]).add(&map_to_curve_g2(&u[1])))
+}
+
+/// hash_to_curve of a message given in chunks, e.g. a large file, without concatenating them.
+///
+/// Uses expand_message_xmd with SHA-256, as the default ciphersuites, which is the only hash
+/// function hashed incrementally. The result equals hashing the concatenated chunks.
+///
+/// ```
+/// # use milagro_bls::hash_to_curve::{hash_to_curve_g2, MessageHasher, XmdHash};
+/// let dst = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+/// let point = MessageHasher::new(dst).update(b"hello ").update(b"world").finalize_to_g2();
+/// assert!(point.equals(&hash_to_curve_g2(b"hello world", dst, XmdHash::Sha256).unwrap()));
+/// ```
+pub struct MessageHasher<'a> {
+    hash256: HASH256,
+    dst: &'a [u8],
+}
+
+impl<'a> MessageHasher<'a> {
+    /// Start hashing a message with the given domain separation tag.
+    pub fn new(dst: &'a [u8]) -> Self {
+        // b_0 = H(Z_pad || msg || ...
+        let mut hash256 = HASH256::new();
+        hash256.init();
+        hash256.process_array(&[0u8; 64]);
+        MessageHasher { hash256, dst }
+    }
+
+    /// Append the next chunk of the message.
+    pub fn update(mut self, chunk: &[u8]) -> Self {
+        self.hash256.process_array(chunk);
+        self
+    }
+
+    /// Append each chunk of the message in turn.
+    pub fn update_all<'c, I: IntoIterator<Item = &'c [u8]>>(self, chunks: I) -> Self {
+        chunks.into_iter().fold(self, |hasher, chunk| hasher.update(chunk))
+    }
+
+    /// hash_to_curve for G1 of the message.
+    pub fn finalize_to_g1(self) -> GroupG1 {
+        let mut uniform_bytes = [0u8; 2 * FIELD_ELEMENT_BYTES];
+        self.expand(&mut uniform_bytes);
+        map_pair_to_g1(&fp_pair(&uniform_bytes)).0
+    }
+
+    /// hash_to_curve for G2 of the message.
+    pub fn finalize_to_g2(self) -> GroupG2 {
+        let mut uniform_bytes = [0u8; 2 * 2 * FIELD_ELEMENT_BYTES];
+        self.expand(&mut uniform_bytes);
+        map_pair_to_g2(&fp2_pair(&uniform_bytes)).0
+    }
+
+    // expand_message_xmd of the message, completing b_0. The output is at most 255 digests.
+    pub(crate) fn expand(mut self, output: &mut [u8]) {
+        // DST = H("H2C-OVERSIZE-DST-" || DST) for long tags
+        let long_dst;
+        let dst = if self.dst.len() > 255 {
+            long_dst = digest(&XmdHash::Sha256, &[b"H2C-OVERSIZE-DST-", self.dst]);
+            &long_dst[..XmdHash::Sha256.output_bytes()]
+        } else {
+            self.dst
+        };
+
+        // ... || l_i_b_str || I2OSP(0, 1) || DST_prime)
+        self.hash256.process_array(&(output.len() as u16).to_be_bytes());
+        self.hash256.process_array(&[0]);
+        self.hash256.process_array(dst);
+        self.hash256.process_array(&[dst.len() as u8]);
+        let mut b_0 = [0u8; MAX_OUTPUT_BYTES];
+        b_0[..32].copy_from_slice(&self.hash256.hash());
+
+        expand_from_b_0(&b_0, dst, &XmdHash::Sha256, output);
+    }
+}
+
+/// encode_to_curve for G1, the nonuniform encoding of the `_NU_` suites.
+///
+/// Maps a single field element, so costs roughly half of `hash_to_curve_g1`, but the output
+/// distribution is not indistinguishable from random. Only use it where the protocol allows.
+/// Errors under the same conditions as `hash_to_field_fp()`.
+/// https://www.rfc-editor.org/rfc/rfc9380#section-3
+pub fn encode_to_curve_g1<H: HashToCurveHasher>(
+    msg: &[u8],
+    dst: &[u8],
+    hash: H,
+) -> Result<GroupG1, AmclError> {
+    let mut uniform_bytes = [0u8; FIELD_ELEMENT_BYTES];
+    expand_message_xmd(msg, dst, hash, &mut uniform_bytes)?;
+    Ok(clear_cofactor_g1(&map_to_curve_g1(&Fp(fp_element(&uniform_bytes)))).0)
+}
+
+/// encode_to_curve for G2, the nonuniform encoding of the `_NU_` suites.
+///
+/// Maps a single field element, so costs roughly half of `hash_to_curve_g2`, but the output
+/// distribution is not indistinguishable from random. Only use it where the protocol allows.
+/// Errors under the same conditions as `hash_to_field_fp2()`.
+/// https://www.rfc-editor.org/rfc/rfc9380#section-3
+pub fn encode_to_curve_g2<H: HashToCurveHasher>(
+    msg: &[u8],
+    dst: &[u8],
+    hash: H,
+) -> Result<GroupG2, AmclError> {
+    let mut uniform_bytes = [0u8; 2 * FIELD_ELEMENT_BYTES];
+    expand_message_xmd(msg, dst, hash, &mut uniform_bytes)?;
+    Ok(clear_cofactor_g2(&map_to_curve_g2(&Fp2(fp2_element(&uniform_bytes)))).0)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate hex;
+
+    use super::super::amcl_utils::{self, DST_G2, MODBYTES};
+    use super::*;
+
+    #[test]
+    fn test_expand_message_xmd_sha256() {
+        // https://tools.ietf.org/html/draft-irtf-cfrg-hash-to-curve-09#appendix-I.1
+        let dst = b"QUUX-V01-CS02-with-expander";
+        let mut output = [0u8; 32];
+
+        expand_message_xmd(b"", dst, XmdHash::Sha256, &mut output).unwrap();
+        assert_eq!(
+            hex::encode(output),
+            "f659819a6473c1835b25ea59e3d38914c98b374f0970b7e4c92181df928fca88"
+        );
+
+        expand_message_xmd(b"abc", dst, XmdHash::Sha256, &mut output).unwrap();
+        assert_eq!(
+            hex::encode(output),
+            "1c38f7c211ef233367b2420d04798fa4698080a8901021a795a1151775fe4da7"
+        );
+    }
+
+    #[test]
+    fn test_expand_message_xmd_sha512() {
+        // https://www.rfc-editor.org/rfc/rfc9380#appendix-K.3
+        let dst = b"QUUX-V01-CS02-with-expander-SHA512-256";
+        let mut output = [0u8; 32];
+
+        expand_message_xmd(b"", dst, XmdHash::Sha512, &mut output).unwrap();
+        assert_eq!(
+            hex::encode(output),
+            "6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba"
+        );
+
+        expand_message_xmd(b"abc", dst, XmdHash::Sha512, &mut output).unwrap();
+        assert_eq!(
+            hex::encode(output),
+            "0da749f12fbe5483eb066a5f595055679b976e93abe9be6f0f6318bce7aca8dc"
+        );
+
+        // Up to 255 digests of 64 bytes
+        let mut output = [0u8; 255 * 64];
+        assert!(expand_message_xmd(b"abc", dst, XmdHash::Sha512, &mut output).is_ok());
+    }
+
+    #[test]
+    fn test_hash_to_curve_g2_sha512() {
+        let point = hash_to_curve_g2(b"abc", DST_G2, XmdHash::Sha512).unwrap();
+        assert!(amcl_utils::subgroup_check_g2(&point));
+        assert!(!point.equals(&hash_to_curve_g2(b"abc", DST_G2, XmdHash::Sha256).unwrap()));
+    }
+
+    #[cfg(feature = "blake2b")]
+    #[test]
+    fn test_hash_to_curve_g2_blake2b() {
+        let point = hash_to_curve_g2(b"abc", DST_G2, XmdHash::Blake2b512).unwrap();
+        assert!(amcl_utils::subgroup_check_g2(&point));
+        assert!(point.equals(&hash_to_curve_g2(b"abc", DST_G2, XmdHash::Blake2b512).unwrap()));
+        assert!(!point.equals(&hash_to_curve_g2(b"abc", DST_G2, XmdHash::Sha512).unwrap()));
+    }
+
+    #[test]
+    fn test_expand_message_xmd_lengths() {
+        let mut output = [0u8; 255 * 32 + 1];
+        assert_eq!(
+            expand_message_xmd(b"abc", DST_G2, XmdHash::Sha256, &mut output),
+            Err(AmclError::HashToFieldError)
+        );
+        assert!(
+            expand_message_xmd(b"abc", DST_G2, XmdHash::Sha256, &mut output[..255 * 32]).is_ok()
+        );
+
+        // Prefixes of the output are not prefixes of a longer output, the length is hashed
+        let mut short = [0u8; 32];
+        let mut long = [0u8; 64];
+        expand_message_xmd(b"abc", DST_G2, XmdHash::Sha256, &mut short).unwrap();
+        expand_message_xmd(b"abc", DST_G2, XmdHash::Sha256, &mut long).unwrap();
+        assert!(short[..] != long[..32]);
+
+        // Long DSTs are hashed rather than rejected
+        let long_dst = [7u8; 300];
+        assert!(expand_message_xmd(b"abc", &long_dst, XmdHash::Sha256, &mut short).is_ok());
+    }
+
+    // SHA-256 from amcl, implemented outside of XmdHash
+    struct CustomSha256;
+
+    impl HashToCurveHasher for CustomSha256 {
+        fn output_bytes(&self) -> usize {
+            32
+        }
+
+        fn block_bytes(&self) -> usize {
+            64
+        }
+
+        fn digest(&self, parts: &[&[u8]], output: &mut [u8]) {
+            let mut hash256 = HASH256::new();
+            hash256.init();
+            for part in parts {
+                hash256.process_array(part);
+            }
+            output.copy_from_slice(&hash256.hash());
+        }
+    }
+
+    // A digest too large for expand_message_xmd's buffers
+    struct OversizedHash;
+
+    impl HashToCurveHasher for OversizedHash {
+        fn output_bytes(&self) -> usize {
+            MAX_OUTPUT_BYTES + 1
+        }
+
+        fn block_bytes(&self) -> usize {
+            MAX_BLOCK_BYTES
+        }
+
+        fn digest(&self, _parts: &[&[u8]], output: &mut [u8]) {
+            output.iter_mut().for_each(|byte| *byte = 0);
+        }
+    }
+
+    #[test]
+    fn test_custom_hasher() {
+        let mut expected = [0u8; 96];
+        let mut output = [0u8; 96];
+        expand_message_xmd(b"abc", DST_G2, XmdHash::Sha256, &mut expected).unwrap();
+        expand_message_xmd(b"abc", DST_G2, CustomSha256, &mut output).unwrap();
+        assert_eq!(output[..], expected[..]);
+
+        let point = hash_to_curve_g2(b"abc", DST_G2, &CustomSha256).unwrap();
+        assert!(point.equals(&hash_to_curve_g2(b"abc", DST_G2, XmdHash::Sha256).unwrap()));
+
+        assert_eq!(
+            expand_message_xmd(b"abc", DST_G2, OversizedHash, &mut output),
+            Err(AmclError::HashToFieldError)
+        );
+        // Unsupported hash functions error rather than panic
+        assert!(hash_to_curve_g1(b"abc", DST_G2, OversizedHash).is_err());
+        assert!(hash_to_curve_g2(b"abc", DST_G2, OversizedHash).is_err());
+        assert!(encode_to_curve_g2(b"abc", DST_G2, OversizedHash).is_err());
+    }
+
+    #[test]
+    fn test_hash_to_curve_g1_sha256_matches_default() {
+        let dst = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+        for msg in [&b""[..], b"abc", b"abcdef0123456789", &[42u8; 200]] {
+            let point = hash_to_curve_g1(msg, dst, XmdHash::Sha256).unwrap();
+            assert!(point.equals(&amcl_utils::hash_to_curve_g1_with_dst(msg, dst)));
+        }
+    }
+
+    #[test]
+    fn test_map_to_curve() {
+        let u = hash_to_field_fp(b"abc", DST_G2, XmdHash::Sha256).unwrap();
+        let point = clear_cofactor_g1(&map_to_curve_g1(&u[0]).add(&map_to_curve_g1(&u[1])));
+        assert!(point.is_in_subgroup());
+        assert!(point.0.equals(&hash_to_curve_g1(b"abc", DST_G2, XmdHash::Sha256).unwrap()));
+        assert_eq!(point, map_pair_to_g1(&u));
+
+        let u = hash_to_field_fp2(b"abc", DST_G2, XmdHash::Sha256).unwrap();
+        let point = clear_cofactor_g2(&map_to_curve_g2(&u[0]).add(&map_to_curve_g2(&u[1])));
+        assert!(point.is_in_subgroup());
+        assert!(point.0.equals(&hash_to_curve_g2(b"abc", DST_G2, XmdHash::Sha256).unwrap()));
+        assert_eq!(point, map_pair_to_g2(&u));
+    }
+
+    #[test]
+    fn test_clear_cofactor_g1() {
+        let mut h_eff = [0u8; MODBYTES];
+        h_eff[MODBYTES - 8..].copy_from_slice(&H_EFF_G1.to_be_bytes());
+        let h_eff = Big::from_bytes(&h_eff);
+
+        for i in 0..32u8 {
+            let u = hash_to_field_fp(&[i], DST_G2, XmdHash::Sha256).unwrap();
+            for point in u.iter().map(map_to_curve_g1) {
+                let cleared = clear_cofactor_g1(&point);
+                assert!(cleared.is_in_subgroup());
+                assert!(!cleared.is_identity());
+                assert!(cleared.0.equals(&point.0.mul(&h_eff)));
+            }
+        }
+
+        // (0, 2) has order 3, which divides h_eff
+        let mut y = [0u8; MODBYTES];
+        y[MODBYTES - 1] = 2;
+        let point = G1Point(amcl_utils::g1_from_coordinates(&[0u8; MODBYTES], &y).unwrap());
+        assert!(!point.is_in_subgroup());
+        assert!(clear_cofactor_g1(&point).is_identity());
+    }
+
+    #[test]
+    fn test_message_hasher() {
+        let msg = [7u8; 1000];
+        let point = MessageHasher::new(DST_G2).update_all(msg.chunks(33)).finalize_to_g2();
+        assert!(point.equals(&hash_to_curve_g2(&msg, DST_G2, XmdHash::Sha256).unwrap()));
+        assert!(point.equals(&amcl_utils::hash_to_curve_g2(&msg)));
+
+        let empty = MessageHasher::new(DST_G2).finalize_to_g2();
+        assert!(empty.equals(&hash_to_curve_g2(b"", DST_G2, XmdHash::Sha256).unwrap()));
+
+        let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        let point = MessageHasher::new(dst).update(b"ab").update(b"c").finalize_to_g1();
+        assert!(point.equals(&hash_to_curve_g1(b"abc", dst, XmdHash::Sha256).unwrap()));
+
+        // Long domain separation tags are hashed first
+        let long_dst = [b'D'; 300];
+        let point = MessageHasher::new(&long_dst).update(b"abc").finalize_to_g2();
+        assert!(point.equals(&hash_to_curve_g2(b"abc", &long_dst, XmdHash::Sha256).unwrap()));
+
+        let mut expected = [0u8; 64];
+        let mut output = [0u8; 64];
+        expand_message_xmd(b"abc", DST_G2, XmdHash::Sha256, &mut expected).unwrap();
+        MessageHasher::new(DST_G2).update(b"a").update(b"bc").expand(&mut output);
+        assert_eq!(output[..], expected[..]);
+    }
+
+    #[test]
+    fn test_encode_to_curve() {
+        let dst_g1 = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_NU_";
+        let dst_g2 = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_NU_";
+        for msg in [&b""[..], b"abc", b"abcdef0123456789", &[42u8; 200]] {
+            let point = encode_to_curve_g1(msg, dst_g1, XmdHash::Sha256).unwrap();
+            assert!(amcl_utils::subgroup_check_g1(&point));
+            assert!(point.equals(&encode_to_curve_g1(msg, dst_g1, XmdHash::Sha256).unwrap()));
+            assert!(!point.equals(&hash_to_curve_g1(msg, dst_g1, XmdHash::Sha256).unwrap()));
+
+            let point = encode_to_curve_g2(msg, dst_g2, XmdHash::Sha256).unwrap();
+            assert!(amcl_utils::subgroup_check_g2(&point));
+            assert!(point.equals(&encode_to_curve_g2(msg, dst_g2, XmdHash::Sha256).unwrap()));
+            assert!(!point.equals(&hash_to_curve_g2(msg, dst_g2, XmdHash::Sha256).unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_hash_to_curve_g2_sha256_matches_default() {
+        for msg in [&b""[..], b"abc", b"abcdef0123456789", &[42u8; 200]] {
+            let point = hash_to_curve_g2(msg, DST_G2, XmdHash::Sha256).unwrap();
+            assert!(point.equals(&amcl_utils::hash_to_curve_g2(msg)));
+        }
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_hash_to_curve_g2_keccak() {
+        let point = hash_to_curve_g2(b"abc", DST_G2, XmdHash::Keccak256).unwrap();
+        assert!(amcl_utils::subgroup_check_g2(&point));
+        assert!(point.equals(&hash_to_curve_g2(b"abc", DST_G2, XmdHash::Keccak256).unwrap()));
+        assert!(!point.equals(&hash_to_curve_g2(b"abc", DST_G2, XmdHash::Sha256).unwrap()));
+    }
+}
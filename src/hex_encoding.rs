@@ -0,0 +1,198 @@
+#[cfg(feature = "secret-hex")]
+extern crate zeroize;
+
+#[cfg(feature = "secret-hex")]
+use self::zeroize::Zeroize;
+use super::aggregates::AggregateSignature;
+#[cfg(feature = "secret-hex")]
+use super::amcl_utils::SECRET_KEY_BYTES;
+use super::amcl_utils::{G1_BYTES, G2_BYTES};
+use super::errors::DecodeError;
+use super::keys::PublicKey;
+#[cfg(feature = "secret-hex")]
+use super::keys::SecretKey;
+use super::signature::Signature;
+#[cfg(not(feature = "std"))]
+use core::{fmt, str::FromStr};
+#[cfg(feature = "std")]
+use std::{fmt, str::FromStr};
+
+// Write bytes as lowercase hex without a prefix.
+fn write_hex(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+    for byte in bytes {
+        write!(f, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
+// Decode a hex string, with an optional 0x prefix, of exactly N bytes.
+pub(crate) fn decode_hex<const N: usize>(s: &str) -> Result<[u8; N], DecodeError> {
+    let s = s.strip_prefix("0x").unwrap_or(s).as_bytes();
+    if !s.len().is_multiple_of(2) {
+        return Err(DecodeError::InvalidHex);
+    }
+    if s.len() != N * 2 {
+        return Err(DecodeError::InvalidLength { expected: N, actual: s.len() / 2 });
+    }
+
+    let mut bytes = [0u8; N];
+    for (byte, chunk) in bytes.iter_mut().zip(s.chunks(2)) {
+        *byte = match *chunk {
+            [high, low] => hex_value(high)? << 4 | hex_value(low)?,
+            _ => return Err(DecodeError::InvalidHex),
+        };
+    }
+    Ok(bytes)
+}
+
+fn hex_value(c: u8) -> Result<u8, DecodeError> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(DecodeError::InvalidHex),
+    }
+}
+
+// Implements FromStr, Display and LowerHex using the compressed bytes of the point.
+//
+// Display and the alternate LowerHex (`{:#x}`) are 0x prefixed, LowerHex (`{:x}`) is not.
+// Parsing accepts both and runs the same validation as `from_bytes()`.
+macro_rules! impl_hex {
+    ($type: ty, $byte_size: expr) => {
+        impl FromStr for $type {
+            type Err = DecodeError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                <$type>::from_bytes(&decode_hex::<{ $byte_size }>(s)?)
+            }
+        }
+
+        impl fmt::Display for $type {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{:#x}", self)
+            }
+        }
+
+        impl fmt::LowerHex for $type {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                if f.alternate() {
+                    write!(f, "0x")?;
+                }
+                write_hex(f, &self.as_bytes())
+            }
+        }
+    };
+}
+
+impl_hex!(PublicKey, G1_BYTES);
+impl_hex!(Signature, G2_BYTES);
+impl_hex!(AggregateSignature, G2_BYTES);
+
+// SecretKeys are only formatted as hex when explicitly enabled by the `secret-hex` feature, as it
+// is easy to accidentally log them.
+#[cfg(feature = "secret-hex")]
+impl FromStr for SecretKey {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = decode_hex::<SECRET_KEY_BYTES>(s)?;
+        let secret_key = SecretKey::from_bytes(&bytes);
+        bytes.zeroize();
+        secret_key
+    }
+}
+
+#[cfg(feature = "secret-hex")]
+impl fmt::Display for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#x}", self)
+    }
+}
+
+#[cfg(feature = "secret-hex")]
+impl fmt::LowerHex for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        let mut bytes = self.as_bytes();
+        let result = write_hex(f, &bytes);
+        bytes.zeroize();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::keys::Keypair;
+    use super::*;
+
+    #[test]
+    fn test_hex_round_trip() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signature = Signature::new(&[42u8; 32], &keypair.sk);
+        let aggregate_signature = AggregateSignature::from_signature(&signature);
+
+        let s = keypair.pk.to_string();
+        assert!(s.starts_with("0x"));
+        assert_eq!(s.len(), 2 + G1_BYTES * 2);
+        assert_eq!(s.parse::<PublicKey>().unwrap(), keypair.pk);
+
+        let s = signature.to_string();
+        assert_eq!(s.len(), 2 + G2_BYTES * 2);
+        assert_eq!(s.parse::<Signature>().unwrap(), signature);
+
+        let s = aggregate_signature.to_string();
+        assert_eq!(s.parse::<AggregateSignature>().unwrap(), aggregate_signature);
+    }
+
+    #[test]
+    fn test_hex_formatting() {
+        let s = "a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a";
+        let public_key: PublicKey = s.parse().unwrap();
+
+        assert_eq!(format!("{:x}", public_key), s);
+        assert_eq!(format!("{:#x}", public_key), format!("0x{}", s));
+        assert_eq!(public_key.to_string(), format!("0x{}", s));
+
+        // Prefix is optional and parsing is case insensitive
+        assert_eq!(format!("0x{}", s).parse::<PublicKey>().unwrap(), public_key);
+        assert_eq!(s.to_uppercase().parse::<PublicKey>().unwrap(), public_key);
+    }
+
+    #[test]
+    fn test_hex_invalid() {
+        let s = "a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a";
+
+        assert_eq!(s[..95].parse::<PublicKey>(), Err(DecodeError::InvalidHex));
+        assert_eq!(
+            s[..94].parse::<PublicKey>(),
+            Err(DecodeError::InvalidLength { expected: 48, actual: 47 })
+        );
+        assert_eq!(s.replace("a4", "g4").parse::<PublicKey>(), Err(DecodeError::InvalidHex));
+        assert_eq!(
+            "0x".parse::<Signature>(),
+            Err(DecodeError::InvalidLength { expected: 96, actual: 0 })
+        );
+    }
+
+    #[cfg(feature = "secret-hex")]
+    #[test]
+    fn test_hex_secret_key() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+
+        let s = keypair.sk.to_string();
+        assert_eq!(s.len(), 2 + SECRET_KEY_BYTES * 2);
+        assert_eq!(s.parse::<SecretKey>().unwrap(), keypair.sk);
+
+        let zero = format!("0x{}", "00".repeat(SECRET_KEY_BYTES));
+        assert_eq!(zero.parse::<SecretKey>(), Err(DecodeError::InvalidSecretKey));
+        assert_eq!(
+            "0x00".parse::<SecretKey>(),
+            Err(DecodeError::InvalidLength { expected: 32, actual: 1 })
+        );
+    }
+}
@@ -0,0 +1,196 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use super::aggregates::{AggregatePublicKey, AggregateSignature};
+use super::amcl_utils::AmclError;
+use super::keys::PublicKey;
+use super::signature::Signature;
+
+/// An AggregateSignature which tracks the indices of the participants it contains.
+///
+/// Used to combine Signatures (e.g. attestations over a slot) without adding any participant
+/// twice. The participants are stored as a little-endian bitfield, as used by
+/// `AggregatePublicKey::from_pubkeys_filtered()`, with one bit per member of the committee.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct IncrementalAggregate {
+    size: usize,
+    participants: Vec<u8>,
+    signature: AggregateSignature,
+}
+
+impl IncrementalAggregate {
+    /// Instantiates a new IncrementalAggregate without any participants, for a committee of
+    /// `size` members.
+    pub fn new(size: usize) -> Self {
+        Self { size, participants: vec![0; size.div_ceil(8)], signature: AggregateSignature::new() }
+    }
+
+    /// Add the Signature of the participant at `index`.
+    ///
+    /// Returns false, leaving the aggregate unchanged, if the participant was already added or
+    /// `index` is outside of the committee.
+    pub fn add(&mut self, index: usize, signature: &Signature) -> bool {
+        if index >= self.size || self.contains(index) {
+            return false;
+        }
+
+        self.participants[index / 8] |= 1 << (index % 8);
+        self.signature.add(signature);
+        true
+    }
+
+    /// Returns whether the participant at `index` has been added.
+    pub fn contains(&self, index: usize) -> bool {
+        match self.participants.get(index / 8) {
+            Some(byte) => byte >> (index % 8) & 1 == 1,
+            None => false,
+        }
+    }
+
+    /// Merge another IncrementalAggregate into this one.
+    ///
+    /// Returns false, leaving the aggregate unchanged, if any participant is in both or the
+    /// committees differ in size.
+    pub fn merge(&mut self, other: &IncrementalAggregate) -> bool {
+        if self.size != other.size
+            || self.participants.iter().zip(other.participants.iter()).any(|(a, b)| a & b != 0)
+        {
+            return false;
+        }
+
+        for (a, b) in self.participants.iter_mut().zip(other.participants.iter()) {
+            *a |= b;
+        }
+        self.signature.add_aggregate(&other.signature);
+        true
+    }
+
+    /// The number of participants which have been added.
+    pub fn len(&self) -> usize {
+        self.participants.iter().map(|byte| byte.count_ones() as usize).sum()
+    }
+
+    /// Returns true if no participants have been added.
+    pub fn is_empty(&self) -> bool {
+        self.participants.iter().all(|byte| *byte == 0)
+    }
+
+    /// The number of members of the committee.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The participants as a little-endian bitfield of `size().div_ceil(8)` bytes.
+    pub fn bitfield(&self) -> &[u8] {
+        &self.participants
+    }
+
+    /// The AggregateSignature of all participants.
+    pub fn aggregate_signature(&self) -> &AggregateSignature {
+        &self.signature
+    }
+
+    /// Produce the AggregatePublicKey of all participants, where `keys[i]` is the PublicKey of
+    /// the participant at index `i`.
+    ///
+    /// Pre-requsites: All public keys must be PoP verified before calling this function.
+    pub fn aggregate_public_key(
+        &self,
+        keys: &[PublicKey],
+    ) -> Result<AggregatePublicKey, AmclError> {
+        AggregatePublicKey::from_pubkeys_filtered(keys, &self.participants)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::keys::Keypair;
+    use super::*;
+
+    #[test]
+    fn test_incremental_aggregate() {
+        let msg = [5u8; 32];
+        let keypairs: Vec<Keypair> =
+            (0..12).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let public_keys: Vec<PublicKey> = keypairs.iter().map(|kp| kp.pk.clone()).collect();
+        let signatures: Vec<Signature> =
+            keypairs.iter().map(|kp| Signature::new(&msg, &kp.sk)).collect();
+
+        let mut incremental = IncrementalAggregate::new(12);
+        assert!(incremental.is_empty());
+        assert_eq!(
+            incremental.aggregate_public_key(&public_keys),
+            Err(AmclError::AggregateEmptyPoints)
+        );
+
+        assert!(incremental.add(2, &signatures[2]));
+        assert!(incremental.add(11, &signatures[11]));
+        assert!(incremental.contains(2));
+        assert!(incremental.contains(11));
+        assert!(!incremental.contains(3));
+        assert!(!incremental.contains(100));
+        assert_eq!(incremental.len(), 2);
+        assert_eq!(incremental.bitfield(), &[0b0000_0100, 0b0000_1000]);
+
+        // Adding the same participant twice is rejected
+        assert!(!incremental.add(2, &signatures[2]));
+        assert_eq!(incremental.len(), 2);
+
+        // As are participants outside of the committee
+        assert!(!incremental.add(12, &signatures[0]));
+        assert!(!incremental.add(usize::MAX, &signatures[0]));
+        assert_eq!(incremental.len(), 2);
+        assert_eq!(incremental.bitfield(), &[0b0000_0100, 0b0000_1000]);
+
+        let aggregate_public_key = incremental.aggregate_public_key(&public_keys).unwrap();
+        assert!(incremental
+            .aggregate_signature()
+            .fast_aggregate_verify_pre_aggregated(&msg, &aggregate_public_key));
+    }
+
+    #[test]
+    fn test_incremental_aggregate_merge() {
+        let msg = [6u8; 32];
+        let keypairs: Vec<Keypair> =
+            (0..12).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let public_keys: Vec<PublicKey> = keypairs.iter().map(|kp| kp.pk.clone()).collect();
+        let signatures: Vec<Signature> =
+            keypairs.iter().map(|kp| Signature::new(&msg, &kp.sk)).collect();
+
+        let mut a = IncrementalAggregate::new(12);
+        a.add(0, &signatures[0]);
+        a.add(1, &signatures[1]);
+
+        let mut b = IncrementalAggregate::new(12);
+        b.add(1, &signatures[1]);
+        b.add(9, &signatures[9]);
+
+        let mut c = IncrementalAggregate::new(12);
+        c.add(9, &signatures[9]);
+
+        // Overlapping participants are rejected
+        let before = a.clone();
+        assert!(!a.merge(&b));
+        assert_eq!(a, before);
+
+        // Committees of different sizes are rejected
+        let mut d = IncrementalAggregate::new(16);
+        d.add(9, &signatures[9]);
+        assert!(!a.merge(&d));
+        assert_eq!(a, before);
+
+        assert!(a.merge(&c));
+        assert_eq!(a.len(), 3);
+        assert!(a.contains(0) && a.contains(1) && a.contains(9));
+
+        let aggregate_public_key = a.aggregate_public_key(&public_keys).unwrap();
+        assert!(a
+            .aggregate_signature()
+            .fast_aggregate_verify_pre_aggregated(&msg, &aggregate_public_key));
+    }
+}
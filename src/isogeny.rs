@@ -0,0 +1,778 @@
+// The simplified SWU maps and the isogenies to E1 and E2, which amcl fuses into map2point.
+//
+// Exposed through the hash_to_curve module. The maps are variable time, as is map2point, which
+// is fine as they are only applied to hashes of public messages.
+// https://www.rfc-editor.org/rfc/rfc9380#section-6.6.3
+
+use super::amcl_utils::{Big, GroupG1, GroupG2, FP2, MODBYTES};
+use super::fields::{Fp, Fp2};
+use super::points::{G1Point, G2Point};
+use BLSCurve::fp::FP;
+
+// E1': y^2 = x^3 + A' * x + B', https://www.rfc-editor.org/rfc/rfc9380#section-8.8.1
+const SSWU_G1_A: [u8; MODBYTES] = [
+    0, 20, 70, 152, 163, 184, 233, 67, 61, 105, 58, 2, 201, 109, 73, 130, 176, 234, 152, 83, 131,
+    238, 102, 168, 216, 232, 152, 26, 239, 216, 129, 172, 152, 147, 111, 141, 160, 224, 249, 127,
+    92, 244, 40, 8, 45, 88, 76, 29,
+];
+const SSWU_G1_B: [u8; MODBYTES] = [
+    18, 226, 144, 141, 17, 104, 128, 48, 1, 139, 18, 232, 117, 62, 238, 59, 32, 22, 193, 240, 242,
+    79, 64, 112, 160, 185, 193, 79, 206, 243, 94, 245, 90, 35, 33, 90, 49, 108, 234, 165, 209, 204,
+    72, 233, 142, 23, 43, 224,
+];
+const SSWU_G1_Z: isize = 11;
+
+// The 11-isogeny from E1' to E1, https://www.rfc-editor.org/rfc/rfc9380#appendix-E.2
+const ISO_G1: Isogeny<[u8; MODBYTES]> = Isogeny {
+    x_num: &ISO_G1_X_NUM,
+    x_den: &ISO_G1_X_DEN,
+    y_num: &ISO_G1_Y_NUM,
+    y_den: &ISO_G1_Y_DEN,
+};
+const ISO_G1_X_NUM: [[u8; MODBYTES]; 12] = [
+    [
+        17, 160, 95, 43, 30, 131, 51, 64, 184, 9, 16, 29, 217, 152, 21, 133, 107, 48, 62, 136, 162,
+        215, 0, 95, 242, 98, 123, 86, 205, 180, 226, 200, 86, 16, 194, 213, 242, 230, 45, 110, 174,
+        172, 22, 98, 115, 70, 73, 183,
+    ],
+    [
+        23, 41, 78, 211, 233, 67, 171, 47, 5, 136, 186, 178, 33, 71, 168, 28, 124, 23, 231, 91, 47,
+        106, 132, 23, 245, 101, 227, 60, 112, 209, 232, 107, 72, 56, 242, 166, 243, 24, 195, 86,
+        232, 52, 238, 241, 179, 203, 131, 187,
+    ],
+    [
+        13, 84, 0, 93, 185, 118, 120, 236, 29, 16, 72, 197, 209, 10, 154, 27, 206, 3, 36, 115, 41,
+        89, 131, 229, 104, 120, 229, 1, 236, 104, 226, 92, 149, 140, 62, 61, 42, 9, 114, 159, 224,
+        23, 159, 157, 172, 158, 220, 176,
+    ],
+    [
+        23, 120, 231, 22, 111, 204, 109, 183, 78, 6, 9, 211, 7, 229, 84, 18, 215, 245, 228, 101,
+        106, 141, 191, 37, 241, 179, 50, 137, 241, 179, 48, 131, 83, 54, 226, 92, 227, 16, 113,
+        147, 197, 179, 136, 100, 29, 155, 104, 97,
+    ],
+    [
+        14, 153, 114, 106, 49, 153, 244, 67, 102, 66, 180, 179, 228, 17, 142, 84, 153, 219, 153,
+        90, 18, 87, 251, 63, 8, 110, 235, 101, 152, 47, 172, 24, 152, 90, 40, 111, 48, 30, 119,
+        196, 81, 21, 76, 233, 172, 136, 149, 217,
+    ],
+    [
+        22, 48, 195, 37, 13, 115, 19, 255, 1, 209, 32, 27, 247, 167, 74, 181, 219, 60, 177, 125,
+        217, 82, 121, 155, 158, 211, 171, 144, 151, 230, 143, 144, 160, 135, 13, 45, 202, 231, 61,
+        25, 205, 19, 193, 198, 111, 101, 41, 131,
+    ],
+    [
+        13, 110, 214, 85, 63, 228, 77, 41, 106, 55, 38, 195, 138, 230, 82, 191, 177, 21, 134, 38,
+        79, 15, 140, 225, 144, 8, 226, 24, 249, 200, 107, 42, 141, 162, 81, 40, 193, 5, 46, 202,
+        221, 215, 242, 37, 161, 57, 237, 132,
+    ],
+    [
+        23, 184, 30, 119, 1, 171, 219, 226, 232, 116, 56, 132, 209, 17, 126, 83, 53, 109, 229, 171,
+        39, 91, 77, 177, 166, 130, 198, 46, 240, 242, 117, 51, 57, 183, 200, 248, 200, 244, 117,
+        175, 156, 203, 86, 24, 227, 240, 200, 142,
+    ],
+    [
+        8, 13, 60, 241, 249, 167, 143, 196, 123, 144, 179, 53, 99, 190, 153, 13, 196, 59, 117, 108,
+        231, 159, 85, 116, 162, 197, 150, 201, 40, 197, 209, 222, 79, 162, 149, 242, 150, 183, 78,
+        149, 109, 113, 152, 106, 132, 151, 227, 23,
+    ],
+    [
+        22, 155, 31, 142, 27, 207, 167, 196, 46, 12, 55, 81, 93, 19, 143, 34, 221, 46, 203, 128,
+        58, 12, 92, 153, 103, 99, 20, 186, 244, 187, 27, 127, 163, 25, 11, 46, 220, 3, 39, 121,
+        127, 36, 16, 103, 190, 57, 12, 158,
+    ],
+    [
+        16, 50, 29, 160, 121, 206, 7, 226, 114, 216, 236, 9, 210, 86, 91, 13, 250, 125, 204, 221,
+        230, 120, 127, 150, 213, 10, 243, 96, 3, 177, 72, 102, 246, 155, 119, 31, 140, 40, 93, 236,
+        202, 103, 223, 63, 22, 5, 251, 123,
+    ],
+    [
+        6, 224, 140, 36, 142, 38, 14, 112, 189, 30, 150, 35, 129, 237, 238, 61, 49, 215, 157, 126,
+        34, 200, 55, 188, 35, 192, 191, 27, 194, 76, 107, 104, 194, 75, 27, 128, 182, 77, 57, 31,
+        169, 200, 186, 46, 139, 162, 210, 41,
+    ],
+];
+const ISO_G1_X_DEN: [[u8; MODBYTES]; 11] = [
+    [
+        8, 202, 141, 84, 140, 255, 25, 174, 24, 178, 230, 47, 75, 211, 250, 111, 1, 213, 239, 75,
+        163, 91, 72, 186, 156, 149, 136, 97, 127, 200, 172, 98, 181, 88, 214, 129, 190, 52, 61,
+        248, 153, 60, 249, 250, 64, 210, 27, 28,
+    ],
+    [
+        18, 86, 26, 93, 235, 85, 156, 67, 72, 180, 113, 18, 152, 229, 54, 54, 112, 65, 232, 202,
+        12, 240, 128, 12, 1, 38, 194, 88, 140, 72, 191, 87, 19, 218, 168, 132, 108, 176, 38, 233,
+        229, 200, 39, 110, 200, 43, 59, 255,
+    ],
+    [
+        11, 41, 98, 254, 87, 163, 34, 94, 129, 55, 230, 41, 191, 242, 153, 31, 111, 137, 65, 111,
+        90, 113, 140, 209, 252, 166, 78, 0, 177, 26, 206, 172, 214, 163, 208, 150, 124, 148, 254,
+        220, 252, 194, 57, 186, 92, 184, 62, 25,
+    ],
+    [
+        3, 66, 85, 129, 165, 138, 226, 254, 200, 58, 175, 239, 124, 64, 235, 84, 91, 8, 36, 63, 22,
+        177, 101, 81, 84, 204, 168, 171, 194, 141, 111, 208, 73, 118, 213, 36, 62, 236, 245, 196,
+        19, 13, 232, 147, 141, 198, 44, 216,
+    ],
+    [
+        19, 168, 225, 98, 2, 41, 20, 168, 10, 111, 29, 95, 67, 231, 160, 125, 255, 223, 199, 89,
+        161, 32, 98, 187, 141, 107, 68, 232, 51, 179, 6, 218, 155, 210, 155, 168, 31, 53, 120, 29,
+        83, 157, 57, 91, 53, 50, 162, 30,
+    ],
+    [
+        14, 115, 85, 248, 228, 230, 103, 185, 85, 57, 15, 127, 5, 6, 198, 233, 57, 87, 53, 233,
+        206, 156, 173, 77, 10, 67, 188, 239, 36, 184, 152, 47, 116, 0, 210, 75, 196, 34, 143, 17,
+        192, 45, 249, 162, 159, 99, 4, 165,
+    ],
+    [
+        7, 114, 202, 172, 241, 105, 54, 25, 15, 62, 12, 99, 224, 89, 103, 33, 87, 15, 87, 153, 175,
+        83, 161, 137, 78, 46, 7, 48, 98, 174, 222, 156, 234, 115, 179, 83, 143, 13, 224, 108, 236,
+        37, 116, 73, 110, 232, 74, 58,
+    ],
+    [
+        20, 167, 172, 42, 157, 100, 168, 178, 48, 179, 245, 176, 116, 207, 1, 153, 110, 127, 99,
+        194, 27, 202, 104, 168, 25, 150, 225, 205, 249, 130, 44, 88, 15, 165, 185, 72, 157, 17,
+        226, 211, 17, 247, 217, 155, 189, 204, 90, 94,
+    ],
+    [
+        10, 16, 236, 246, 173, 165, 79, 130, 94, 146, 11, 61, 175, 199, 163, 204, 224, 127, 141,
+        29, 113, 97, 54, 107, 116, 16, 13, 166, 127, 57, 136, 53, 3, 130, 102, 146, 171, 186, 67,
+        112, 71, 118, 236, 58, 121, 161, 214, 65,
+    ],
+    [
+        9, 95, 193, 58, 185, 233, 42, 212, 71, 109, 110, 62, 179, 165, 102, 128, 246, 130, 180,
+        238, 150, 247, 208, 55, 118, 223, 83, 57, 120, 243, 28, 21, 147, 23, 78, 75, 75, 120, 101,
+        0, 45, 99, 132, 209, 104, 236, 221, 10,
+    ],
+    [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+    ],
+];
+const ISO_G1_Y_NUM: [[u8; MODBYTES]; 16] = [
+    [
+        9, 13, 151, 200, 27, 162, 78, 224, 37, 157, 31, 9, 73, 128, 220, 250, 17, 173, 19, 142, 72,
+        168, 105, 82, 43, 82, 175, 108, 149, 101, 67, 211, 205, 12, 122, 238, 155, 59, 163, 194,
+        190, 152, 69, 113, 151, 7, 187, 51,
+    ],
+    [
+        19, 73, 150, 161, 4, 238, 88, 17, 213, 16, 54, 215, 118, 251, 70, 131, 18, 35, 233, 108,
+        37, 79, 56, 61, 15, 144, 99, 67, 235, 103, 173, 52, 214, 197, 103, 17, 150, 47, 168, 191,
+        224, 151, 231, 90, 46, 65, 198, 150,
+    ],
+    [
+        0, 204, 120, 107, 170, 150, 110, 102, 244, 163, 132, 200, 106, 59, 73, 148, 37, 82, 226,
+        214, 88, 163, 28, 226, 195, 68, 190, 75, 145, 64, 13, 167, 210, 109, 82, 22, 40, 176, 5,
+        35, 184, 223, 226, 64, 199, 45, 225, 246,
+    ],
+    [
+        1, 248, 99, 118, 232, 152, 28, 33, 120, 152, 117, 26, 216, 116, 103, 87, 212, 42, 167, 185,
+        14, 235, 121, 28, 9, 228, 163, 236, 3, 37, 28, 249, 222, 64, 90, 186, 158, 198, 29, 236,
+        166, 53, 92, 119, 176, 229, 244, 203,
+    ],
+    [
+        8, 204, 3, 253, 239, 224, 255, 19, 92, 175, 79, 226, 162, 21, 41, 196, 25, 85, 54, 251,
+        227, 206, 80, 184, 121, 131, 63, 210, 33, 53, 26, 220, 46, 231, 248, 220, 9, 144, 64, 168,
+        65, 182, 218, 236, 242, 232, 254, 219,
+    ],
+    [
+        22, 96, 63, 202, 64, 99, 75, 106, 34, 17, 225, 29, 184, 240, 166, 160, 116, 167, 208, 212,
+        175, 173, 183, 189, 118, 80, 92, 61, 58, 213, 84, 78, 32, 63, 99, 38, 201, 90, 128, 114,
+        153, 178, 58, 177, 54, 51, 165, 240,
+    ],
+    [
+        4, 171, 11, 155, 207, 172, 27, 188, 178, 201, 119, 208, 39, 121, 107, 60, 231, 91, 184,
+        202, 43, 225, 132, 203, 82, 49, 65, 60, 77, 99, 79, 55, 71, 168, 122, 194, 70, 15, 65, 94,
+        201, 97, 248, 133, 95, 233, 214, 242,
+    ],
+    [
+        9, 135, 200, 213, 51, 58, 184, 111, 222, 153, 38, 189, 44, 166, 198, 116, 23, 10, 5, 191,
+        227, 189, 216, 31, 253, 3, 141, 166, 194, 108, 132, 38, 66, 246, 69, 80, 254, 223, 233, 53,
+        161, 94, 76, 163, 24, 112, 251, 41,
+    ],
+    [
+        9, 252, 64, 24, 189, 150, 104, 75, 232, 140, 158, 34, 30, 77, 161, 187, 143, 58, 189, 22,
+        103, 157, 194, 108, 30, 139, 110, 106, 31, 32, 202, 190, 105, 214, 82, 1, 199, 134, 7, 163,
+        96, 55, 14, 87, 123, 219, 165, 135,
+    ],
+    [
+        14, 27, 186, 122, 17, 134, 189, 181, 34, 58, 189, 231, 173, 161, 74, 35, 196, 42, 12, 167,
+        145, 90, 246, 254, 6, 152, 94, 126, 209, 228, 212, 59, 155, 63, 112, 85, 221, 78, 186, 111,
+        43, 175, 170, 235, 202, 115, 28, 48,
+    ],
+    [
+        25, 113, 62, 71, 147, 124, 209, 190, 13, 253, 11, 143, 29, 67, 251, 147, 205, 47, 203, 203,
+        108, 175, 73, 63, 209, 24, 62, 65, 99, 137, 230, 16, 49, 191, 58, 92, 206, 63, 186, 252,
+        232, 19, 113, 26, 208, 17, 193, 50,
+    ],
+    [
+        24, 180, 106, 144, 143, 54, 246, 222, 185, 24, 193, 67, 254, 210, 237, 204, 82, 53, 89,
+        184, 170, 240, 194, 70, 46, 107, 254, 127, 145, 31, 100, 50, 73, 217, 205, 244, 27, 68,
+        214, 6, 206, 7, 200, 164, 208, 7, 77, 142,
+    ],
+    [
+        11, 24, 44, 172, 16, 27, 147, 153, 209, 85, 9, 96, 4, 245, 63, 68, 122, 167, 177, 42, 52,
+        38, 176, 142, 192, 39, 16, 232, 7, 180, 99, 63, 6, 200, 81, 193, 145, 146, 17, 242, 13, 76,
+        4, 240, 11, 151, 30, 248,
+    ],
+    [
+        2, 69, 163, 148, 173, 30, 202, 155, 114, 252, 0, 174, 123, 227, 21, 220, 117, 123, 59, 8,
+        13, 76, 21, 128, 19, 230, 99, 45, 60, 64, 101, 156, 198, 207, 144, 173, 28, 35, 42, 100,
+        66, 217, 211, 245, 219, 152, 1, 51,
+    ],
+    [
+        5, 193, 41, 100, 94, 68, 207, 17, 2, 161, 89, 247, 72, 196, 163, 252, 94, 103, 61, 129,
+        215, 232, 101, 104, 217, 171, 15, 93, 57, 106, 124, 228, 107, 161, 4, 155, 101, 121, 175,
+        183, 134, 107, 30, 113, 84, 117, 34, 75,
+    ],
+    [
+        21, 230, 190, 78, 153, 15, 3, 206, 78, 165, 11, 59, 66, 223, 46, 181, 203, 24, 29, 143,
+        132, 150, 90, 57, 87, 173, 212, 250, 149, 175, 1, 178, 182, 101, 2, 126, 254, 192, 28, 119,
+        4, 180, 86, 190, 105, 200, 182, 4,
+    ],
+];
+const ISO_G1_Y_DEN: [[u8; MODBYTES]; 16] = [
+    [
+        22, 17, 44, 76, 58, 156, 152, 178, 82, 24, 17, 64, 250, 208, 234, 233, 96, 26, 109, 229,
+        120, 152, 11, 230, 238, 195, 35, 43, 91, 231, 46, 122, 7, 243, 104, 142, 246, 12, 32, 109,
+        1, 71, 146, 83, 176, 54, 99, 193,
+    ],
+    [
+        25, 98, 215, 92, 35, 129, 32, 30, 26, 12, 189, 108, 67, 195, 72, 184, 133, 200, 79, 247,
+        49, 196, 213, 156, 164, 161, 3, 86, 244, 83, 224, 31, 120, 164, 38, 7, 99, 82, 158, 53, 50,
+        246, 16, 44, 46, 73, 160, 61,
+    ],
+    [
+        5, 141, 243, 48, 102, 64, 218, 39, 111, 170, 174, 125, 110, 142, 177, 87, 120, 196, 133,
+        85, 81, 174, 127, 49, 12, 53, 165, 221, 39, 156, 210, 236, 166, 117, 124, 214, 54, 249,
+        111, 137, 30, 37, 56, 181, 61, 191, 103, 242,
+    ],
+    [
+        22, 183, 210, 136, 121, 142, 83, 149, 242, 13, 35, 191, 137, 237, 180, 209, 209, 21, 197,
+        219, 221, 188, 211, 14, 18, 61, 164, 137, 231, 38, 175, 65, 114, 115, 100, 242, 194, 130,
+        151, 173, 168, 210, 109, 152, 68, 95, 84, 22,
+    ],
+    [
+        11, 224, 224, 121, 84, 95, 67, 228, 176, 12, 201, 18, 248, 34, 141, 220, 198, 209, 156,
+        159, 15, 105, 187, 176, 84, 46, 218, 15, 201, 222, 201, 22, 162, 11, 21, 220, 15, 210, 237,
+        237, 218, 57, 20, 35, 17, 165, 0, 29,
+    ],
+    [
+        8, 217, 229, 41, 113, 134, 219, 45, 159, 178, 102, 234, 172, 120, 49, 130, 183, 1, 82, 198,
+        85, 80, 216, 129, 197, 236, 216, 123, 111, 15, 90, 100, 73, 243, 141, 185, 223, 169, 204,
+        226, 2, 198, 71, 127, 170, 249, 183, 172,
+    ],
+    [
+        22, 96, 7, 192, 138, 153, 219, 47, 195, 186, 135, 52, 172, 233, 130, 75, 94, 236, 253, 250,
+        141, 12, 248, 239, 93, 211, 101, 188, 64, 10, 0, 81, 213, 250, 156, 1, 165, 139, 31, 185,
+        61, 26, 19, 153, 18, 106, 119, 92,
+    ],
+    [
+        22, 163, 239, 8, 190, 62, 167, 234, 3, 188, 221, 250, 187, 166, 255, 110, 229, 164, 55, 94,
+        250, 31, 79, 215, 254, 179, 79, 210, 6, 53, 113, 50, 185, 32, 245, 176, 8, 1, 222, 228, 96,
+        238, 65, 90, 21, 129, 46, 217,
+    ],
+    [
+        24, 102, 200, 237, 51, 108, 97, 35, 26, 27, 229, 79, 209, 215, 76, 196, 249, 251, 12, 228,
+        198, 175, 89, 32, 171, 197, 117, 12, 75, 243, 155, 72, 82, 207, 226, 247, 187, 146, 72,
+        131, 107, 35, 61, 157, 85, 83, 93, 74,
+    ],
+    [
+        22, 122, 85, 205, 167, 10, 110, 28, 234, 130, 5, 151, 217, 74, 132, 144, 50, 22, 247, 99,
+        225, 61, 135, 187, 83, 8, 89, 46, 126, 167, 212, 251, 199, 56, 94, 163, 213, 41, 179, 94,
+        52, 110, 244, 139, 184, 145, 63, 85,
+    ],
+    [
+        4, 210, 242, 89, 238, 164, 5, 189, 72, 240, 16, 160, 26, 210, 145, 29, 156, 109, 208, 57,
+        187, 97, 166, 41, 14, 89, 27, 54, 230, 54, 165, 200, 113, 165, 194, 159, 79, 131, 6, 4, 0,
+        248, 180, 156, 186, 143, 106, 168,
+    ],
+    [
+        10, 204, 187, 103, 72, 29, 3, 63, 245, 133, 44, 30, 72, 197, 12, 71, 127, 148, 255, 138,
+        239, 206, 66, 210, 140, 15, 154, 136, 206, 167, 145, 53, 22, 249, 104, 152, 111, 126, 187,
+        234, 150, 132, 181, 41, 226, 86, 16, 146,
+    ],
+    [
+        10, 214, 185, 81, 76, 118, 127, 227, 195, 97, 49, 68, 180, 95, 20, 150, 84, 51, 70, 217,
+        138, 223, 2, 38, 125, 92, 238, 249, 160, 13, 155, 134, 147, 0, 7, 99, 227, 185, 10, 193,
+        30, 153, 177, 56, 87, 51, 69, 204,
+    ],
+    [
+        2, 102, 4, 0, 235, 46, 79, 59, 98, 139, 221, 13, 83, 205, 118, 242, 191, 86, 91, 148, 231,
+        41, 39, 193, 203, 116, 141, 242, 121, 66, 72, 14, 66, 5, 23, 189, 135, 20, 204, 128, 209,
+        250, 220, 19, 38, 237, 6, 247,
+    ],
+    [
+        14, 15, 161, 216, 22, 221, 192, 62, 107, 36, 37, 94, 13, 120, 25, 193, 113, 196, 15, 101,
+        226, 115, 184, 83, 50, 78, 252, 214, 53, 108, 170, 32, 92, 162, 245, 112, 241, 52, 151,
+        128, 68, 21, 71, 58, 29, 99, 75, 143,
+    ],
+    [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+    ],
+];
+
+// The 3-isogeny from E2' to E2, with each coefficient as (c0, c1).
+// https://www.rfc-editor.org/rfc/rfc9380#appendix-E.3
+const ISO_G2: Isogeny<[[u8; MODBYTES]; 2]> = Isogeny {
+    x_num: &ISO_G2_X_NUM,
+    x_den: &ISO_G2_X_DEN,
+    y_num: &ISO_G2_Y_NUM,
+    y_den: &ISO_G2_Y_DEN,
+};
+const ISO_G2_X_NUM: [[[u8; MODBYTES]; 2]; 4] = [
+    [
+        [
+            5, 199, 89, 80, 126, 142, 51, 62, 187, 91, 122, 154, 71, 215, 237, 133, 50, 197, 45,
+            57, 253, 58, 4, 42, 136, 181, 132, 35, 197, 10, 225, 93, 92, 38, 56, 227, 67, 217, 199,
+            28, 98, 56, 170, 170, 170, 170, 151, 214,
+        ],
+        [
+            5, 199, 89, 80, 126, 142, 51, 62, 187, 91, 122, 154, 71, 215, 237, 133, 50, 197, 45,
+            57, 253, 58, 4, 42, 136, 181, 132, 35, 197, 10, 225, 93, 92, 38, 56, 227, 67, 217, 199,
+            28, 98, 56, 170, 170, 170, 170, 151, 214,
+        ],
+    ],
+    [
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ],
+        [
+            17, 86, 11, 241, 123, 170, 153, 188, 50, 18, 111, 206, 215, 135, 200, 143, 152, 79,
+            135, 173, 247, 174, 12, 127, 154, 32, 140, 107, 79, 32, 164, 24, 20, 114, 170, 169,
+            203, 141, 85, 85, 38, 169, 255, 255, 255, 255, 199, 26,
+        ],
+    ],
+    [
+        [
+            17, 86, 11, 241, 123, 170, 153, 188, 50, 18, 111, 206, 215, 135, 200, 143, 152, 79,
+            135, 173, 247, 174, 12, 127, 154, 32, 140, 107, 79, 32, 164, 24, 20, 114, 170, 169,
+            203, 141, 85, 85, 38, 169, 255, 255, 255, 255, 199, 30,
+        ],
+        [
+            8, 171, 5, 248, 189, 213, 76, 222, 25, 9, 55, 231, 107, 195, 228, 71, 204, 39, 195,
+            214, 251, 215, 6, 63, 205, 16, 70, 53, 167, 144, 82, 12, 10, 57, 85, 84, 229, 198, 170,
+            170, 147, 84, 255, 255, 255, 255, 227, 141,
+        ],
+    ],
+    [
+        [
+            23, 29, 101, 65, 250, 56, 204, 250, 237, 109, 234, 105, 31, 95, 182, 20, 203, 20, 180,
+            231, 244, 232, 16, 170, 34, 214, 16, 143, 20, 43, 133, 117, 112, 152, 227, 141, 15,
+            103, 28, 113, 136, 226, 170, 170, 170, 170, 94, 209,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ],
+    ],
+];
+const ISO_G2_X_DEN: [[[u8; MODBYTES]; 2]; 3] = [
+    [
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ],
+        [
+            26, 1, 17, 234, 57, 127, 230, 154, 75, 27, 167, 182, 67, 75, 172, 215, 100, 119, 75,
+            132, 243, 133, 18, 191, 103, 48, 210, 160, 246, 176, 246, 36, 30, 171, 255, 254, 177,
+            83, 255, 255, 185, 254, 255, 255, 255, 255, 170, 99,
+        ],
+    ],
+    [
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 12,
+        ],
+        [
+            26, 1, 17, 234, 57, 127, 230, 154, 75, 27, 167, 182, 67, 75, 172, 215, 100, 119, 75,
+            132, 243, 133, 18, 191, 103, 48, 210, 160, 246, 176, 246, 36, 30, 171, 255, 254, 177,
+            83, 255, 255, 185, 254, 255, 255, 255, 255, 170, 159,
+        ],
+    ],
+    [
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ],
+    ],
+];
+const ISO_G2_Y_NUM: [[[u8; MODBYTES]; 2]; 4] = [
+    [
+        [
+            21, 48, 71, 124, 122, 180, 17, 59, 89, 164, 193, 139, 7, 109, 17, 147, 15, 125, 165,
+            212, 160, 127, 100, 155, 245, 68, 57, 216, 125, 39, 229, 0, 252, 140, 37, 235, 248,
+            201, 47, 104, 18, 207, 199, 28, 113, 198, 215, 6,
+        ],
+        [
+            21, 48, 71, 124, 122, 180, 17, 59, 89, 164, 193, 139, 7, 109, 17, 147, 15, 125, 165,
+            212, 160, 127, 100, 155, 245, 68, 57, 216, 125, 39, 229, 0, 252, 140, 37, 235, 248,
+            201, 47, 104, 18, 207, 199, 28, 113, 198, 215, 6,
+        ],
+    ],
+    [
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ],
+        [
+            5, 199, 89, 80, 126, 142, 51, 62, 187, 91, 122, 154, 71, 215, 237, 133, 50, 197, 45,
+            57, 253, 58, 4, 42, 136, 181, 132, 35, 197, 10, 225, 93, 92, 38, 56, 227, 67, 217, 199,
+            28, 98, 56, 170, 170, 170, 170, 151, 190,
+        ],
+    ],
+    [
+        [
+            17, 86, 11, 241, 123, 170, 153, 188, 50, 18, 111, 206, 215, 135, 200, 143, 152, 79,
+            135, 173, 247, 174, 12, 127, 154, 32, 140, 107, 79, 32, 164, 24, 20, 114, 170, 169,
+            203, 141, 85, 85, 38, 169, 255, 255, 255, 255, 199, 28,
+        ],
+        [
+            8, 171, 5, 248, 189, 213, 76, 222, 25, 9, 55, 231, 107, 195, 228, 71, 204, 39, 195,
+            214, 251, 215, 6, 63, 205, 16, 70, 53, 167, 144, 82, 12, 10, 57, 85, 84, 229, 198, 170,
+            170, 147, 84, 255, 255, 255, 255, 227, 143,
+        ],
+    ],
+    [
+        [
+            18, 76, 154, 212, 59, 108, 247, 155, 251, 247, 4, 61, 227, 129, 26, 208, 118, 27, 15,
+            55, 161, 226, 98, 134, 176, 233, 119, 198, 154, 162, 116, 82, 78, 121, 9, 122, 86, 220,
+            75, 217, 225, 179, 113, 199, 28, 113, 139, 16,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ],
+    ],
+];
+const ISO_G2_Y_DEN: [[[u8; MODBYTES]; 2]; 4] = [
+    [
+        [
+            26, 1, 17, 234, 57, 127, 230, 154, 75, 27, 167, 182, 67, 75, 172, 215, 100, 119, 75,
+            132, 243, 133, 18, 191, 103, 48, 210, 160, 246, 176, 246, 36, 30, 171, 255, 254, 177,
+            83, 255, 255, 185, 254, 255, 255, 255, 255, 168, 251,
+        ],
+        [
+            26, 1, 17, 234, 57, 127, 230, 154, 75, 27, 167, 182, 67, 75, 172, 215, 100, 119, 75,
+            132, 243, 133, 18, 191, 103, 48, 210, 160, 246, 176, 246, 36, 30, 171, 255, 254, 177,
+            83, 255, 255, 185, 254, 255, 255, 255, 255, 168, 251,
+        ],
+    ],
+    [
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ],
+        [
+            26, 1, 17, 234, 57, 127, 230, 154, 75, 27, 167, 182, 67, 75, 172, 215, 100, 119, 75,
+            132, 243, 133, 18, 191, 103, 48, 210, 160, 246, 176, 246, 36, 30, 171, 255, 254, 177,
+            83, 255, 255, 185, 254, 255, 255, 255, 255, 169, 211,
+        ],
+    ],
+    [
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 18,
+        ],
+        [
+            26, 1, 17, 234, 57, 127, 230, 154, 75, 27, 167, 182, 67, 75, 172, 215, 100, 119, 75,
+            132, 243, 133, 18, 191, 103, 48, 210, 160, 246, 176, 246, 36, 30, 171, 255, 254, 177,
+            83, 255, 255, 185, 254, 255, 255, 255, 255, 170, 153,
+        ],
+    ],
+    [
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ],
+    ],
+];
+
+// x = x_num(x') / x_den(x') and y = y' * y_num(x') / y_den(x'), coefficients lowest degree first.
+struct Isogeny<C: 'static> {
+    x_num: &'static [C],
+    x_den: &'static [C],
+    y_num: &'static [C],
+    y_den: &'static [C],
+}
+
+// The arithmetic of Fp and Fp2 which the maps are written in.
+trait Field: Sized {
+    type Constant;
+
+    fn constant(constant: &Self::Constant) -> Self;
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn is_zero(&self) -> bool;
+    fn add(&self, other: &Self) -> Self;
+    fn mul(&self, other: &Self) -> Self;
+    fn square(&self) -> Self;
+    fn neg(&self) -> Self;
+    fn invert(&self) -> Option<Self>;
+    fn sqrt(&self) -> Option<Self>;
+    // https://www.rfc-editor.org/rfc/rfc9380#section-4.1
+    fn sgn0(&self) -> bool;
+
+    // The inverse, or 0 for 0 as inv0 in RFC 9380.
+    fn inv0(&self) -> Self {
+        self.invert().unwrap_or_else(Self::zero)
+    }
+}
+
+impl Field for Fp {
+    type Constant = [u8; MODBYTES];
+
+    fn constant(bytes: &[u8; MODBYTES]) -> Self {
+        Fp(FP::new_big(&Big::from_bytes(bytes)))
+    }
+
+    fn zero() -> Self {
+        Fp::zero()
+    }
+
+    fn one() -> Self {
+        Fp::one()
+    }
+
+    fn is_zero(&self) -> bool {
+        Fp::is_zero(self)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Fp::add(self, other)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        Fp::mul(self, other)
+    }
+
+    fn square(&self) -> Self {
+        Fp::square(self)
+    }
+
+    fn neg(&self) -> Self {
+        Fp::neg(self)
+    }
+
+    fn invert(&self) -> Option<Self> {
+        Fp::invert(self)
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        Fp::sqrt(self)
+    }
+
+    fn sgn0(&self) -> bool {
+        self.0.redc().parity() == 1
+    }
+}
+
+impl Field for Fp2 {
+    type Constant = [[u8; MODBYTES]; 2];
+
+    fn constant(bytes: &[[u8; MODBYTES]; 2]) -> Self {
+        Fp2::new(&Fp::constant(&bytes[0]), &Fp::constant(&bytes[1]))
+    }
+
+    fn zero() -> Self {
+        Fp2::zero()
+    }
+
+    fn one() -> Self {
+        Fp2::one()
+    }
+
+    fn is_zero(&self) -> bool {
+        Fp2::is_zero(self)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Fp2::add(self, other)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        Fp2::mul(self, other)
+    }
+
+    fn square(&self) -> Self {
+        Fp2::square(self)
+    }
+
+    fn neg(&self) -> Self {
+        Fp2::neg(self)
+    }
+
+    fn invert(&self) -> Option<Self> {
+        Fp2::invert(self)
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        Fp2::sqrt(self)
+    }
+
+    fn sgn0(&self) -> bool {
+        let c0 = self.c0();
+        c0.sgn0() || (c0.is_zero() && self.c1().sgn0())
+    }
+}
+
+// The simplified SWU map onto y^2 = x^3 + A * x + B for the non-square Z.
+// https://www.rfc-editor.org/rfc/rfc9380#section-6.6.2
+fn sswu<F: Field>(u: &F, a: &F, b: &F, z: &F) -> (F, F) {
+    // tv1 = inv0(Z^2 * u^4 + Z * u^2)
+    let z_u2 = z.mul(&u.square());
+    let tv1 = z_u2.square().add(&z_u2).inv0();
+    let x1 = if tv1.is_zero() {
+        // The exceptional case x1 = B / (Z * A)
+        b.mul(&z.mul(a).inv0())
+    } else {
+        // x1 = (-B / A) * (1 + tv1)
+        b.neg().mul(&a.inv0()).mul(&F::one().add(&tv1))
+    };
+    let gx1 = x1.square().add(a).mul(&x1).add(b);
+    let x2 = z_u2.mul(&x1);
+    let gx2 = x2.square().add(a).mul(&x2).add(b);
+
+    // gx2 is a square whenever gx1 is not
+    let (x, y) = match gx1.sqrt() {
+        Some(y) => (x1, y),
+        None => (x2, gx2.sqrt().unwrap_or_else(F::zero)),
+    };
+    if y.sgn0() == u.sgn0() {
+        (x, y)
+    } else {
+        (x, y.neg())
+    }
+}
+
+// Horner's rule, for coefficients lowest degree first.
+fn evaluate<F: Field>(coefficients: &[F::Constant], x: &F) -> F {
+    coefficients.iter().rev().fold(F::zero(), |acc, c| acc.mul(x).add(&F::constant(c)))
+}
+
+// The image of (x, y), or None for the point at infinity when a denominator is 0.
+fn iso_map<F: Field>(x: &F, y: &F, isogeny: &Isogeny<F::Constant>) -> Option<(F, F)> {
+    let x_den = evaluate(isogeny.x_den, x).invert()?;
+    let y_den = evaluate(isogeny.y_den, x).invert()?;
+    Some((evaluate(isogeny.x_num, x).mul(&x_den), y.mul(&evaluate(isogeny.y_num, x)).mul(&y_den)))
+}
+
+fn sswu_g2_parameters() -> (Fp2, Fp2, Fp2) {
+    // A' = 240 * u, B' = 1012 * (1 + u) and Z = -(2 + u)
+    let a = FP2::new_ints(0, 240);
+    let b = FP2::new_ints(1012, 1012);
+    let mut z = FP2::new_ints(2, 1);
+    z.neg();
+    (Fp2::reduced(a), Fp2::reduced(b), Fp2::reduced(z))
+}
+
+/// The simplified SWU map onto E1', the curve 11-isogenous to E1.
+///
+/// Returns the affine coordinates (x', y') on E1', which `iso_map_g1` maps to E1.
+/// https://www.rfc-editor.org/rfc/rfc9380#section-6.6.2
+pub fn sswu_g1(u: &Fp) -> (Fp, Fp) {
+    let a = Fp::constant(&SSWU_G1_A);
+    let b = Fp::constant(&SSWU_G1_B);
+    let z = Fp(FP::new_int(SSWU_G1_Z));
+    sswu(&Fp::reduced(u.0.clone()), &a, &b, &z)
+}
+
+/// The 11-isogeny from E1' to E1, of affine coordinates (x', y') on E1'.
+///
+/// `iso_map_g1` of `sswu_g1` is `map_to_curve_g1`. Points in the kernel map to infinity.
+/// https://www.rfc-editor.org/rfc/rfc9380#appendix-E.2
+pub fn iso_map_g1(x: &Fp, y: &Fp) -> G1Point {
+    let x = Fp::reduced(x.0.clone());
+    let y = Fp::reduced(y.0.clone());
+    match iso_map(&x, &y, &ISO_G1) {
+        Some((x, y)) => G1Point(GroupG1::new_bigs(&x.0.redc(), &y.0.redc())),
+        None => G1Point::identity(),
+    }
+}
+
+/// The simplified SWU map onto E2', the curve 3-isogenous to E2.
+///
+/// Returns the affine coordinates (x', y') on E2', which `iso_map_g2` maps to E2.
+/// https://www.rfc-editor.org/rfc/rfc9380#section-6.6.2
+pub fn sswu_g2(u: &Fp2) -> (Fp2, Fp2) {
+    let (a, b, z) = sswu_g2_parameters();
+    sswu(&Fp2::reduced(u.0.clone()), &a, &b, &z)
+}
+
+/// The 3-isogeny from E2' to E2, of affine coordinates (x', y') on E2'.
+///
+/// `iso_map_g2` of `sswu_g2` is `map_to_curve_g2`. Points in the kernel map to infinity.
+/// https://www.rfc-editor.org/rfc/rfc9380#appendix-E.3
+pub fn iso_map_g2(x: &Fp2, y: &Fp2) -> G2Point {
+    let x = Fp2::reduced(x.0.clone());
+    let y = Fp2::reduced(y.0.clone());
+    match iso_map(&x, &y, &ISO_G2) {
+        Some((x, y)) => G2Point(GroupG2::new_fp2s(&x.0, &y.0)),
+        None => G2Point::identity(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::hash_to_curve::{
+        hash_to_field_fp, hash_to_field_fp2, map_to_curve_g1, map_to_curve_g2, XmdHash,
+    };
+    use super::*;
+
+    const DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+    // y^2 = x^3 + A * x + B
+    fn is_on_curve<F: Field + PartialEq>(x: &F, y: &F, a: &F, b: &F) -> bool {
+        y.square() == x.square().add(a).mul(x).add(b)
+    }
+
+    #[test]
+    fn test_sswu_g1() {
+        let a = Fp::constant(&SSWU_G1_A);
+        let b = Fp::constant(&SSWU_G1_B);
+        for msg in [&b"abc"[..], b"", b"cats"].iter() {
+            for u in hash_to_field_fp(msg, DST, XmdHash::Sha256).unwrap().iter() {
+                let (x, y) = sswu_g1(u);
+                assert!(is_on_curve(&x, &y, &a, &b));
+                assert_eq!(iso_map_g1(&x, &y), map_to_curve_g1(u));
+            }
+        }
+
+        // u = 0 is the exceptional case
+        let (x, y) = sswu_g1(&Fp::zero());
+        assert!(is_on_curve(&x, &y, &a, &b));
+        assert_eq!(iso_map_g1(&x, &y), map_to_curve_g1(&Fp::zero()));
+    }
+
+    #[test]
+    fn test_sswu_g2() {
+        let (a, b, _) = sswu_g2_parameters();
+        for msg in [&b"abc"[..], b"", b"cats"].iter() {
+            for u in hash_to_field_fp2(msg, DST, XmdHash::Sha256).unwrap().iter() {
+                let (x, y) = sswu_g2(u);
+                assert!(is_on_curve(&x, &y, &a, &b));
+                assert_eq!(iso_map_g2(&x, &y), map_to_curve_g2(u));
+            }
+        }
+
+        let (x, y) = sswu_g2(&Fp2::zero());
+        assert!(is_on_curve(&x, &y, &a, &b));
+        assert_eq!(iso_map_g2(&x, &y), map_to_curve_g2(&Fp2::zero()));
+    }
+
+    #[test]
+    fn test_iso_map_infinity() {
+        // The denominators vanish at x' = -6 + 6 * u, the x coordinate of the kernel
+        let x = Fp2::reduced(FP2::new_ints(6, -6)).neg();
+        assert!(iso_map_g2(&x, &Fp2::one()).is_identity());
+        assert!(!iso_map_g2(&Fp2::zero(), &Fp2::one()).is_identity());
+    }
+}
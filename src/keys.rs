@@ -1,22 +1,36 @@
 extern crate amcl;
-extern crate rand;
+extern crate rand_core;
 extern crate zeroize;
 
 use self::zeroize::Zeroize;
 use super::amcl_utils::{
-    self, compress_g1, decompress_g1, g1mul, subgroup_check_g1, AmclError, Big, GroupG1,
-    CURVE_ORDER, G1_BYTES, SECRET_KEY_BYTES,
+    self, compress_g1, decompress_g1, decompress_g1_unchecked, deserialize_uncompressed_g1,
+    g1_from_coordinates, g1_from_eip2537, g1_to_coordinates, g1_to_eip2537, g1mul, g2mul,
+    on_curve_check_g1, serialize_uncompressed_g1, subgroup_check_g1, AmclError, Big, GroupG1,
+    CURVE_ORDER, EIP2537_G1_BYTES, G1_BYTES, MODBYTES, SECRET_KEY_BYTES,
 };
+use super::errors::DecodeError;
+use super::points::{G1Point, G2Point};
+use super::signature::Signature;
+use super::strictness::{self, Strictness};
+#[cfg(feature = "parallel")]
+use super::thread_pool;
 
-#[cfg(not(feature = "std"))]
+#[cfg(all(feature = "alloc", not(feature = "std")))]
 pub use alloc::vec::Vec;
 use amcl::hash256::HASH256;
-use rand::Rng;
+#[cfg(not(feature = "std"))]
+use core::cmp::Ordering;
+#[cfg(not(feature = "std"))]
+use core::hash::{Hash, Hasher};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "std")]
+use std::cmp::Ordering;
 #[cfg(feature = "std")]
 use std::fmt;
-use BLSCurve::bls381::utils::{
-    deserialize_g1, secret_key_from_bytes, secret_key_to_bytes, serialize_uncompressed_g1,
-};
+#[cfg(feature = "std")]
+use std::hash::{Hash, Hasher};
+use BLSCurve::bls381::utils::{secret_key_from_bytes, secret_key_to_bytes};
 
 // Key Generation Constants
 /// Domain for key generation.
@@ -24,6 +38,62 @@ pub const KEY_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
 /// L = ceil((3 * ceil(log2(r))) / 16) = 48.
 pub const L: u8 = 48;
 
+/// Whether Signatures may verify under the infinity PublicKey, as was permitted before
+/// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-2.5
+///
+/// Enabled by the `permissive-infinity` feature, e.g. to replay historical data.
+pub const ALLOW_INFINITY_PUBLIC_KEY: bool = cfg!(feature = "permissive-infinity");
+
+fn sha256(message: &[u8]) -> [u8; 32] {
+    let mut hash256 = HASH256::new();
+    hash256.init();
+    hash256.process_array(message);
+    hash256.hash()
+}
+
+// HMAC-SHA256 (RFC 2104) of the concatenated message parts, computed without allocating.
+fn hmac_sha256(key: &[u8; 32], message: &[&[u8]]) -> [u8; 32] {
+    // Keys are shorter than the 64 byte block size so are zero padded
+    let mut ipad = [0x36u8; 64];
+    let mut opad = [0x5cu8; 64];
+    for (i, byte) in key.iter().enumerate() {
+        ipad[i] ^= byte;
+        opad[i] ^= byte;
+    }
+
+    let mut hash256 = HASH256::new();
+    hash256.init();
+    hash256.process_array(&ipad);
+    for part in message {
+        hash256.process_array(part);
+    }
+    let mut inner = hash256.hash();
+
+    let mut hash256 = HASH256::new();
+    hash256.init();
+    hash256.process_array(&opad);
+    hash256.process_array(&inner);
+    ipad.zeroize();
+    opad.zeroize();
+    inner.zeroize();
+    hash256.hash()
+}
+
+// HKDF-Expand (RFC 5869) of L bytes with info `key_info || I2OSP(L, 2)`.
+fn hkdf_expand_l(prk: &[u8; 32], key_info: &[u8]) -> [u8; L as usize] {
+    let info_suffix = [0, L];
+    let mut okm = [0u8; L as usize];
+
+    // T(1) = HMAC(PRK, info || 0x01), T(2) = HMAC(PRK, T(1) || info || 0x02)
+    let mut t1 = hmac_sha256(prk, &[key_info, &info_suffix, &[1]]);
+    let mut t2 = hmac_sha256(prk, &[&t1, key_info, &info_suffix, &[2]]);
+    okm[..32].copy_from_slice(&t1);
+    okm[32..].copy_from_slice(&t2[..L as usize - 32]);
+    t1.zeroize();
+    t2.zeroize();
+    okm
+}
+
 /// A BLS secret key.
 #[derive(Clone)]
 pub struct SecretKey {
@@ -31,10 +101,26 @@ pub struct SecretKey {
 }
 
 impl SecretKey {
-    /// Generate a new SecretKey using an Rng to seed the `amcl::rand::RAND` PRNG.
-    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
-        let ikm: [u8; 32] = rng.gen();
-        Self::key_generate(&ikm, &[]).unwrap() // will only error if ikm < 32 bytes
+    /// Generate a new SecretKey from 32 bytes of input keying material drawn from `rng`.
+    ///
+    /// Any `rand_core` cryptographically secure RNG may be used, e.g. `rand::thread_rng()` or
+    /// `rand_core::OsRng`.
+    /// The key depends only on the RNG's output, so a seeded RNG reproduces it in tests.
+    pub fn random<R: RngCore + CryptoRng + ?Sized>(rng: &mut R) -> Self {
+        let mut ikm = [0u8; 32];
+        rng.fill_bytes(&mut ikm);
+        let sk = Self::key_generate_from_ikm(&ikm, &[]);
+        ikm.zeroize();
+        sk
+    }
+
+    /// Generate a new SecretKey using entropy from the operating system via `getrandom`.
+    ///
+    /// On wasm32-unknown-unknown entropy is taken from the JavaScript crypto API, so this works
+    /// in browsers and Node.
+    #[cfg(feature = "getrandom")]
+    pub fn from_os_rng() -> Self {
+        Self::random(&mut rand_core::OsRng)
     }
 
     /// KeyGenerate
@@ -45,41 +131,76 @@ impl SecretKey {
         if ikm.len() < 32 {
             return Err(AmclError::InvalidSecretKeySize);
         }
+        Ok(Self::key_generate_from_ikm(ikm, key_info))
+    }
 
-        let mut sk = Big::new();
-        let mut salt = KEY_SALT.to_vec();
-
-        while sk.is_zilch() {
-            // salt = H(salt)
-            let mut hash256 = HASH256::new();
-            hash256.init();
-            hash256.process_array(&salt);
-            salt = hash256.hash().to_vec();
+    // KeyGenerate for IKM of at least 32 bytes.
+    fn key_generate_from_ikm(ikm: &[u8], key_info: &[u8]) -> Self {
+        // salt = H(salt)
+        let mut salt = sha256(KEY_SALT);
 
+        loop {
             // PRK = HKDF-Extract(salt, IKM || I2OSP(0, 1))
-            let mut prk = Vec::<u8>::with_capacity(1 + ikm.len());
-            prk.extend_from_slice(ikm);
-            prk.push(0);
-            let prk = HASH256::hkdf_extract(&salt, &prk);
+            let mut prk = hmac_sha256(&salt, &[ikm, &[0]]);
 
             // OKM = HKDF-Expand(PRK, key_info || I2OSP(L, 2), L)
-            let mut info = key_info.to_vec();
-            info.extend_from_slice(&[0, L]);
-            let okm = HASH256::hkdf_extend(&prk, &info, L);
+            let mut okm = hkdf_expand_l(&prk, key_info);
+            prk.zeroize();
 
             // SK = OS2IP(OKM) mod r
             let r = Big::new_ints(&CURVE_ORDER);
-            sk = Big::from_bytes(&okm);
+            let mut sk = Big::from_bytes(&okm);
+            okm.zeroize();
             sk.rmod(&r);
+
+            if !sk.is_zilch() {
+                return Self { x: sk };
+            }
+            salt = sha256(&salt);
         }
-        Ok(Self { x: sk })
     }
 
     /// Instantiate a SecretKey from existing bytes.
-    pub fn from_bytes(input: &[u8]) -> Result<SecretKey, AmclError> {
-        Ok(Self {
-            x: secret_key_from_bytes(input)?,
-        })
+    ///
+    /// The bytes must be a big-endian scalar x with 0 < x < r.
+    pub fn from_bytes(input: &[u8]) -> Result<SecretKey, DecodeError> {
+        if input.len() != SECRET_KEY_BYTES {
+            return Err(DecodeError::InvalidLength {
+                expected: SECRET_KEY_BYTES,
+                actual: input.len(),
+            });
+        }
+        let x = secret_key_from_bytes(input).map_err(|_| DecodeError::InvalidSecretKey)?;
+
+        // Ensure 0 < x < r
+        if x.is_zilch() || Big::comp(&x, &Big::new_ints(&CURVE_ORDER)) >= 0 {
+            return Err(DecodeError::InvalidSecretKey);
+        }
+        Ok(Self { x })
+    }
+
+    /// Instantiate a SecretKey from existing bytes, reducing the big-endian scalar modulo r.
+    ///
+    /// Unlike `from_bytes()` scalars >= r are accepted, as done by some other libraries.
+    /// Scalars which reduce to zero are rejected.
+    pub fn from_bytes_reduced(input: &[u8]) -> Result<SecretKey, DecodeError> {
+        if input.len() != SECRET_KEY_BYTES {
+            return Err(DecodeError::InvalidLength {
+                expected: SECRET_KEY_BYTES,
+                actual: input.len(),
+            });
+        }
+
+        let mut bytes = [0u8; MODBYTES];
+        bytes[MODBYTES - SECRET_KEY_BYTES..].copy_from_slice(input);
+        let mut x = Big::from_bytes(&bytes);
+        bytes.zeroize();
+
+        x.rmod(&Big::new_ints(&CURVE_ORDER));
+        if x.is_zilch() {
+            return Err(DecodeError::InvalidSecretKey);
+        }
+        Ok(Self { x })
     }
 
     /// Export the SecretKey as 32 bytes.
@@ -87,15 +208,32 @@ impl SecretKey {
         secret_key_to_bytes(&self.x)
     }
 
+    /// Sign a message which has already been hashed to G2, e.g. by `G2Point::hash_to_curve()`
+    /// once for a whole committee.
+    pub fn sign_hashed(&self, msg_point: &G2Point) -> Signature {
+        Signature {
+            point: g2mul(&msg_point.0, self.as_raw()),
+        }
+    }
+
+    /// The raw amcl scalar, which may change between releases.
+    #[cfg(feature = "unstable-raw")]
     pub fn as_raw(&self) -> &Big {
         &self.x
     }
+
+    #[cfg(not(feature = "unstable-raw"))]
+    pub(crate) fn as_raw(&self) -> &Big {
+        &self.x
+    }
 }
 
+// Redacted so that key material cannot leak into logs, including through derive(Debug) of
+// containing types such as Keypair.
 #[cfg(feature = "std")]
 impl fmt::Debug for SecretKey {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.x.to_string())
+        write!(f, "SecretKey(<redacted>)")
     }
 }
 
@@ -117,79 +255,255 @@ impl Drop for SecretKey {
 #[derive(Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct PublicKey {
+    #[cfg(feature = "unstable-raw")]
     pub point: GroupG1,
+    #[cfg(not(feature = "unstable-raw"))]
+    pub(crate) point: GroupG1,
 }
 
 impl PublicKey {
     /// Instantiate a PublicKey from some SecretKey.
     pub fn from_secret_key(sk: &SecretKey) -> Self {
         PublicKey {
-            point: {
-                #[cfg(feature = "std")]
-                {
-                    g1mul(&amcl_utils::GENERATORG1, sk.as_raw())
-                }
-                #[cfg(not(feature = "std"))]
-                {
-                    g1mul(&amcl_utils::GroupG1::generator(), sk.as_raw())
-                }
-            },
+            point: g1mul(&amcl_utils::GENERATORG1, sk.as_raw()),
         }
     }
 
     /// Instantiate a PublicKey from compressed bytes.
-    pub fn from_bytes(bytes: &[u8]) -> Result<PublicKey, AmclError> {
-        let public_key = Self::from_bytes_unchecked(bytes)?;
-        if !public_key.key_validate() {
-            return Err(AmclError::InvalidPoint);
+    ///
+    /// The key is validated as in `key_validate_bytes()`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<PublicKey, DecodeError> {
+        let point = decompress_g1(bytes)?;
+        if point.is_infinity() {
+            return Err(DecodeError::Infinity);
         }
 
-        Ok(public_key)
+        Ok(Self { point })
+    }
+
+    /// Instantiate a PublicKey from bytes under the given encoding rules.
+    ///
+    /// The key is validated as in `from_bytes()`, the rules only relax how it may be encoded.
+    pub fn from_bytes_with_strictness(
+        bytes: &[u8],
+        strictness: &Strictness,
+    ) -> Result<PublicKey, DecodeError> {
+        let point = strictness::decode_g1(bytes, strictness)?;
+        if point.is_infinity() {
+            return Err(DecodeError::Infinity);
+        }
+
+        Ok(Self { point })
     }
 
     /// Instantiate a PublicKey from compressed bytes.
-    pub fn from_bytes_unchecked(bytes: &[u8]) -> Result<PublicKey, AmclError> {
-        let point = decompress_g1(bytes)?;
+    ///
+    /// Does not validate the key, MUST only be used on verified keys.
+    pub fn from_bytes_unchecked(bytes: &[u8]) -> Result<PublicKey, DecodeError> {
+        let point = decompress_g1_unchecked(bytes)?;
         let public_key = Self { point };
 
         Ok(public_key)
     }
 
+    /// Instantiate many PublicKeys from compressed bytes, e.g. an entire validator registry.
+    ///
+    /// Each key is validated as in `from_bytes()`. With the `parallel` feature the keys are
+    /// decompressed and validated across threads.
+    #[cfg(feature = "alloc")]
+    pub fn from_bytes_batch(bytes: &[&[u8]]) -> Result<Vec<PublicKey>, DecodeError> {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            thread_pool::install(|| {
+                bytes
+                    .par_iter()
+                    .map(|bytes| Self::from_bytes(bytes))
+                    .collect()
+            })
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            bytes.iter().map(|bytes| Self::from_bytes(bytes)).collect()
+        }
+    }
+
     /// Export the PublicKey to compressed bytes.
     pub fn as_bytes(&self) -> [u8; G1_BYTES] {
         compress_g1(&self.point)
     }
 
+    /// Export the PublicKey to uncompressed (x, y) bytes.
+    ///
+    /// The same as `as_uncompressed_bytes()`, named as `Signature::to_uncompressed_bytes()`.
+    pub fn to_uncompressed_bytes(&self) -> [u8; G1_BYTES * 2] {
+        self.as_uncompressed_bytes()
+    }
+
     /// Export the public key to uncompress (x, y) bytes
-    pub fn as_uncompressed_bytes(&mut self) -> [u8; G1_BYTES * 2] {
+    pub fn as_uncompressed_bytes(&self) -> [u8; G1_BYTES * 2] {
         serialize_uncompressed_g1(&self.point)
     }
 
-    /// InstantiatePublicKey from uncompress (x, y) bytes
+    /// Instantiate a PublicKey from uncompressed (x, y) bytes.
     ///
-    /// Does not validate the key, MUST only be used on verified keys.
-    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<PublicKey, AmclError> {
-        if bytes.len() != G1_BYTES * 2 {
-            return Err(AmclError::InvalidG1Size);
+    /// The key is validated as in `from_bytes()`.
+    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<PublicKey, DecodeError> {
+        let point = deserialize_uncompressed_g1(bytes)?;
+        if point.is_infinity() {
+            return Err(DecodeError::Infinity);
         }
+        if !subgroup_check_g1(&point) {
+            return Err(DecodeError::NotInSubgroup);
+        }
+
+        Ok(Self { point })
+    }
+
+    /// Instantiate a PublicKey from uncompressed (x, y) bytes.
+    ///
+    /// Does not validate the key, MUST only be used on verified keys.
+    pub fn from_uncompressed_bytes_unchecked(bytes: &[u8]) -> Result<PublicKey, DecodeError> {
         Ok(Self {
-            point: deserialize_g1(bytes)?,
+            point: deserialize_uncompressed_g1(bytes)?,
         })
     }
 
+    /// Instantiate a PublicKey from big-endian affine coordinates (x, y).
+    ///
+    /// The key is validated, (0, 0) is the point at infinity which is rejected.
+    pub fn from_coordinates(
+        x: &[u8; G1_BYTES],
+        y: &[u8; G1_BYTES],
+    ) -> Result<PublicKey, DecodeError> {
+        let point = g1_from_coordinates(x, y)?;
+        if point.is_infinity() {
+            return Err(DecodeError::Infinity);
+        }
+        if !subgroup_check_g1(&point) {
+            return Err(DecodeError::NotInSubgroup);
+        }
+
+        Ok(Self { point })
+    }
+
+    /// Export the PublicKey as big-endian affine coordinates (x, y).
+    pub fn as_coordinates(&self) -> ([u8; G1_BYTES], [u8; G1_BYTES]) {
+        g1_to_coordinates(&self.point)
+    }
+
     /// KeyValidate
     ///
     /// Verifies a public key is valid
     /// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-2.5
     pub fn key_validate(&self) -> bool {
-        if self.point.is_infinity() || !subgroup_check_g1(&self.point) {
+        if self.point.is_infinity() || !self.is_in_subgroup() {
             return false;
         }
         true
     }
+
+    /// KeyValidate - compressed bytes
+    ///
+    /// Verifies compressed bytes are a valid public key: of the correct length, a canonical
+    /// encoding of a point on the curve, in the G1 subgroup and not infinity.
+    /// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-2.5
+    pub fn key_validate_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+        Self::from_bytes(bytes).map(|_| ())
+    }
+
+    /// KeyValidate of many PublicKeys, e.g. after `from_bytes_unchecked()` of a validator
+    /// registry.
+    ///
+    /// With the `parallel` feature the keys are validated across threads.
+    pub fn key_validate_batch(keys: &[PublicKey]) -> bool {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            thread_pool::install(|| keys.par_iter().all(|key| key.key_validate()))
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            keys.iter().all(|key| key.key_validate())
+        }
+    }
+
+    /// Instantiate a PublicKey from the 128 byte encoding used by the EIP-2537 precompiles.
+    ///
+    /// The key is validated as in `key_validate_bytes()`.
+    pub fn from_eip2537_bytes(bytes: &[u8]) -> Result<PublicKey, DecodeError> {
+        let point = g1_from_eip2537(bytes)?;
+        if point.is_infinity() {
+            return Err(DecodeError::Infinity);
+        }
+        if !subgroup_check_g1(&point) {
+            return Err(DecodeError::NotInSubgroup);
+        }
+        Ok(Self { point })
+    }
+
+    /// Export the PublicKey to the 128 byte encoding used by the EIP-2537 precompiles.
+    pub fn to_eip2537_bytes(&self) -> [u8; EIP2537_G1_BYTES] {
+        g1_to_eip2537(&self.point)
+    }
+
+    /// Returns true if the PublicKey is in the G1 subgroup.
+    ///
+    /// Uses the endomorphism based check, which is faster than multiplying by the group order.
+    pub fn is_in_subgroup(&self) -> bool {
+        subgroup_check_g1(&self.point)
+    }
+
+    /// Returns true if the PublicKey is on the curve E1, which is not a subgroup check.
+    ///
+    /// Every PublicKey decoded by this crate is on the curve, including those of
+    /// `from_bytes_unchecked()`.
+    pub fn is_on_curve(&self) -> bool {
+        on_curve_check_g1(&self.point)
+    }
+
+    /// The G1 point underlying the PublicKey, which was validated when the key was created.
+    ///
+    /// Useful for multi-scalar multiplications, commitments and custom pairings which would
+    /// otherwise decompress `as_bytes()` again.
+    pub fn as_raw_point(&self) -> G1Point {
+        G1Point(self.point.clone())
+    }
+
+    /// CoreVerify - message already hashed to G2
+    ///
+    /// Verifies a Signature against the PublicKey where the message point was computed by the
+    /// caller, e.g. by `G2Point::hash_to_curve()` once for a whole committee.
+    pub fn verify_hashed(&self, msg_point: &G2Point, signature: &Signature) -> bool {
+        signature
+            .verify_hash_point(msg_point.0.clone(), self)
+            .is_ok()
+    }
+}
+
+// Hashing and ordering use the compressed bytes, ordering lexicographically, so equal points
+// hash and compare equal regardless of their projective representation.
+impl Hash for PublicKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
+}
+
+impl Ord for PublicKey {
+    fn cmp(&self, other: &PublicKey) -> Ordering {
+        self.as_bytes().cmp(&other.as_bytes())
+    }
+}
+
+impl PartialOrd for PublicKey {
+    fn partial_cmp(&self, other: &PublicKey) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 /// A helper which stores a BLS public and private key pair.
+///
+/// Debug output redacts the SecretKey.
 #[derive(Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct Keypair {
@@ -199,11 +513,19 @@ pub struct Keypair {
 
 impl Keypair {
     /// Instantiate a Keypair using SecretKey::random().
-    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+    pub fn random<R: RngCore + CryptoRng + ?Sized>(rng: &mut R) -> Self {
         let sk = SecretKey::random(rng);
         let pk = PublicKey::from_secret_key(&sk);
         Keypair { sk, pk }
     }
+
+    /// Instantiate a Keypair using SecretKey::from_os_rng().
+    #[cfg(feature = "getrandom")]
+    pub fn from_os_rng() -> Self {
+        let sk = SecretKey::from_os_rng();
+        let pk = PublicKey::from_secret_key(&sk);
+        Keypair { sk, pk }
+    }
 }
 
 #[cfg(test)]
@@ -211,6 +533,9 @@ mod tests {
     extern crate hex;
     extern crate rand;
 
+    use self::rand::Rng;
+    use super::super::errors::EncodingFlag;
+    use super::super::points::Scalar;
     use super::super::signature::Signature;
     use super::*;
 
@@ -241,23 +566,86 @@ mod tests {
     fn test_public_key_uncompressed_serialization_isomorphism() {
         for _ in 0..30 {
             let sk = SecretKey::random(&mut rand::thread_rng());
-            let mut pk = PublicKey::from_secret_key(&sk);
+            let pk = PublicKey::from_secret_key(&sk);
             let decoded_pk = pk.as_uncompressed_bytes().to_vec();
-            let mut encoded_pk = PublicKey::from_uncompressed_bytes(&decoded_pk).unwrap();
+            let encoded_pk = PublicKey::from_uncompressed_bytes(&decoded_pk).unwrap();
             let re_recoded_pk = encoded_pk.as_uncompressed_bytes().to_vec().to_vec();
             assert_eq!(decoded_pk, re_recoded_pk);
         }
     }
 
+    #[test]
+    fn test_public_key_to_uncompressed_bytes() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random(&mut rand::thread_rng()));
+        let bytes = pk.to_uncompressed_bytes();
+        assert_eq!(PublicKey::from_uncompressed_bytes(&bytes).unwrap(), pk);
+
+        // Sort flag is not allowed in uncompressed bytes
+        let mut bytes = bytes;
+        bytes[0] |= 32;
+        assert_eq!(
+            PublicKey::from_uncompressed_bytes(&bytes),
+            Err(DecodeError::InvalidFlag(EncodingFlag::Sort))
+        );
+    }
+
+    #[test]
+    fn test_public_key_coordinates() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random(&mut rand::thread_rng()));
+        let (x, y) = pk.as_coordinates();
+        assert_eq!(PublicKey::from_coordinates(&x, &y).unwrap(), pk);
+
+        // Infinity
+        assert_eq!(
+            PublicKey::from_coordinates(&[0; 48], &[0; 48]),
+            Err(DecodeError::Infinity)
+        );
+
+        // The point (0, 2) is not in the group
+        let mut two = [0; 48];
+        two[47] = 2;
+        assert_eq!(
+            PublicKey::from_coordinates(&[0; 48], &two),
+            Err(DecodeError::NotInSubgroup)
+        );
+    }
+
+    #[test]
+    fn test_public_key_eip2537_bytes() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random(&mut rand::thread_rng()));
+        let bytes = pk.to_eip2537_bytes();
+        assert_eq!(&bytes[16..64], &pk.as_coordinates().0[..]);
+        assert_eq!(PublicKey::from_eip2537_bytes(&bytes).unwrap(), pk);
+
+        // Infinity
+        assert_eq!(
+            PublicKey::from_eip2537_bytes(&[0; 128]),
+            Err(DecodeError::Infinity)
+        );
+
+        // The point (0, 2) is not in the group
+        let mut bytes = [0; 128];
+        bytes[127] = 2;
+        assert_eq!(
+            PublicKey::from_eip2537_bytes(&bytes),
+            Err(DecodeError::NotInSubgroup)
+        );
+    }
+
     #[test]
     fn test_public_key_uncompressed_serialization_infinity() {
         let mut pk_inf_bytes = vec![0u8; 48];
         pk_inf_bytes[0] = 192;
-        let mut pk = PublicKey::from_bytes_unchecked(&pk_inf_bytes).unwrap();
+        let pk = PublicKey::from_bytes_unchecked(&pk_inf_bytes).unwrap();
         let decoded_pk = pk.as_uncompressed_bytes().to_vec();
-        let recoded_pk = PublicKey::from_uncompressed_bytes(&decoded_pk).unwrap();
+        let recoded_pk = PublicKey::from_uncompressed_bytes_unchecked(&decoded_pk).unwrap();
         assert_eq!(recoded_pk, pk);
-        assert!(recoded_pk.point.is_infinity())
+        assert!(recoded_pk.point.is_infinity());
+
+        assert_eq!(
+            PublicKey::from_uncompressed_bytes(&decoded_pk),
+            Err(DecodeError::Infinity)
+        );
     }
 
     #[test]
@@ -265,25 +653,37 @@ mod tests {
         let bytes = vec![1; 1];
         assert_eq!(
             PublicKey::from_uncompressed_bytes(&bytes),
-            Err(AmclError::InvalidG1Size)
+            Err(DecodeError::InvalidLength {
+                expected: 96,
+                actual: 1
+            })
         );
 
         let bytes = vec![1; 95];
         assert_eq!(
             PublicKey::from_uncompressed_bytes(&bytes),
-            Err(AmclError::InvalidG1Size)
+            Err(DecodeError::InvalidLength {
+                expected: 96,
+                actual: 95
+            })
         );
 
         let bytes = vec![1; 97];
         assert_eq!(
             PublicKey::from_uncompressed_bytes(&bytes),
-            Err(AmclError::InvalidG1Size)
+            Err(DecodeError::InvalidLength {
+                expected: 96,
+                actual: 97
+            })
         );
 
         let bytes = vec![];
         assert_eq!(
             PublicKey::from_uncompressed_bytes(&bytes),
-            Err(AmclError::InvalidG1Size)
+            Err(DecodeError::InvalidLength {
+                expected: 96,
+                actual: 0
+            })
         );
     }
 
@@ -295,7 +695,7 @@ mod tests {
         bytes[95] = 1;
         assert_eq!(
             PublicKey::from_uncompressed_bytes(&bytes),
-            Err(AmclError::InvalidPoint)
+            Err(DecodeError::NotOnCurve)
         );
     }
 
@@ -304,26 +704,94 @@ mod tests {
         let bytes = vec![];
         assert_eq!(
             SecretKey::from_bytes(&bytes),
-            Err(AmclError::InvalidSecretKeySize)
+            Err(DecodeError::InvalidLength {
+                expected: 32,
+                actual: 0
+            })
         );
 
         let bytes = vec![1; 33];
         assert_eq!(
             SecretKey::from_bytes(&bytes),
-            Err(AmclError::InvalidSecretKeySize)
+            Err(DecodeError::InvalidLength {
+                expected: 32,
+                actual: 33
+            })
         );
 
         let bytes = vec![0; 32];
         assert_eq!(
             SecretKey::from_bytes(&bytes),
-            Err(AmclError::InvalidSecretKeyRange)
+            Err(DecodeError::InvalidSecretKey)
         );
 
         let bytes = vec![255; 32];
         assert_eq!(
             SecretKey::from_bytes(&bytes),
-            Err(AmclError::InvalidSecretKeyRange)
+            Err(DecodeError::InvalidSecretKey)
+        );
+    }
+
+    #[test]
+    fn test_secret_key_from_bytes_curve_order() {
+        let r = hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001")
+            .unwrap();
+        assert_eq!(
+            SecretKey::from_bytes(&r),
+            Err(DecodeError::InvalidSecretKey)
+        );
+
+        // r - 1 is the largest valid SecretKey
+        let mut r_minus_one = r.clone();
+        r_minus_one[31] = 0;
+        assert!(SecretKey::from_bytes(&r_minus_one).is_ok());
+    }
+
+    #[test]
+    fn test_secret_key_from_bytes_reduced() {
+        let r = hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001")
+            .unwrap();
+        assert_eq!(
+            SecretKey::from_bytes_reduced(&r),
+            Err(DecodeError::InvalidSecretKey)
+        );
+        assert_eq!(
+            SecretKey::from_bytes_reduced(&[0; 32]),
+            Err(DecodeError::InvalidSecretKey)
+        );
+        assert_eq!(
+            SecretKey::from_bytes_reduced(&[1; 31]),
+            Err(DecodeError::InvalidLength {
+                expected: 32,
+                actual: 31
+            })
+        );
+
+        // r + 1 reduces to 1
+        let mut r_plus_one = r.clone();
+        r_plus_one[31] = 2;
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(
+            SecretKey::from_bytes_reduced(&r_plus_one).unwrap(),
+            SecretKey::from_bytes(&one).unwrap()
         );
+
+        // Valid scalars are unchanged
+        let sk = SecretKey::random(&mut rand::thread_rng());
+        assert_eq!(SecretKey::from_bytes_reduced(&sk.as_bytes()).unwrap(), sk);
+    }
+
+    #[test]
+    fn test_secret_key_debug_redacted() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let sk_hex = hex::encode(keypair.sk.as_bytes());
+        assert_eq!(format!("{:?}", keypair.sk), "SecretKey(<redacted>)");
+
+        let debug = format!("{:?}", keypair);
+        assert!(debug.contains("SecretKey(<redacted>)"));
+        assert!(!debug.contains(&sk_hex));
+        assert!(!debug.contains(&keypair.sk.as_raw().to_string()));
     }
 
     #[test]
@@ -360,6 +828,85 @@ mod tests {
         assert!(signature.verify(&message, &pk));
     }
 
+    #[test]
+    fn test_sign_verify_hashed() {
+        let message = "cats".as_bytes();
+        let msg_point = G2Point::hash_to_curve(message);
+
+        for _ in 0..3 {
+            let keypair = Keypair::random(&mut rand::thread_rng());
+            let signature = keypair.sk.sign_hashed(&msg_point);
+            assert_eq!(signature, Signature::new(message, &keypair.sk));
+            assert!(keypair.pk.verify_hashed(&msg_point, &signature));
+            assert!(signature.verify(message, &keypair.pk));
+
+            let other_point = G2Point::hash_to_curve(b"dogs");
+            assert!(!keypair.pk.verify_hashed(&other_point, &signature));
+        }
+    }
+
+    #[test]
+    fn test_hkdf_matches_amcl() {
+        let mut rng = rand::thread_rng();
+        for ikm_len in [32, 64, 100] {
+            let ikm: Vec<u8> = (0..ikm_len).map(|_| rng.gen()).collect();
+            let salt: [u8; 32] = rng.gen();
+            let key_info: [u8; 7] = rng.gen();
+
+            let mut ikm_zero = ikm.clone();
+            ikm_zero.push(0);
+            let prk = HASH256::hkdf_extract(&salt, &ikm_zero);
+            assert_eq!(hmac_sha256(&salt, &[&ikm, &[0]]), prk);
+
+            let mut info = key_info.to_vec();
+            info.extend_from_slice(&[0, L]);
+            assert_eq!(
+                hkdf_expand_l(&prk, &key_info).to_vec(),
+                HASH256::hkdf_extend(&prk, &info, L)
+            );
+        }
+    }
+
+    // A fixed byte RNG standing in for a caller provided entropy source.
+    struct FixedRng(u8);
+
+    impl RngCore for FixedRng {
+        fn next_u32(&mut self) -> u32 {
+            u32::from(self.0)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            u64::from(self.0)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.iter_mut().for_each(|byte| *byte = self.0);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for FixedRng {}
+
+    #[test]
+    fn test_random_secret_key_custom_rng() {
+        let sk = SecretKey::random(&mut FixedRng(7));
+        assert_eq!(sk, SecretKey::key_generate(&[7; 32], &[]).unwrap());
+        assert_eq!(Keypair::random(&mut FixedRng(7)).sk, sk);
+        assert!(SecretKey::random(&mut FixedRng(8)) != sk);
+    }
+
+    #[test]
+    #[cfg(feature = "getrandom")]
+    fn test_from_os_rng() {
+        let keypair = Keypair::from_os_rng();
+        assert_eq!(keypair.pk, PublicKey::from_secret_key(&keypair.sk));
+        assert!(SecretKey::from_os_rng() != keypair.sk);
+    }
+
     #[test]
     fn test_key_validate() {
         // Attempt to deserialise the point (0, 2) which is not in the group.
@@ -368,20 +915,143 @@ mod tests {
 
         assert_eq!(
             PublicKey::from_bytes(&pk_bytes),
-            Err(AmclError::InvalidPoint)
+            Err(DecodeError::NotInSubgroup)
+        );
+        let pk = PublicKey::from_bytes_unchecked(&pk_bytes).unwrap();
+        assert!(pk.is_on_curve());
+        assert!(!pk.is_in_subgroup());
+        assert!(!pk.key_validate());
+    }
+
+    #[test]
+    fn test_key_validate_bytes() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random(&mut rand::thread_rng()));
+        let pk_bytes = pk.as_bytes();
+        assert_eq!(PublicKey::key_validate_bytes(&pk_bytes), Ok(()));
+
+        assert_eq!(
+            PublicKey::key_validate_bytes(&pk_bytes[1..]),
+            Err(DecodeError::InvalidLength {
+                expected: 48,
+                actual: 47
+            })
+        );
+
+        // Infinity
+        let mut infinity_bytes = [0; 48];
+        infinity_bytes[0] = 192;
+        assert_eq!(
+            PublicKey::key_validate_bytes(&infinity_bytes),
+            Err(DecodeError::Infinity)
+        );
+
+        // The point (0, 2) is not in the group
+        let mut not_in_subgroup_bytes = [0; 48];
+        not_in_subgroup_bytes[0] = 128;
+        assert_eq!(
+            PublicKey::key_validate_bytes(&not_in_subgroup_bytes),
+            Err(DecodeError::NotInSubgroup)
         );
-        assert!(PublicKey::from_bytes_unchecked(&pk_bytes).is_ok());
     }
 
     #[test]
     fn test_public_key_from_bytes_infinity() {
         // Infinity
         let mut pk_bytes = vec![0; 48];
-        pk_bytes[0] = 196;
+        pk_bytes[0] = 192;
 
+        assert_eq!(PublicKey::from_bytes(&pk_bytes), Err(DecodeError::Infinity));
+
+        // Infinity with non-zero bits
+        pk_bytes[0] = 196;
         assert_eq!(
             PublicKey::from_bytes(&pk_bytes),
-            Err(AmclError::InvalidPoint)
+            Err(DecodeError::InvalidFlag(EncodingFlag::Infinity))
+        );
+    }
+
+    #[test]
+    fn test_public_key_from_bytes_batch() {
+        let pks: Vec<[u8; G1_BYTES]> = (0..10)
+            .map(|_| {
+                PublicKey::from_secret_key(&SecretKey::random(&mut rand::thread_rng())).as_bytes()
+            })
+            .collect();
+        let mut bytes: Vec<&[u8]> = pks.iter().map(|pk| &pk[..]).collect();
+
+        let decoded = PublicKey::from_bytes_batch(&bytes).unwrap();
+        assert_eq!(decoded.len(), pks.len());
+        for (pk, pk_bytes) in decoded.iter().zip(pks.iter()) {
+            assert_eq!(&pk.as_bytes(), pk_bytes);
+        }
+
+        // A single invalid key fails the batch
+        let mut invalid = vec![0; 48];
+        invalid[0] = 128;
+        bytes[4] = &invalid;
+        assert_eq!(
+            PublicKey::from_bytes_batch(&bytes),
+            Err(DecodeError::NotInSubgroup)
+        );
+
+        assert_eq!(PublicKey::from_bytes_batch(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_key_validate_batch() {
+        let mut rng = rand::thread_rng();
+        let mut keys: Vec<PublicKey> = (0..80)
+            .map(|_| PublicKey::from_secret_key(&SecretKey::random(&mut rng)))
+            .collect();
+        assert!(PublicKey::key_validate_batch(&keys));
+
+        // The point (0, 2) is not in the group
+        let mut pk_bytes = vec![0; 48];
+        pk_bytes[0] = 128;
+        keys[17] = PublicKey::from_bytes_unchecked(&pk_bytes).unwrap();
+        assert!(!PublicKey::key_validate_batch(&keys));
+
+        // Infinity
+        let mut pk_bytes = vec![0; 48];
+        pk_bytes[0] = 192;
+        keys[17] = PublicKey::from_bytes_unchecked(&pk_bytes).unwrap();
+        assert!(!PublicKey::key_validate_batch(&keys));
+    }
+
+    #[test]
+    fn test_public_key_hash_ord() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let keys: Vec<PublicKey> = (0..10)
+            .map(|_| Keypair::random(&mut rand::thread_rng()).pk)
+            .collect();
+
+        // Equal keys hash equally, even if only one is in affine form
+        let decoded = PublicKey::from_bytes(&keys[0].as_bytes()).unwrap();
+        let mut set = HashSet::new();
+        assert!(set.insert(keys[0].clone()));
+        assert!(!set.insert(decoded.clone()));
+        assert_eq!(keys[0].cmp(&decoded), Ordering::Equal);
+
+        // Ordered lexicographically by compressed bytes
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert!(sorted
+            .windows(2)
+            .all(|pair| pair[0].as_bytes() < pair[1].as_bytes()));
+        let btree: BTreeSet<PublicKey> = keys.iter().cloned().collect();
+        assert_eq!(btree.into_iter().collect::<Vec<_>>(), sorted);
+    }
+
+    #[test]
+    fn test_as_raw_point() {
+        let sk = SecretKey::random(&mut rand::thread_rng());
+        let pk = PublicKey::from_secret_key(&sk);
+        let point = pk.as_raw_point();
+        assert_eq!(point.as_bytes(), pk.as_bytes());
+        assert_eq!(
+            point,
+            G1Point::generator().mul(&Scalar::from_bytes(&sk.as_bytes()).unwrap())
         );
     }
 
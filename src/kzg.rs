@@ -0,0 +1,872 @@
+//! KZG polynomial commitments over BLS12-381, as used for EIP-4844 blobs.
+//!
+//! Polynomials are given in evaluation form, as blobs of 32 byte big-endian field elements
+//! evaluated at the roots of unity in bit-reversed order, see
+//! https://github.com/ethereum/consensus-specs/blob/dev/specs/deneb/polynomial-commitments.md
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use super::amcl_utils::{
+    ate2_evaluation, compress_g1, decompress_g1, decompress_g2, g1mul, g2mul, Big, GroupG1,
+    GroupG2, CURVE_ORDER, G1_BYTES, G2_BYTES, GENERATORG1, GENERATORG2, MODBYTES,
+    NEGATIVE_GENERATORG1, NEGATIVE_GENERATORG2,
+};
+use super::errors::{DecodeError, KzgError};
+use super::hash_to_curve::{HashToCurveHasher, XmdHash};
+use super::hex_encoding::decode_hex;
+use super::points::Scalar;
+use rand_core::{CryptoRng, RngCore};
+
+/// The length of an encoded field element.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+
+// Domain separation of the challenge at which a blob is opened.
+const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8] = b"FSBLOBVERIFY_V1_";
+
+// A generator of the multiplicative group of the scalar field.
+const PRIMITIVE_ROOT_OF_UNITY: isize = 7;
+// The largest power of two dividing r - 1.
+const TWO_ADICITY: u32 = 32;
+
+/// A commitment to a polynomial, a point in G1.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct KzgCommitment {
+    point: GroupG1,
+}
+
+impl KzgCommitment {
+    /// Instantiate a KzgCommitment from compressed bytes.
+    ///
+    /// The point must be in G1, the point at infinity commits to the zero polynomial.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self { point: decompress_g1(bytes)? })
+    }
+
+    /// Export the KzgCommitment to compressed bytes.
+    pub fn as_bytes(&self) -> [u8; G1_BYTES] {
+        compress_g1(&self.point)
+    }
+}
+
+/// A proof of the evaluation of a committed polynomial, a point in G1.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct KzgProof {
+    point: GroupG1,
+}
+
+impl KzgProof {
+    /// Instantiate a KzgProof from compressed bytes.
+    ///
+    /// The point must be in G1, the point at infinity is accepted.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self { point: decompress_g1(bytes)? })
+    }
+
+    /// Export the KzgProof to compressed bytes.
+    pub fn as_bytes(&self) -> [u8; G1_BYTES] {
+        compress_g1(&self.point)
+    }
+}
+
+/// The trusted setup: powers of a secret tau in G1, in Lagrange form, and in G2.
+#[derive(Clone)]
+pub struct KzgSettings {
+    // The roots of unity in bit-reversed order, where blob elements are evaluated
+    roots_of_unity: Vec<Big>,
+    // [L_i(tau)]G1 for the Lagrange polynomial L_i of each root of unity
+    g1_lagrange: Vec<GroupG1>,
+    // [tau]G2
+    g2_tau: GroupG2,
+}
+
+impl KzgSettings {
+    /// Load KzgSettings from the contents of a trusted setup file.
+    ///
+    /// The format is that of `trusted_setup.txt` in the consensus specs and c-kzg: the number of
+    /// G1 points, the number of G2 points, then each G1 point in Lagrange form followed by each
+    /// G2 point in monomial form, all as compressed hex separated by whitespace.
+    ///
+    /// No file system access is needed, so no_std consumers may embed the setup with
+    /// `include_str!`, or use `from_bytes()` with `include_bytes!` to skip the hex decoding.
+    pub fn load_trusted_setup(contents: &str) -> Result<Self, KzgError> {
+        let mut words = contents.split_whitespace();
+        let mut count = || -> Result<usize, KzgError> {
+            words.next().and_then(|word| word.parse().ok()).ok_or(KzgError::InvalidTrustedSetup)
+        };
+        let n_g1 = count()?;
+        let n_g2 = count()?;
+
+        let g1_lagrange = words
+            .by_ref()
+            .take(n_g1)
+            .map(|word| decompress_g1(&decode_hex::<G1_BYTES>(word)?))
+            .collect::<Result<Vec<GroupG1>, DecodeError>>()
+            .map_err(|_| KzgError::InvalidTrustedSetup)?;
+        let g2_monomial = words
+            .by_ref()
+            .take(n_g2)
+            .map(|word| decompress_g2(&decode_hex::<G2_BYTES>(word)?))
+            .collect::<Result<Vec<GroupG2>, DecodeError>>()
+            .map_err(|_| KzgError::InvalidTrustedSetup)?;
+        if g1_lagrange.len() != n_g1 || g2_monomial.len() != n_g2 || words.next().is_some() {
+            return Err(KzgError::InvalidTrustedSetup);
+        }
+
+        Self::from_points(g1_lagrange, &g2_monomial)
+    }
+
+    /// Instantiate KzgSettings from concatenated compressed points.
+    ///
+    /// `g1_lagrange` is the powers of tau in G1 in Lagrange form and bit-reversed order, its
+    /// length sets the number of field elements in a blob and must be a power of two.
+    /// `g2_monomial` is the powers of tau in G2, of which G2 and [tau]G2 are used.
+    ///
+    /// The points are validated as in `load_trusted_setup()`.
+    pub fn from_bytes(g1_lagrange: &[u8], g2_monomial: &[u8]) -> Result<Self, KzgError> {
+        let g1_chunks = g1_lagrange.chunks_exact(G1_BYTES);
+        let g2_chunks = g2_monomial.chunks_exact(G2_BYTES);
+        if !g1_chunks.remainder().is_empty() || !g2_chunks.remainder().is_empty() {
+            return Err(KzgError::InvalidTrustedSetup);
+        }
+        let g1_lagrange = g1_chunks
+            .map(decompress_g1)
+            .collect::<Result<Vec<GroupG1>, DecodeError>>()
+            .map_err(|_| KzgError::InvalidTrustedSetup)?;
+        let g2_monomial = g2_chunks
+            .map(decompress_g2)
+            .collect::<Result<Vec<GroupG2>, DecodeError>>()
+            .map_err(|_| KzgError::InvalidTrustedSetup)?;
+        Self::from_points(g1_lagrange, &g2_monomial)
+    }
+
+    // Points are in their subgroups from decompression, this checks the G1 and G2 points are
+    // powers of the same tau.
+    fn from_points(g1_lagrange: Vec<GroupG1>, g2_monomial: &[GroupG2]) -> Result<Self, KzgError> {
+        let n = g1_lagrange.len();
+        if !n.is_power_of_two() || n.trailing_zeros() > TWO_ADICITY {
+            return Err(KzgError::InvalidTrustedSetup);
+        }
+        if g2_monomial.len() < 2 || !g2_monomial[0].equals(&GENERATORG2) {
+            return Err(KzgError::InvalidTrustedSetup);
+        }
+        let g2_tau = g2_monomial[1].clone();
+        if g2_tau.is_infinity() {
+            return Err(KzgError::InvalidTrustedSetup);
+        }
+        let roots_of_unity = roots_of_unity(n);
+
+        // The Lagrange polynomials sum to 1, so sum L_i(tau) = 1
+        let mut one = GroupG1::new();
+        g1_lagrange.iter().for_each(|point| one.add(point));
+        if !one.equals(&GENERATORG1) {
+            return Err(KzgError::InvalidTrustedSetup);
+        }
+
+        // Interpolating x gives sum omega_i * L_i(tau) = tau, check e(G1, [tau]G2) matches
+        if n > 1 {
+            let tau = g1_lincomb(&g1_lagrange, &roots_of_unity);
+            if !ate2_evaluation(&GENERATORG2, &tau, &g2_tau, &NEGATIVE_GENERATORG1) {
+                return Err(KzgError::InvalidTrustedSetup);
+            }
+        }
+
+        Ok(Self { roots_of_unity, g1_lagrange, g2_tau })
+    }
+
+    /// The number of field elements in a blob.
+    pub fn field_elements_per_blob(&self) -> usize {
+        self.g1_lagrange.len()
+    }
+}
+
+/// Commit to the polynomial given by a blob.
+pub fn blob_to_kzg_commitment(
+    blob: &[u8],
+    settings: &KzgSettings,
+) -> Result<KzgCommitment, KzgError> {
+    let polynomial = blob_to_polynomial(blob, settings)?;
+    Ok(KzgCommitment { point: g1_lincomb(&settings.g1_lagrange, &polynomial) })
+}
+
+/// Open the polynomial given by a blob at z, returning the proof and the evaluation y.
+pub fn compute_kzg_proof(
+    blob: &[u8],
+    z: &[u8; BYTES_PER_FIELD_ELEMENT],
+    settings: &KzgSettings,
+) -> Result<(KzgProof, [u8; BYTES_PER_FIELD_ELEMENT]), KzgError> {
+    let polynomial = blob_to_polynomial(blob, settings)?;
+    let z = fr_from_bytes(z)?;
+    let (proof, y) = compute_kzg_proof_impl(&polynomial, &z, settings);
+    Ok((proof, fr_to_bytes(&y)))
+}
+
+/// Verify a proof that the committed polynomial evaluates to y at z.
+pub fn verify_kzg_proof(
+    commitment: &KzgCommitment,
+    z: &[u8; BYTES_PER_FIELD_ELEMENT],
+    y: &[u8; BYTES_PER_FIELD_ELEMENT],
+    proof: &KzgProof,
+    settings: &KzgSettings,
+) -> Result<bool, KzgError> {
+    let z = fr_from_bytes(z)?;
+    let y = fr_from_bytes(y)?;
+    Ok(verify_kzg_proof_impl(&commitment.point, &z, &y, &proof.point, settings))
+}
+
+/// Verify many proofs (commitment[i], z[i], y[i], proof[i]) with a single pairing check.
+///
+/// The proofs are combined with random scalars from the rng, so an invalid proof cannot be
+/// cancelled out by another.
+pub fn verify_kzg_proof_batch<R: RngCore + CryptoRng + ?Sized>(
+    rng: &mut R,
+    commitments: &[KzgCommitment],
+    zs: &[[u8; BYTES_PER_FIELD_ELEMENT]],
+    ys: &[[u8; BYTES_PER_FIELD_ELEMENT]],
+    proofs: &[KzgProof],
+    settings: &KzgSettings,
+) -> Result<bool, KzgError> {
+    let n = commitments.len();
+    if zs.len() != n || ys.len() != n || proofs.len() != n {
+        return Err(KzgError::LengthMismatch);
+    }
+
+    let mut points = Vec::with_capacity(n);
+    for i in 0..n {
+        points.push((
+            &commitments[i].point,
+            fr_from_bytes(&zs[i])?,
+            fr_from_bytes(&ys[i])?,
+            &proofs[i].point,
+        ));
+    }
+    Ok(verify_kzg_proof_batch_impl(rng, points.into_iter(), settings))
+}
+
+/// Compute the proof for a blob, opened at the Fiat-Shamir challenge of the blob and commitment.
+pub fn compute_blob_kzg_proof(
+    blob: &[u8],
+    commitment: &KzgCommitment,
+    settings: &KzgSettings,
+) -> Result<KzgProof, KzgError> {
+    let polynomial = blob_to_polynomial(blob, settings)?;
+    let z = compute_challenge(blob, commitment);
+    Ok(compute_kzg_proof_impl(&polynomial, &z, settings).0)
+}
+
+/// Verify a proof that a blob matches its commitment.
+pub fn verify_blob_kzg_proof(
+    blob: &[u8],
+    commitment: &KzgCommitment,
+    proof: &KzgProof,
+    settings: &KzgSettings,
+) -> Result<bool, KzgError> {
+    let polynomial = blob_to_polynomial(blob, settings)?;
+    let z = compute_challenge(blob, commitment);
+    let y = evaluate_polynomial(&polynomial, &z, settings);
+    Ok(verify_kzg_proof_impl(&commitment.point, &z, &y, &proof.point, settings))
+}
+
+/// Verify many proofs (blob[i], commitment[i], proof[i]) with a single pairing check.
+///
+/// Each blob is evaluated at its challenge and the openings are combined with random scalars as
+/// in `verify_kzg_proof_batch()`.
+pub fn verify_blob_kzg_proof_batch<R: RngCore + CryptoRng + ?Sized>(
+    rng: &mut R,
+    blobs: &[&[u8]],
+    commitments: &[KzgCommitment],
+    proofs: &[KzgProof],
+    settings: &KzgSettings,
+) -> Result<bool, KzgError> {
+    let n = blobs.len();
+    if commitments.len() != n || proofs.len() != n {
+        return Err(KzgError::LengthMismatch);
+    }
+
+    let mut points = Vec::with_capacity(n);
+    for i in 0..n {
+        let polynomial = blob_to_polynomial(blobs[i], settings)?;
+        let z = compute_challenge(blobs[i], &commitments[i]);
+        let y = evaluate_polynomial(&polynomial, &z, settings);
+        points.push((&commitments[i].point, z, y, &proofs[i].point));
+    }
+    Ok(verify_kzg_proof_batch_impl(rng, points.into_iter(), settings))
+}
+
+// Checks e(P - [y]G1, -G2) * e(proof, [tau - z]G2) == 1
+fn verify_kzg_proof_impl(
+    commitment: &GroupG1,
+    z: &Big,
+    y: &Big,
+    proof: &GroupG1,
+    settings: &KzgSettings,
+) -> bool {
+    let mut tau_minus_z = g2mul(&GENERATORG2, z);
+    tau_minus_z.neg();
+    tau_minus_z.add(&settings.g2_tau);
+    tau_minus_z.affine();
+
+    let mut p_minus_y = g1mul(&GENERATORG1, y);
+    p_minus_y.neg();
+    p_minus_y.add(commitment);
+    p_minus_y.affine();
+
+    let mut proof = proof.clone();
+    proof.affine();
+
+    ate2_evaluation(&tau_minus_z, &proof, &NEGATIVE_GENERATORG2, &p_minus_y)
+}
+
+// With random r[i], checks
+// e(sum r[i] * proof[i], -[tau]G2) * e(sum r[i] * (P[i] - [y[i]]G1 + [z[i]]proof[i]), G2) == 1
+fn verify_kzg_proof_batch_impl<'a, R, I>(rng: &mut R, proofs: I, settings: &KzgSettings) -> bool
+where
+    R: RngCore + CryptoRng + ?Sized,
+    I: Iterator<Item = (&'a GroupG1, Big, Big, &'a GroupG1)>,
+{
+    let mut proof_lincomb = GroupG1::new();
+    let mut commitment_lincomb = GroupG1::new();
+
+    for (commitment, z, y, proof) in proofs {
+        let rand = random_scalar(rng);
+        proof_lincomb.add(&g1mul(proof, &rand));
+
+        let mut point = g1mul(&GENERATORG1, &y);
+        point.neg();
+        point.add(commitment);
+        point.add(&g1mul(proof, &z));
+        commitment_lincomb.add(&g1mul(&point, &rand));
+    }
+
+    let mut negative_tau = settings.g2_tau.clone();
+    negative_tau.neg();
+    negative_tau.affine();
+    proof_lincomb.affine();
+    commitment_lincomb.affine();
+
+    ate2_evaluation(&negative_tau, &proof_lincomb, &GENERATORG2, &commitment_lincomb)
+}
+
+fn compute_kzg_proof_impl(polynomial: &[Big], z: &Big, settings: &KzgSettings) -> (KzgProof, Big) {
+    let y = evaluate_polynomial(polynomial, z, settings);
+
+    // q(x) = (p(x) - y) / (x - z), evaluated at each root of unity
+    let mut quotient = Vec::with_capacity(polynomial.len());
+    let mut root_index = None;
+    for (i, (p_i, omega_i)) in polynomial.iter().zip(&settings.roots_of_unity).enumerate() {
+        if Big::comp(z, omega_i) == 0 {
+            root_index = Some(i);
+            quotient.push(Big::new());
+            continue;
+        }
+        quotient.push(fr_mul(&fr_sub(p_i, &y), &fr_inverse(&fr_sub(omega_i, z))));
+    }
+
+    // Where z = omega_m, q(omega_m) = sum over i != m of (p_i - y) * omega_i / (z * (z - omega_i))
+    if let Some(m) = root_index {
+        let mut q_m = Big::new();
+        for (i, (p_i, omega_i)) in polynomial.iter().zip(&settings.roots_of_unity).enumerate() {
+            if i == m {
+                continue;
+            }
+            let numerator = fr_mul(&fr_sub(p_i, &y), omega_i);
+            let denominator = fr_mul(z, &fr_sub(z, omega_i));
+            q_m = fr_add(&q_m, &fr_mul(&numerator, &fr_inverse(&denominator)));
+        }
+        quotient[m] = q_m;
+    }
+
+    (KzgProof { point: g1_lincomb(&settings.g1_lagrange, &quotient) }, y)
+}
+
+// Evaluates a polynomial in evaluation form at z with the barycentric formula
+// p(z) = (z^n - 1) / n * sum of p_i * omega_i / (z - omega_i)
+fn evaluate_polynomial(polynomial: &[Big], z: &Big, settings: &KzgSettings) -> Big {
+    let mut sum = Big::new();
+    for (p_i, omega_i) in polynomial.iter().zip(&settings.roots_of_unity) {
+        if Big::comp(z, omega_i) == 0 {
+            return p_i.clone();
+        }
+        let term = fr_mul(&fr_mul(p_i, omega_i), &fr_inverse(&fr_sub(z, omega_i)));
+        sum = fr_add(&sum, &term);
+    }
+
+    let n = polynomial.len();
+    let z_n_minus_one = fr_sub(&fr_pow(z, n), &Big::new_int(1));
+    let factor = fr_mul(&z_n_minus_one, &fr_inverse(&Big::new_int(n as isize)));
+    fr_mul(&sum, &factor)
+}
+
+// SHA-256 of the domain, the number of field elements, the blob and the commitment, reduced
+// modulo r.
+fn compute_challenge(blob: &[u8], commitment: &KzgCommitment) -> Big {
+    let degree = (blob.len() / BYTES_PER_FIELD_ELEMENT) as u128;
+    let commitment = commitment.as_bytes();
+    let parts: [&[u8]; 4] = [FIAT_SHAMIR_PROTOCOL_DOMAIN, &degree.to_be_bytes(), blob, &commitment];
+
+    let mut hash = [0u8; MODBYTES];
+    XmdHash::Sha256.digest(&parts, &mut hash[MODBYTES - BYTES_PER_FIELD_ELEMENT..]);
+    let mut challenge = Big::from_bytes(&hash);
+    challenge.rmod(&modulus());
+    challenge
+}
+
+// Parses a blob into its field elements.
+fn blob_to_polynomial(blob: &[u8], settings: &KzgSettings) -> Result<Vec<Big>, KzgError> {
+    let expected = settings.field_elements_per_blob() * BYTES_PER_FIELD_ELEMENT;
+    if blob.len() != expected {
+        return Err(KzgError::InvalidBlobLength { expected, actual: blob.len() });
+    }
+    blob.chunks(BYTES_PER_FIELD_ELEMENT).map(fr_from_bytes).collect()
+}
+
+// The n-th roots of unity, in bit-reversed order.
+fn roots_of_unity(n: usize) -> Vec<Big> {
+    // omega = 7^((r - 1) / n)
+    let bits = n.trailing_zeros();
+    let mut exponent = modulus();
+    exponent.sub(&Big::new_int(1));
+    exponent.norm();
+    if bits > 0 {
+        exponent.fshr(bits as usize);
+    }
+    let omega = Big::new_int(PRIMITIVE_ROOT_OF_UNITY).powmod(&exponent, &modulus());
+
+    let mut roots = Vec::with_capacity(n);
+    let mut root = Big::new_int(1);
+    for _ in 0..n {
+        roots.push(root.clone());
+        root = fr_mul(&root, &omega);
+    }
+    (0..n).map(|i| roots[reverse_bits(i, bits)].clone()).collect()
+}
+
+// Reverses the lowest `bits` bits of i.
+fn reverse_bits(i: usize, bits: u32) -> usize {
+    if bits == 0 {
+        return 0;
+    }
+    i.reverse_bits() >> (usize::BITS - bits)
+}
+
+// Sum of scalars[i] * points[i]
+fn g1_lincomb(points: &[GroupG1], scalars: &[Big]) -> GroupG1 {
+    let mut sum = GroupG1::new();
+    for (point, scalar) in points.iter().zip(scalars) {
+        sum.add(&g1mul(point, scalar));
+    }
+    sum.affine();
+    sum
+}
+
+// A uniformly random non-zero scalar, from 64 random bytes reduced modulo r.
+fn random_scalar<R: RngCore + CryptoRng + ?Sized>(rng: &mut R) -> Big {
+    loop {
+        let scalar = Scalar::random(rng);
+        if !scalar.is_zero() {
+            return scalar.0;
+        }
+    }
+}
+
+fn modulus() -> Big {
+    Big::new_ints(&CURVE_ORDER)
+}
+
+// A 32 byte big-endian field element, which must be less than r.
+fn fr_from_bytes(bytes: &[u8]) -> Result<Big, KzgError> {
+    if bytes.len() != BYTES_PER_FIELD_ELEMENT {
+        return Err(KzgError::InvalidFieldElement);
+    }
+    let mut padded = [0u8; MODBYTES];
+    padded[MODBYTES - BYTES_PER_FIELD_ELEMENT..].copy_from_slice(bytes);
+    let element = Big::from_bytes(&padded);
+    if Big::comp(&element, &modulus()) >= 0 {
+        return Err(KzgError::InvalidFieldElement);
+    }
+    Ok(element)
+}
+
+fn fr_to_bytes(element: &Big) -> [u8; BYTES_PER_FIELD_ELEMENT] {
+    let mut padded = [0u8; MODBYTES];
+    element.to_bytes(&mut padded);
+    let mut bytes = [0u8; BYTES_PER_FIELD_ELEMENT];
+    bytes.copy_from_slice(&padded[MODBYTES - BYTES_PER_FIELD_ELEMENT..]);
+    bytes
+}
+
+fn fr_add(a: &Big, b: &Big) -> Big {
+    let mut sum = a.plus(b);
+    sum.norm();
+    sum.rmod(&modulus());
+    sum
+}
+
+fn fr_sub(a: &Big, b: &Big) -> Big {
+    fr_add(a, &Big::modneg(b, &modulus()))
+}
+
+fn fr_mul(a: &Big, b: &Big) -> Big {
+    Big::modmul(a, b, &modulus())
+}
+
+fn fr_inverse(a: &Big) -> Big {
+    let mut inverse = a.clone();
+    inverse.invmodp(&modulus());
+    inverse
+}
+
+fn fr_pow(a: &Big, exponent: usize) -> Big {
+    a.clone().powmod(&Big::new_int(exponent as isize), &modulus())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate hex;
+    extern crate rand;
+
+    use super::super::amcl_utils::compress_g2;
+    use super::*;
+
+    const N: usize = 4;
+
+    // A setup from a known tau, L_i(tau) = omega_i / n * (tau^n - 1) / (tau - omega_i)
+    fn insecure_setup(tau: isize) -> (Vec<GroupG1>, Vec<GroupG2>) {
+        let tau = Big::new_int(tau);
+        let factor = fr_mul(
+            &fr_sub(&fr_pow(&tau, N), &Big::new_int(1)),
+            &fr_inverse(&Big::new_int(N as isize)),
+        );
+        let g1_lagrange = roots_of_unity(N)
+            .iter()
+            .map(|omega| {
+                let lagrange = fr_mul(&fr_mul(omega, &factor), &fr_inverse(&fr_sub(&tau, omega)));
+                g1mul(&GENERATORG1, &lagrange)
+            })
+            .collect();
+        let g2_monomial = vec![
+            GENERATORG2.clone(),
+            g2mul(&GENERATORG2, &tau),
+            g2mul(&GENERATORG2, &fr_mul(&tau, &tau)),
+        ];
+        (g1_lagrange, g2_monomial)
+    }
+
+    fn insecure_settings() -> KzgSettings {
+        let (g1_lagrange, g2_monomial) = insecure_setup(1234567);
+        KzgSettings::from_points(g1_lagrange, &g2_monomial).unwrap()
+    }
+
+    // Formats a setup as a trusted_setup.txt file
+    fn trusted_setup_file(g1_lagrange: &[GroupG1], g2_monomial: &[GroupG2]) -> String {
+        let mut contents = format!("{}\n{}\n", g1_lagrange.len(), g2_monomial.len());
+        for point in g1_lagrange {
+            contents += &format!("{}\n", hex::encode(compress_g1(point)));
+        }
+        for point in g2_monomial {
+            contents += &format!("{}\n", hex::encode(&compress_g2(point)[..]));
+        }
+        contents
+    }
+
+    fn field_element(x: u8) -> [u8; BYTES_PER_FIELD_ELEMENT] {
+        let mut bytes = [0u8; BYTES_PER_FIELD_ELEMENT];
+        bytes[BYTES_PER_FIELD_ELEMENT - 1] = x;
+        bytes
+    }
+
+    fn blob(elements: &[u8]) -> Vec<u8> {
+        elements.iter().flat_map(|x| field_element(*x).to_vec()).collect()
+    }
+
+    #[test]
+    fn test_roots_of_unity() {
+        let roots = roots_of_unity(N);
+        assert_eq!(roots.len(), N);
+        // Bit-reversed order of 1, omega, -1, -omega
+        assert_eq!(Big::comp(&roots[0], &Big::new_int(1)), 0);
+        assert_eq!(Big::comp(&roots[1], &Big::modneg(&Big::new_int(1), &modulus())), 0);
+        for root in &roots {
+            assert_eq!(Big::comp(&fr_pow(root, N), &Big::new_int(1)), 0);
+        }
+        assert!(Big::comp(&fr_pow(&roots[2], N / 2), &Big::new_int(1)) != 0);
+
+        assert_eq!(reverse_bits(1, 2), 2);
+        assert_eq!(reverse_bits(3, 3), 6);
+    }
+
+    #[test]
+    fn test_commit_constant() {
+        // Sum of the Lagrange polynomials is 1, so a constant c commits to [c]G1
+        let settings = insecure_settings();
+        let commitment = blob_to_kzg_commitment(&blob(&[5, 5, 5, 5]), &settings).unwrap();
+        assert!(commitment.point.equals(&g1mul(&GENERATORG1, &Big::new_int(5))));
+
+        let commitment = blob_to_kzg_commitment(&blob(&[0, 0, 0, 0]), &settings).unwrap();
+        assert!(commitment.point.is_infinity());
+    }
+
+    #[test]
+    fn test_compute_verify_kzg_proof() {
+        let settings = insecure_settings();
+        let blob = blob(&[1, 2, 3, 4]);
+        let commitment = blob_to_kzg_commitment(&blob, &settings).unwrap();
+
+        // Outside the domain
+        let z = field_element(42);
+        let (proof, y) = compute_kzg_proof(&blob, &z, &settings).unwrap();
+        assert!(verify_kzg_proof(&commitment, &z, &y, &proof, &settings).unwrap());
+        assert!(!verify_kzg_proof(&commitment, &z, &field_element(7), &proof, &settings).unwrap());
+        assert!(!verify_kzg_proof(&commitment, &field_element(43), &y, &proof, &settings).unwrap());
+
+        // On a root of unity the evaluation is the blob element
+        let z = fr_to_bytes(&settings.roots_of_unity[2]);
+        let (proof, y) = compute_kzg_proof(&blob, &z, &settings).unwrap();
+        assert_eq!(y, field_element(3));
+        assert!(verify_kzg_proof(&commitment, &z, &y, &proof, &settings).unwrap());
+
+        // Encodings round trip
+        let decoded = KzgCommitment::from_bytes(&commitment.as_bytes()).unwrap();
+        assert_eq!(decoded.as_bytes(), commitment.as_bytes());
+        let decoded = KzgProof::from_bytes(&proof.as_bytes()).unwrap();
+        assert_eq!(decoded.as_bytes(), proof.as_bytes());
+    }
+
+    #[test]
+    fn test_verify_kzg_proof_batch() {
+        let mut rng = rand::thread_rng();
+        let settings = insecure_settings();
+
+        let mut commitments = vec![];
+        let mut zs = vec![];
+        let mut ys = vec![];
+        let mut proofs = vec![];
+        for i in 0..3 {
+            let blob = blob(&[i, i + 1, 2 * i, 7]);
+            let z = field_element(100 + i);
+            let (proof, y) = compute_kzg_proof(&blob, &z, &settings).unwrap();
+            commitments.push(blob_to_kzg_commitment(&blob, &settings).unwrap());
+            zs.push(z);
+            ys.push(y);
+            proofs.push(proof);
+        }
+        assert!(
+            verify_kzg_proof_batch(&mut rng, &commitments, &zs, &ys, &proofs, &settings).unwrap()
+        );
+        assert!(verify_kzg_proof_batch(&mut rng, &[], &[], &[], &[], &settings).unwrap());
+
+        // Swapped evaluations
+        ys.swap(0, 1);
+        assert!(
+            !verify_kzg_proof_batch(&mut rng, &commitments, &zs, &ys, &proofs, &settings).unwrap()
+        );
+
+        assert_eq!(
+            verify_kzg_proof_batch(&mut rng, &commitments, &zs, &ys[..2], &proofs, &settings),
+            Err(KzgError::LengthMismatch)
+        );
+    }
+
+    // Fills with the bytes of i64::MIN, which has no absolute value.
+    struct I64MinRng;
+
+    impl RngCore for I64MinRng {
+        fn next_u32(&mut self) -> u32 {
+            0x8000_0000
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            i64::MIN as u64
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for chunk in dest.chunks_mut(8) {
+                chunk.copy_from_slice(&i64::MIN.to_be_bytes()[..chunk.len()]);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for I64MinRng {}
+
+    #[test]
+    fn test_random_scalar() {
+        let scalar = random_scalar(&mut I64MinRng);
+        assert!(!scalar.is_zilch());
+        assert!(Big::comp(&scalar, &modulus()) < 0);
+
+        let settings = insecure_settings();
+        let blob = blob(&[1, 2, 3, 4]);
+        let z = field_element(9);
+        let commitment = blob_to_kzg_commitment(&blob, &settings).unwrap();
+        let (proof, y) = compute_kzg_proof(&blob, &z, &settings).unwrap();
+        assert!(verify_kzg_proof_batch(
+            &mut I64MinRng,
+            &[commitment],
+            &[z],
+            &[y],
+            &[proof],
+            &settings
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let settings = insecure_settings();
+        assert_eq!(
+            blob_to_kzg_commitment(&[0u8; 3 * BYTES_PER_FIELD_ELEMENT], &settings),
+            Err(KzgError::InvalidBlobLength { expected: 128, actual: 96 })
+        );
+
+        // Field elements must be less than r
+        let mut blob = blob(&[1, 2, 3, 4]);
+        blob[..BYTES_PER_FIELD_ELEMENT].copy_from_slice(&[0xff; BYTES_PER_FIELD_ELEMENT]);
+        assert_eq!(blob_to_kzg_commitment(&blob, &settings), Err(KzgError::InvalidFieldElement));
+        assert_eq!(
+            compute_kzg_proof(&blob[..], &[0xff; BYTES_PER_FIELD_ELEMENT], &settings),
+            Err(KzgError::InvalidFieldElement)
+        );
+
+        // Number of points must be a power of two
+        let g1_lagrange = settings.g1_lagrange[..3].to_vec();
+        let g2_monomial = [GENERATORG2.clone(), settings.g2_tau.clone()];
+        assert!(KzgSettings::from_points(g1_lagrange, &g2_monomial).is_err());
+    }
+
+    #[test]
+    fn test_load_trusted_setup() {
+        let (g1_lagrange, g2_monomial) = insecure_setup(1234567);
+        let contents = trusted_setup_file(&g1_lagrange, &g2_monomial);
+        let settings = KzgSettings::load_trusted_setup(&contents).unwrap();
+        assert_eq!(settings.field_elements_per_blob(), N);
+        assert!(settings.g2_tau.equals(&g2_monomial[1]));
+
+        // Same as the embedded bytes
+        let g1_bytes: Vec<u8> = g1_lagrange.iter().flat_map(|p| compress_g1(p).to_vec()).collect();
+        let g2_bytes: Vec<u8> = g2_monomial.iter().flat_map(|p| compress_g2(p).to_vec()).collect();
+        let from_bytes = KzgSettings::from_bytes(&g1_bytes, &g2_bytes).unwrap();
+        assert!(from_bytes.g2_tau.equals(&settings.g2_tau));
+        assert_eq!(from_bytes.g1_lagrange, settings.g1_lagrange);
+
+        // CRLF line endings
+        assert!(KzgSettings::load_trusted_setup(&contents.replace('\n', "\r\n")).is_ok());
+
+        // Wrong counts and trailing data
+        let invalid = [
+            contents.replacen("4\n", "5\n", 1),
+            contents.replacen("4\n", "3\n", 1),
+            contents.replacen("3\n", "4\n", 1),
+            format!("{}{}\n", contents, hex::encode(compress_g1(&g1_lagrange[0]))),
+            contents.replacen("4\n", "four\n", 1),
+            String::new(),
+        ];
+        for contents in &invalid {
+            assert_eq!(
+                KzgSettings::load_trusted_setup(contents).err(),
+                Some(KzgError::InvalidTrustedSetup)
+            );
+        }
+
+        // Invalid hex and truncated bytes
+        let g1_hex = hex::encode(compress_g1(&g1_lagrange[0]));
+        let contents = trusted_setup_file(&g1_lagrange, &g2_monomial);
+        assert!(
+            KzgSettings::load_trusted_setup(&contents.replacen(&g1_hex, &g1_hex[2..], 1)).is_err()
+        );
+        assert!(KzgSettings::load_trusted_setup(&contents.replacen(&g1_hex[..2], "zz", 1)).is_err());
+        assert!(KzgSettings::from_bytes(&g1_bytes[1..], &g2_bytes).is_err());
+    }
+
+    #[test]
+    fn test_trusted_setup_consistency() {
+        let (g1_lagrange, g2_monomial) = insecure_setup(1234567);
+        assert!(KzgSettings::from_points(g1_lagrange.clone(), &g2_monomial).is_ok());
+
+        // G2 points from another tau
+        let (_, other_g2_monomial) = insecure_setup(7654321);
+        assert!(KzgSettings::from_points(g1_lagrange.clone(), &other_g2_monomial).is_err());
+
+        // G1 points in a different order no longer interpolate tau
+        let mut swapped = g1_lagrange.clone();
+        swapped.swap(1, 2);
+        assert!(KzgSettings::from_points(swapped, &g2_monomial).is_err());
+
+        // G1 points not summing to the generator
+        let mut scaled = g1_lagrange.clone();
+        scaled[0] = g1mul(&scaled[0], &Big::new_int(2));
+        assert!(KzgSettings::from_points(scaled, &g2_monomial).is_err());
+
+        // First G2 point must be the generator and tau must be non-zero
+        let mut g2_invalid = g2_monomial.clone();
+        g2_invalid.swap(0, 1);
+        assert!(KzgSettings::from_points(g1_lagrange.clone(), &g2_invalid).is_err());
+        let g2_invalid = [GENERATORG2.clone(), GroupG2::new()];
+        assert!(KzgSettings::from_points(g1_lagrange.clone(), &g2_invalid).is_err());
+        assert!(KzgSettings::from_points(g1_lagrange, &g2_monomial[..1]).is_err());
+    }
+
+    #[test]
+    fn test_compute_challenge() {
+        // SHA-256 of the domain, 16 byte degree, zero blob and infinity commitment
+        let commitment = KzgCommitment { point: GroupG1::new() };
+        let challenge = compute_challenge(&[0u8; N * BYTES_PER_FIELD_ELEMENT], &commitment);
+        assert_eq!(
+            hex::encode(fr_to_bytes(&challenge)),
+            "34c8fd0e1d62431cbf5fbdea388376c3413ed11edf816e652339d20ccdb6e604"
+        );
+    }
+
+    #[test]
+    fn test_compute_verify_blob_kzg_proof() {
+        let settings = insecure_settings();
+        let blob = blob(&[9, 8, 7, 6]);
+        let commitment = blob_to_kzg_commitment(&blob, &settings).unwrap();
+        let proof = compute_blob_kzg_proof(&blob, &commitment, &settings).unwrap();
+        assert!(verify_blob_kzg_proof(&blob, &commitment, &proof, &settings).unwrap());
+
+        // Another blob under the same commitment
+        let other = self::blob(&[9, 8, 7, 5]);
+        assert!(!verify_blob_kzg_proof(&other, &commitment, &proof, &settings).unwrap());
+
+        assert_eq!(
+            verify_blob_kzg_proof(&blob[1..], &commitment, &proof, &settings),
+            Err(KzgError::InvalidBlobLength { expected: 128, actual: 127 })
+        );
+    }
+
+    #[test]
+    fn test_verify_blob_kzg_proof_batch() {
+        let mut rng = rand::thread_rng();
+        let settings = insecure_settings();
+
+        let blobs: Vec<Vec<u8>> = (0..4).map(|i| blob(&[i, 3 * i, 1, 200 - i])).collect();
+        let blobs: Vec<&[u8]> = blobs.iter().map(|blob| &blob[..]).collect();
+        let commitments: Vec<KzgCommitment> =
+            blobs.iter().map(|blob| blob_to_kzg_commitment(blob, &settings).unwrap()).collect();
+        let mut proofs: Vec<KzgProof> = blobs
+            .iter()
+            .zip(&commitments)
+            .map(|(blob, commitment)| compute_blob_kzg_proof(blob, commitment, &settings).unwrap())
+            .collect();
+
+        assert!(verify_blob_kzg_proof_batch(&mut rng, &blobs, &commitments, &proofs, &settings)
+            .unwrap());
+        assert!(verify_blob_kzg_proof_batch(&mut rng, &[], &[], &[], &settings).unwrap());
+        assert_eq!(
+            verify_blob_kzg_proof_batch(&mut rng, &blobs, &commitments[1..], &proofs, &settings),
+            Err(KzgError::LengthMismatch)
+        );
+
+        // Swapped proofs
+        proofs.swap(1, 3);
+        assert!(!verify_blob_kzg_proof_batch(&mut rng, &blobs, &commitments, &proofs, &settings)
+            .unwrap());
+    }
+}
@@ -1,10 +1,13 @@
 #![cfg_attr(not(feature = "std"), no_std)]
+// Public functions return errors rather than panic, a panic while verifying may halt a chain.
+#![cfg_attr(not(test), deny(clippy::unwrap_used, clippy::expect_used, clippy::panic))]
 
-#[cfg(not(feature = "std"))]
+#[cfg(all(feature = "alloc", not(feature = "std")))]
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
 pub(crate) mod prelude {
+    #[cfg(feature = "alloc")]
     pub use alloc::vec::Vec;
     pub use core::prelude::v1::*;
 }
@@ -14,19 +17,124 @@ pub(crate) mod prelude {
 use crate::prelude::*;
 
 extern crate amcl;
-#[cfg(feature = "std")]
-#[macro_use]
-extern crate lazy_static;
-extern crate rand;
+#[cfg(feature = "ark")]
+extern crate ark_bls12_381;
+#[cfg(feature = "ark")]
+extern crate ark_ec;
+#[cfg(feature = "ark")]
+extern crate ark_ff;
+#[cfg(feature = "base64")]
+extern crate base64;
+#[cfg(feature = "blake2b")]
+extern crate blake2;
+#[cfg(feature = "zkcrypto")]
+extern crate bls12_381;
+#[cfg(any(feature = "blst-backend", all(test, feature = "blst-differential")))]
+extern crate blst;
+#[cfg(feature = "scale")]
+extern crate codec;
+extern crate rand_core;
+#[cfg(all(test, feature = "property-tests"))]
+extern crate proptest;
+#[cfg(feature = "mmap")]
+extern crate memmap2;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "scale")]
+extern crate scale_info;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_bytes;
+#[cfg(all(test, feature = "ef_tests"))]
+extern crate serde_yaml;
+#[cfg(feature = "keccak")]
+extern crate sha3;
+extern crate spin;
+#[cfg(feature = "ssz")]
+extern crate ssz as ethereum_ssz;
+#[cfg(feature = "ssz")]
+extern crate tree_hash;
 
 mod aggregates;
 mod amcl_utils;
+pub mod backend;
+#[cfg(feature = "ark")]
+pub mod ark;
+#[cfg(feature = "base64")]
+mod base64_encoding;
+#[cfg(feature = "std")]
+pub mod batch_verifier;
+#[cfg(all(test, feature = "blst-differential"))]
+mod blst_differential;
+#[cfg(feature = "constant-time")]
+mod constant_time;
+mod conversions;
+pub mod drand;
+#[cfg(all(test, feature = "ef_tests"))]
+mod ef_tests;
+mod errors;
+mod fields;
+pub mod hash_to_curve;
+mod hex_encoding;
+#[cfg(feature = "alloc")]
+mod incremental;
+mod isogeny;
 mod keys;
+#[cfg(feature = "kzg")]
+pub mod kzg;
+#[cfg(feature = "alloc")]
+pub mod multisig;
+mod points;
+#[cfg(all(test, feature = "property-tests"))]
+mod property_tests;
+#[cfg(feature = "alloc")]
+mod pubkey_cache;
+#[cfg(feature = "alloc")]
+mod public_key_set;
+#[cfg(test)]
+mod rfc9380;
+#[cfg(feature = "scale")]
+mod scale;
+pub mod schemes;
+#[cfg(feature = "serde")]
+mod serde_impls;
 mod signature;
+mod strictness;
+#[cfg(feature = "ssz")]
+mod ssz;
+mod sync_committee;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+#[cfg(feature = "parallel")]
+pub mod thread_pool;
+#[cfg(feature = "zkcrypto")]
+mod zkcrypto;
 
 use self::amcl::bls381 as BLSCurve;
 
 pub use aggregates::{AggregatePublicKey, AggregateSignature};
-pub use amcl_utils::{AmclError, G1_BYTES, G2_BYTES, SECRET_KEY_BYTES};
+#[cfg(feature = "unstable-raw")]
+pub use amcl_utils::{psi, Big, GroupG1, GroupG2};
+pub use amcl_utils::{
+    AmclError, EIP2537_G1_BYTES, EIP2537_G2_BYTES, G1_BYTES, G2_BYTES, PUBLIC_KEY_BYTES,
+    PUBLIC_KEY_UNCOMPRESSED_BYTES, SECRET_KEY_BYTES, SIGNATURE_BYTES, SIGNATURE_UNCOMPRESSED_BYTES,
+};
+pub use errors::{DecodeError, EncodingFlag, VerifyError};
+pub use fields::{Fp, Fp12, Fp2, FP12_BYTES, FP2_BYTES, FP_BYTES};
+#[cfg(feature = "kzg")]
+pub use errors::KzgError;
+#[cfg(feature = "alloc")]
+pub use incremental::IncrementalAggregate;
 pub use keys::{Keypair, PublicKey, SecretKey};
+pub use points::{
+    miller_loop, multi_miller_loop, pairing, Fr, G1Point, G2Point, Gt, MillerLoopResult,
+    PairingAccumulator, Scalar, GT_BYTES, GT_COMPRESSED_BYTES, SCALAR_BYTES, SCALAR_WIDE_BYTES,
+};
+#[cfg(feature = "alloc")]
+pub use pubkey_cache::PubkeyCache;
+#[cfg(feature = "alloc")]
+pub use public_key_set::{KeyHandle, PublicKeySet};
 pub use signature::Signature;
+pub use strictness::Strictness;
+pub use sync_committee::verify_sync_committee;
@@ -0,0 +1,504 @@
+//! Multisignatures over a known group of PublicKeys, secure against rogue-key attacks without
+//! proofs of possession.
+//!
+//! Each PublicKey is weighted by a coefficient derived by hashing the whole group, as in
+//! Boneh, Drijvers and Neven, "Compact Multi-Signatures for Smaller Blockchains".
+//! https://eprint.iacr.org/2018/483
+//!
+//! `BdnKeySet` implements their plain multisignature, where ordinary Signatures over the same
+//! message are combined with the coefficients of their signers. `AsmGroup` implements their
+//! accountable-subgroup multisignature (ASM), where the subset of the group which signed is
+//! part of the multisignature and verified along with it.
+
+extern crate amcl;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use super::aggregates::{AggregatePublicKey, AggregateSignature};
+use super::amcl_utils::{
+    hash_to_curve_g2_with_dst, subgroup_check_g2, AmclError, G1_BYTES, NEGATIVE_GENERATORG1,
+};
+use super::hash_to_curve::MessageHasher;
+use super::keys::{PublicKey, SecretKey};
+use super::points::{multi_miller_loop, G1Point, G2Point, Scalar, SCALAR_WIDE_BYTES};
+use super::signature::Signature;
+use amcl::hash256::HASH256;
+
+/// Domain separation tag of the per-key coefficients.
+pub const DST_COEFFICIENT: &[u8] = b"BLS_MULTISIG_BLS12381_XMD:SHA-256_COEFFICIENT_";
+/// Domain separation tag of ASM membership keys.
+pub const DST_ASM_MEMBERSHIP: &[u8] = b"BLS_ASM_BLS12381G2_XMD:SHA-256_SSWU_RO_MEMBERSHIP_";
+/// Domain separation tag of messages signed with ASM.
+pub const DST_ASM: &[u8] = b"BLS_ASM_BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+/// The coefficient of each PublicKey, a_i = H(PK_i, {PK_1, ..., PK_n}).
+///
+/// The group is hashed in the given order, so every party must order the keys the same way.
+pub fn key_coefficients(public_keys: &[PublicKey]) -> Vec<Scalar> {
+    // t = SHA-256(PK_1 || ... || PK_n)
+    let mut hash256 = HASH256::new();
+    hash256.init();
+    for pk in public_keys {
+        hash256.process_array(&pk.as_bytes());
+    }
+    let group_hash = hash256.hash();
+
+    public_keys
+        .iter()
+        .map(|pk| {
+            // a_i = expand_message_xmd(PK_i || t) mod r
+            let mut wide = [0u8; SCALAR_WIDE_BYTES];
+            MessageHasher::new(DST_COEFFICIENT)
+                .update(&pk.as_bytes())
+                .update(&group_hash)
+                .expand(&mut wide);
+            Scalar::from_bytes_wide(&wide)
+        })
+        .collect()
+}
+
+/// A set of PublicKeys and their coefficients, whose Signatures may be combined into a
+/// multisignature without proofs of possession.
+///
+/// The multisignature of a message is the sum of a_i * Signature_i, verified against the
+/// aggregate PublicKey, the sum of a_i * PK_i.
+#[derive(Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct BdnKeySet {
+    public_keys: Vec<PublicKey>,
+    coefficients: Vec<Scalar>,
+    aggregate_key: G1Point,
+}
+
+impl BdnKeySet {
+    /// Derive the coefficients and aggregate PublicKey of a set of PublicKeys.
+    ///
+    /// Errors if there are no PublicKeys or any fails KeyValidate.
+    pub fn new(public_keys: &[PublicKey]) -> Result<Self, AmclError> {
+        if public_keys.is_empty() {
+            return Err(AmclError::AggregateEmptyPoints);
+        }
+        if !public_keys.iter().all(|pk| pk.key_validate()) {
+            return Err(AmclError::InvalidPoint);
+        }
+
+        // apk = a_1 * PK_1 + ... + a_n * PK_n
+        let coefficients = key_coefficients(public_keys);
+        let aggregate_key = public_keys
+            .iter()
+            .zip(&coefficients)
+            .fold(G1Point::identity(), |apk, (pk, a)| apk.add(&pk.as_raw_point().mul(a)));
+
+        Ok(BdnKeySet { public_keys: public_keys.to_vec(), coefficients, aggregate_key })
+    }
+
+    /// The number of PublicKeys.
+    pub fn len(&self) -> usize {
+        self.public_keys.len()
+    }
+
+    /// Returns true if there are no PublicKeys, which `new()` does not allow.
+    pub fn is_empty(&self) -> bool {
+        self.public_keys.is_empty()
+    }
+
+    /// The PublicKeys, in the order they were hashed.
+    pub fn public_keys(&self) -> &[PublicKey] {
+        &self.public_keys
+    }
+
+    /// The coefficient of each PublicKey.
+    pub fn coefficients(&self) -> &[Scalar] {
+        &self.coefficients
+    }
+
+    /// The index of a PublicKey.
+    pub fn index_of(&self, pk: &PublicKey) -> Option<usize> {
+        self.public_keys.iter().position(|member| member == pk)
+    }
+
+    /// The aggregate PublicKey, weighted by the coefficients.
+    pub fn aggregate_public_key(&self) -> AggregatePublicKey {
+        AggregatePublicKey { point: self.aggregate_key.0.clone() }
+    }
+
+    /// Combine a Signature over the same message from every PublicKey, in the same order.
+    ///
+    /// Returns None if the number of Signatures differs from the number of PublicKeys.
+    pub fn aggregate_signatures(&self, signatures: &[&Signature]) -> Option<AggregateSignature> {
+        if signatures.len() != self.len() {
+            return None;
+        }
+        let point = signatures
+            .iter()
+            .zip(&self.coefficients)
+            .fold(G2Point::identity(), |sum, (signature, a)| sum.add(&signature.as_point().mul(a)));
+        Some(AggregateSignature::from_point(&point))
+    }
+
+    /// Verifies a multisignature of the message, signed with `Signature::new()`, against the
+    /// aggregate PublicKey.
+    pub fn verify(&self, msg: &[u8], signature: &AggregateSignature) -> bool {
+        signature.fast_aggregate_verify_pre_aggregated(msg, &self.aggregate_public_key())
+    }
+}
+
+/// A group of PublicKeys for accountable-subgroup multisignatures.
+///
+/// Setup: every member computes a share of every other member's `MembershipKey`, once per
+/// group. A member signs with their SecretKey and MembershipKey, and Signatures of any subset
+/// of members combine into an `AsmSignature` naming that subset.
+#[derive(Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct AsmGroup {
+    key_set: BdnKeySet,
+}
+
+impl AsmGroup {
+    /// Set up a group, the members' indices are their positions in `public_keys`.
+    ///
+    /// Errors if there are no PublicKeys or any fails KeyValidate.
+    pub fn new(public_keys: &[PublicKey]) -> Result<Self, AmclError> {
+        Ok(AsmGroup { key_set: BdnKeySet::new(public_keys)? })
+    }
+
+    /// The number of members.
+    pub fn len(&self) -> usize {
+        self.key_set.len()
+    }
+
+    /// Returns true if the group has no members, which `new()` does not allow.
+    pub fn is_empty(&self) -> bool {
+        self.key_set.is_empty()
+    }
+
+    /// The PublicKeys of the members.
+    pub fn public_keys(&self) -> &[PublicKey] {
+        self.key_set.public_keys()
+    }
+
+    /// The index of a member's PublicKey.
+    pub fn index_of(&self, pk: &PublicKey) -> Option<usize> {
+        self.key_set.index_of(pk)
+    }
+
+    /// The aggregate PublicKey of the whole group, weighted by the coefficients.
+    pub fn aggregate_public_key(&self) -> AggregatePublicKey {
+        self.key_set.aggregate_public_key()
+    }
+
+    /// The share of `member`'s MembershipKey computed by the member at index `signer`, whose
+    /// SecretKey is `sk`: (a_signer * sk) * H(apk, member).
+    ///
+    /// Returns None if either index is outside the group.
+    pub fn membership_key_share(
+        &self,
+        signer: usize,
+        sk: &SecretKey,
+        member: usize,
+    ) -> Option<G2Point> {
+        let coefficient = self.key_set.coefficients.get(signer)?;
+        if member >= self.len() {
+            return None;
+        }
+        let scalar = Scalar(sk.as_raw().clone()).mul(coefficient);
+        Some(self.membership_point(member).mul(&scalar))
+    }
+
+    /// Verifies a member's MembershipKey: e(G1, mk) == e(apk, H(apk, member)).
+    pub fn verify_membership_key(&self, member: usize, membership_key: &MembershipKey) -> bool {
+        if member >= self.len() {
+            return false;
+        }
+        let hash_point = self.membership_point(member);
+        let generator_g1_negative = G1Point(NEGATIVE_GENERATORG1.clone());
+        multi_miller_loop(&[
+            (&generator_g1_negative, &membership_key.0),
+            (&self.key_set.aggregate_key, &hash_point),
+        ])
+        .final_exponentiation()
+        .is_identity()
+    }
+
+    /// Sign a message as a member, with their SecretKey and MembershipKey:
+    /// sk * H(apk, msg) + mk.
+    pub fn sign(&self, sk: &SecretKey, membership_key: &MembershipKey, msg: &[u8]) -> Signature {
+        let point = self.message_point(msg);
+        Signature::from_point(&sk.sign_hashed(&point).as_point().add(&membership_key.0))
+    }
+
+    /// Combine the Signatures of a subset of members, given with their indices.
+    ///
+    /// Returns None if there are no Signatures or an index is outside the group or repeated.
+    pub fn aggregate(&self, signatures: &[(usize, &Signature)]) -> Option<AsmSignature> {
+        let mut signers: Vec<usize> = signatures.iter().map(|(index, _)| *index).collect();
+        signers.sort_unstable();
+        signers.dedup();
+        if signers.is_empty()
+            || signers.len() != signatures.len()
+            || signers[signers.len() - 1] >= self.len()
+        {
+            return None;
+        }
+
+        let signature = signatures
+            .iter()
+            .fold(G2Point::identity(), |sum, (_, signature)| sum.add(&signature.as_point()));
+        Some(AsmSignature { signers, signature })
+    }
+
+    /// Verifies an AsmSignature over a message, and so that its signers signed it:
+    /// e(G1, s) == e(PK_S, H(apk, msg)) * e(apk, H(apk, j_1) + ... + H(apk, j_k)).
+    pub fn verify(&self, msg: &[u8], signature: &AsmSignature) -> bool {
+        // Signers must be distinct members, in order
+        let signers = &signature.signers;
+        if signers.is_empty()
+            || signers.windows(2).any(|pair| pair[0] >= pair[1])
+            || signers[signers.len() - 1] >= self.len()
+        {
+            return false;
+        }
+        if !subgroup_check_g2(&signature.signature.0) {
+            return false;
+        }
+
+        let (public_key, membership_point) = signers.iter().fold(
+            (G1Point::identity(), G2Point::identity()),
+            |(public_key, membership_point), &member| {
+                (
+                    public_key.add(&self.key_set.public_keys[member].as_raw_point()),
+                    membership_point.add(&self.membership_point(member)),
+                )
+            },
+        );
+        let message_point = self.message_point(msg);
+        let generator_g1_negative = G1Point(NEGATIVE_GENERATORG1.clone());
+
+        multi_miller_loop(&[
+            (&generator_g1_negative, &signature.signature),
+            (&public_key, &message_point),
+            (&self.key_set.aggregate_key, &membership_point),
+        ])
+        .final_exponentiation()
+        .is_identity()
+    }
+
+    // H(apk, member), hashing the index as 8 big-endian bytes.
+    fn membership_point(&self, member: usize) -> G2Point {
+        let mut input = [0u8; G1_BYTES + 8];
+        input[..G1_BYTES].copy_from_slice(&self.key_set.aggregate_key.as_bytes());
+        input[G1_BYTES..].copy_from_slice(&(member as u64).to_be_bytes());
+        G2Point(hash_to_curve_g2_with_dst(&input, DST_ASM_MEMBERSHIP))
+    }
+
+    // H(apk, msg)
+    fn message_point(&self, msg: &[u8]) -> G2Point {
+        let hasher = MessageHasher::new(DST_ASM).update(&self.key_set.aggregate_key.as_bytes());
+        G2Point(hasher.update(msg).finalize_to_g2())
+    }
+}
+
+/// A member's key certifying membership of an `AsmGroup`, the sum of the shares computed by
+/// every member.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct MembershipKey(G2Point);
+
+impl MembershipKey {
+    /// Combine the shares of a member's MembershipKey from every member of the group.
+    pub fn from_shares(shares: &[G2Point]) -> Self {
+        MembershipKey(shares.iter().fold(G2Point::identity(), |sum, share| sum.add(share)))
+    }
+
+    /// The G2 point of the MembershipKey.
+    pub fn as_point(&self) -> &G2Point {
+        &self.0
+    }
+}
+
+/// A multisignature by a subset of an `AsmGroup`, naming the signers.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct AsmSignature {
+    signers: Vec<usize>,
+    signature: G2Point,
+}
+
+impl AsmSignature {
+    /// An AsmSignature received from its aggregator, the indices of the signers along with the
+    /// sum of their Signatures.
+    ///
+    /// Neither is checked here, `AsmGroup::verify()` rejects signers which are not distinct
+    /// members in ascending order and signatures outside of G2.
+    pub fn new(signers: Vec<usize>, signature: Signature) -> Self {
+        AsmSignature { signers, signature: signature.as_point() }
+    }
+
+    /// The indices of the members who signed, in ascending order.
+    pub fn signers(&self) -> &[usize] {
+        &self.signers
+    }
+
+    /// The sum of the signers' Signatures.
+    pub fn signature(&self) -> Signature {
+        Signature::from_point(&self.signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::keys::Keypair;
+    use super::*;
+
+    fn setup(n: usize) -> (Vec<Keypair>, AsmGroup, Vec<MembershipKey>) {
+        let keypairs: Vec<Keypair> =
+            (0..n).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let public_keys: Vec<PublicKey> =
+            keypairs.iter().map(|keypair| keypair.pk.clone()).collect();
+        let group = AsmGroup::new(&public_keys).unwrap();
+
+        let membership_keys: Vec<MembershipKey> = (0..n)
+            .map(|member| {
+                let shares: Vec<G2Point> = keypairs
+                    .iter()
+                    .enumerate()
+                    .map(|(signer, keypair)| {
+                        group.membership_key_share(signer, &keypair.sk, member).unwrap()
+                    })
+                    .collect();
+                MembershipKey::from_shares(&shares)
+            })
+            .collect();
+        (keypairs, group, membership_keys)
+    }
+
+    #[test]
+    fn test_key_coefficients() {
+        let public_keys: Vec<PublicKey> =
+            (0..3).map(|_| Keypair::random(&mut rand::thread_rng()).pk).collect();
+        let coefficients = key_coefficients(&public_keys);
+        assert_eq!(coefficients.len(), 3);
+        assert_eq!(coefficients, key_coefficients(&public_keys));
+
+        // The coefficients depend on the whole group
+        assert!(coefficients[0] != key_coefficients(&public_keys[..2])[0]);
+        assert!(coefficients[0] != coefficients[1]);
+    }
+
+    #[test]
+    fn test_bdn_multisignature() {
+        let keypairs: Vec<Keypair> =
+            (0..3).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let public_keys: Vec<PublicKey> =
+            keypairs.iter().map(|keypair| keypair.pk.clone()).collect();
+        let key_set = BdnKeySet::new(&public_keys).unwrap();
+
+        let msg = b"cats";
+        let signatures: Vec<Signature> =
+            keypairs.iter().map(|keypair| Signature::new(msg, &keypair.sk)).collect();
+        let signature_refs: Vec<&Signature> = signatures.iter().collect();
+        let multisignature = key_set.aggregate_signatures(&signature_refs).unwrap();
+        assert!(key_set.verify(msg, &multisignature));
+        assert!(!key_set.verify(b"dogs", &multisignature));
+        assert!(key_set.aggregate_signatures(&signature_refs[..2]).is_none());
+
+        // The plain sum of the Signatures is not a multisignature
+        let sum = AggregateSignature::aggregate(&signature_refs);
+        assert!(!key_set.verify(msg, &sum));
+
+        // Nor is the multisignature of a different order of the same keys
+        let reordered = [public_keys[1].clone(), public_keys[0].clone(), public_keys[2].clone()];
+        assert!(!BdnKeySet::new(&reordered).unwrap().verify(msg, &multisignature));
+    }
+
+    #[test]
+    fn test_bdn_rogue_key() {
+        // A rogue key PK_r = x * G1 - PK_h cancels the honest key in a plain aggregate, the
+        // coefficients stop it from cancelling in the aggregate PublicKey
+        let honest = Keypair::random(&mut rand::thread_rng());
+        let x = SecretKey::random(&mut rand::thread_rng());
+        let rogue_point =
+            PublicKey::from_secret_key(&x).as_raw_point().sub(&honest.pk.as_raw_point());
+        let rogue = PublicKey { point: rogue_point.0 };
+
+        let msg = b"cats";
+        let forged = Signature::new(msg, &x);
+        let plain = AggregatePublicKey::aggregate(&[&honest.pk, &rogue]).unwrap();
+        assert!(AggregateSignature::from_signature(&forged)
+            .fast_aggregate_verify_pre_aggregated(msg, &plain));
+
+        let key_set = BdnKeySet::new(&[honest.pk.clone(), rogue]).unwrap();
+        assert!(!key_set.verify(msg, &AggregateSignature::from_signature(&forged)));
+    }
+
+    #[test]
+    fn test_asm_sign_verify() {
+        let (keypairs, group, membership_keys) = setup(4);
+        assert_eq!(group.len(), 4);
+        assert_eq!(group.index_of(&keypairs[2].pk), Some(2));
+        for (member, membership_key) in membership_keys.iter().enumerate() {
+            assert!(group.verify_membership_key(member, membership_key));
+        }
+        assert!(!group.verify_membership_key(1, &membership_keys[0]));
+
+        let msg = b"cats";
+        let signatures: Vec<Signature> = [0, 2, 3]
+            .iter()
+            .map(|&member| group.sign(&keypairs[member].sk, &membership_keys[member], msg))
+            .collect();
+        let asm_signature = group
+            .aggregate(&[(3, &signatures[2]), (0, &signatures[0]), (2, &signatures[1])])
+            .unwrap();
+        assert_eq!(asm_signature.signers(), &[0, 2, 3]);
+        assert!(group.verify(msg, &asm_signature));
+        assert!(!group.verify(b"dogs", &asm_signature));
+
+        // The signers are accountable, claiming a different subset fails
+        let mut wrong_signers = asm_signature.clone();
+        wrong_signers.signers = vec![0, 1, 3];
+        assert!(!group.verify(msg, &wrong_signers));
+        let mut fewer_signers = asm_signature.clone();
+        fewer_signers.signers = vec![0, 2];
+        assert!(!group.verify(msg, &fewer_signers));
+    }
+
+    #[test]
+    fn test_asm_signature_new() {
+        let (keypairs, group, membership_keys) = setup(3);
+        let msg = b"cats";
+        let signatures: Vec<Signature> = (0..2)
+            .map(|member| group.sign(&keypairs[member].sk, &membership_keys[member], msg))
+            .collect();
+        let asm_signature = group.aggregate(&[(0, &signatures[0]), (1, &signatures[1])]).unwrap();
+
+        // Rebuilt by a verifier from the signers and the compressed signature
+        let bytes = asm_signature.signature().as_bytes();
+        let received = AsmSignature::new(
+            asm_signature.signers().to_vec(),
+            Signature::from_bytes(&bytes).unwrap(),
+        );
+        assert_eq!(received, asm_signature);
+        assert!(group.verify(msg, &received));
+
+        // Signers out of order, repeated or outside the group are rejected
+        for signers in &[vec![1, 0], vec![0, 0, 1], vec![0, 3], vec![]] {
+            let received = AsmSignature::new(signers.clone(), asm_signature.signature());
+            assert!(!group.verify(msg, &received));
+        }
+    }
+
+    #[test]
+    fn test_asm_aggregate_errors() {
+        let (keypairs, group, membership_keys) = setup(2);
+        let signature = group.sign(&keypairs[0].sk, &membership_keys[0], b"cats");
+        assert!(group.aggregate(&[]).is_none());
+        assert!(group.aggregate(&[(0, &signature), (0, &signature)]).is_none());
+        assert!(group.aggregate(&[(2, &signature)]).is_none());
+        assert!(group.membership_key_share(2, &keypairs[0].sk, 0).is_none());
+        assert!(group.membership_key_share(0, &keypairs[0].sk, 2).is_none());
+        assert!(AsmGroup::new(&[]).is_err());
+    }
+}
@@ -0,0 +1,1040 @@
+//! Validated wrappers over the BLS12-381 groups and scalar field, so the raw amcl types do not
+//! leak into the public API.
+
+extern crate zeroize;
+
+use self::zeroize::Zeroize;
+use super::amcl_utils::{
+    compress_g1, compress_g2, decompress_g1, decompress_g2, deserialize_uncompressed_g1,
+    deserialize_uncompressed_g2, g1mul, g2mul, hash_to_curve_g2, on_curve_check_g1,
+    on_curve_check_g2, psi, serialize_uncompressed_g1, serialize_uncompressed_g2,
+    subgroup_check_g1, subgroup_check_g2, Big, GroupG1, GroupG2, CURVE_ORDER, FP12, FP2, G1_BYTES,
+    G2_BYTES, GENERATORG1, GENERATORG2, MODBYTES,
+};
+use super::errors::DecodeError;
+use super::fields::{Fp12, Fp2, FP12_BYTES, FP2_BYTES};
+#[cfg(not(feature = "std"))]
+use core::fmt;
+#[cfg(not(feature = "std"))]
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "std")]
+use std::fmt;
+#[cfg(feature = "std")]
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use BLSCurve::fp4::FP4;
+use BLSCurve::pair::{self, ate, fexp};
+
+/// The length of an encoded Scalar.
+pub const SCALAR_BYTES: usize = 32;
+
+/// The length of an uncompressed Gt element.
+pub const GT_BYTES: usize = FP12_BYTES;
+/// The length of a compressed Gt element.
+pub const GT_COMPRESSED_BYTES: usize = FP2_BYTES * 3;
+
+/// The length of the input to `Scalar::from_bytes_wide()`.
+pub const SCALAR_WIDE_BYTES: usize = 64;
+
+/// An element of the scalar field, an integer modulo the curve order r.
+#[derive(Clone)]
+pub struct Scalar(pub(crate) Big);
+
+/// The scalar field of BLS12-381, as it is commonly named.
+pub type Fr = Scalar;
+
+impl Scalar {
+    /// The Scalar 0.
+    pub fn zero() -> Self {
+        Scalar(Big::new())
+    }
+
+    /// The Scalar 1.
+    pub fn one() -> Self {
+        Scalar(Big::new_int(1))
+    }
+
+    /// Instantiate a Scalar from a u64.
+    pub fn from_u64(x: u64) -> Self {
+        Scalar(from_bytes_unreduced(&x.to_be_bytes()))
+    }
+
+    /// Instantiate a Scalar from 32 big-endian bytes, which must be less than r.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != SCALAR_BYTES {
+            return Err(DecodeError::InvalidLength { expected: SCALAR_BYTES, actual: bytes.len() });
+        }
+        let x = from_bytes_unreduced(bytes);
+        if Big::comp(&x, &modulus()) >= 0 {
+            return Err(DecodeError::FieldElementTooLarge);
+        }
+        Ok(Scalar(x))
+    }
+
+    /// Instantiate a Scalar from 32 little-endian bytes, which must be less than r.
+    pub fn from_bytes_le(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != SCALAR_BYTES {
+            return Err(DecodeError::InvalidLength { expected: SCALAR_BYTES, actual: bytes.len() });
+        }
+        let mut reversed = [0u8; SCALAR_BYTES];
+        reversed.copy_from_slice(bytes);
+        reversed.reverse();
+        Self::from_bytes(&reversed)
+    }
+
+    /// Instantiate a Scalar from 64 big-endian bytes reduced modulo r.
+    ///
+    /// The bias of the reduction is negligible, so uniformly random bytes give a uniformly
+    /// random Scalar, e.g. from the output of a hash function.
+    pub fn from_bytes_wide(bytes: &[u8; SCALAR_WIDE_BYTES]) -> Self {
+        // hi * 2^256 + lo
+        let (hi, lo) = bytes.split_at(SCALAR_WIDE_BYTES - SCALAR_BYTES);
+        let mut two_256 = [0u8; MODBYTES];
+        two_256[MODBYTES - SCALAR_BYTES - 1] = 1;
+        let hi =
+            Scalar(Big::modmul(&from_bytes_unreduced(hi), &Big::from_bytes(&two_256), &modulus()));
+        let mut lo = from_bytes_unreduced(lo);
+        lo.rmod(&modulus());
+        hi.add(&Scalar(lo))
+    }
+
+    /// A uniformly random Scalar.
+    pub fn random<R: RngCore + CryptoRng + ?Sized>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; SCALAR_WIDE_BYTES];
+        rng.fill_bytes(&mut bytes);
+        Self::from_bytes_wide(&bytes)
+    }
+
+    /// Export the Scalar as 32 big-endian bytes.
+    pub fn as_bytes(&self) -> [u8; SCALAR_BYTES] {
+        let mut padded = [0u8; MODBYTES];
+        self.0.to_bytes(&mut padded);
+        let mut bytes = [0u8; SCALAR_BYTES];
+        bytes.copy_from_slice(&padded[MODBYTES - SCALAR_BYTES..]);
+        bytes
+    }
+
+    /// Export the Scalar as 32 little-endian bytes.
+    pub fn as_bytes_le(&self) -> [u8; SCALAR_BYTES] {
+        let mut bytes = self.as_bytes();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Returns true if the Scalar is 0.
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zilch()
+    }
+
+    /// self + other mod r.
+    pub fn add(&self, other: &Scalar) -> Scalar {
+        let mut sum = self.0.plus(&other.0);
+        sum.norm();
+        sum.rmod(&modulus());
+        Scalar(sum)
+    }
+
+    /// self - other mod r.
+    pub fn sub(&self, other: &Scalar) -> Scalar {
+        self.add(&other.neg())
+    }
+
+    /// self * other mod r.
+    pub fn mul(&self, other: &Scalar) -> Scalar {
+        Scalar(Big::modmul(&self.0, &other.0, &modulus()))
+    }
+
+    /// -self mod r.
+    pub fn neg(&self) -> Scalar {
+        // modneg returns r for 0
+        let mut x = Big::modneg(&self.0, &modulus());
+        x.rmod(&modulus());
+        Scalar(x)
+    }
+
+    /// The multiplicative inverse, or None for 0.
+    pub fn invert(&self) -> Option<Scalar> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut inverse = self.0.clone();
+        inverse.invmodp(&modulus());
+        Some(Scalar(inverse))
+    }
+
+    // Clear the limbs, for Scalars derived from a SecretKey which are about to be dropped.
+    pub(crate) fn zeroize(&mut self) {
+        self.0.w.zeroize();
+    }
+}
+
+impl PartialEq for Scalar {
+    fn eq(&self, other: &Scalar) -> bool {
+        Big::comp(&self.0, &other.0) == 0
+    }
+}
+
+impl Eq for Scalar {}
+
+impl fmt::Debug for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Scalar(0x")?;
+        for byte in self.as_bytes().iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, ")")
+    }
+}
+
+fn modulus() -> Big {
+    Big::new_ints(&CURVE_ORDER)
+}
+
+// Up to 32 big-endian bytes as a Big, which may not be less than r.
+fn from_bytes_unreduced(bytes: &[u8]) -> Big {
+    let mut padded = [0u8; MODBYTES];
+    padded[MODBYTES - bytes.len()..].copy_from_slice(bytes);
+    Big::from_bytes(&padded)
+}
+
+// Implements the shared API of G1Point and G2Point over the raw amcl point type.
+macro_rules! impl_point {
+    ($type: ident, $raw: ty, $bytes: expr, $generator: expr, $compress: expr, $decompress: expr,
+     $serialize_uncompressed: expr, $deserialize_uncompressed: expr, $on_curve_check: expr,
+     $subgroup_check: expr, $mul: expr) => {
+        impl $type {
+            /// The fixed generator of the group.
+            pub fn generator() -> Self {
+                $type($generator.clone())
+            }
+
+            /// The identity, the point at infinity.
+            pub fn identity() -> Self {
+                $type(<$raw>::new())
+            }
+
+            /// Instantiate a point from compressed bytes.
+            ///
+            /// The encoding must be canonical and the point in the prime order subgroup, the
+            /// identity is accepted.
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+                Ok($type($decompress(bytes)?))
+            }
+
+            /// Export the point to compressed bytes.
+            pub fn as_bytes(&self) -> [u8; $bytes] {
+                $compress(&self.0)
+            }
+
+            /// Instantiate a point from uncompressed (x, y) bytes.
+            ///
+            /// The point is validated as in `from_bytes()`.
+            pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+                let point = $deserialize_uncompressed(bytes)?;
+                if !$subgroup_check(&point) {
+                    return Err(DecodeError::NotInSubgroup);
+                }
+                Ok($type(point))
+            }
+
+            /// Export the point to uncompressed (x, y) bytes.
+            pub fn to_uncompressed_bytes(&self) -> [u8; $bytes * 2] {
+                $serialize_uncompressed(&self.0)
+            }
+
+            /// Returns true if the point is the identity.
+            pub fn is_identity(&self) -> bool {
+                self.0.is_infinity()
+            }
+
+            /// Returns true if the point is on the curve.
+            ///
+            /// Points are validated when they are created, so this holds for every point.
+            pub fn is_on_curve(&self) -> bool {
+                $on_curve_check(&self.0)
+            }
+
+            /// Returns true if the point is in the prime order subgroup.
+            ///
+            /// Points are validated when they are created, so this holds for every point.
+            pub fn is_in_subgroup(&self) -> bool {
+                $subgroup_check(&self.0)
+            }
+
+            /// The sum of two points.
+            pub fn add(&self, other: &Self) -> Self {
+                let mut point = self.0.clone();
+                point.add(&other.0);
+                point.affine();
+                $type(point)
+            }
+
+            /// The difference of two points.
+            pub fn sub(&self, other: &Self) -> Self {
+                let mut point = self.0.clone();
+                point.sub(&other.0);
+                point.affine();
+                $type(point)
+            }
+
+            /// The negation of the point.
+            pub fn neg(&self) -> Self {
+                let mut point = self.0.clone();
+                point.neg();
+                $type(point)
+            }
+
+            /// The point multiplied by a Scalar.
+            pub fn mul(&self, scalar: &Scalar) -> Self {
+                let mut point = $mul(&self.0, &scalar.0);
+                point.affine();
+                $type(point)
+            }
+        }
+
+        impl PartialEq for $type {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.equals(&other.0)
+            }
+        }
+
+        impl Eq for $type {}
+
+        // Operators are implemented on references only, by value they would shadow the inherent
+        // methods of the same names.
+        impl<'a> Add<&'a $type> for &'a $type {
+            type Output = $type;
+
+            fn add(self, other: &'a $type) -> $type {
+                $type::add(self, other)
+            }
+        }
+
+        impl<'a> Sub<&'a $type> for &'a $type {
+            type Output = $type;
+
+            fn sub(self, other: &'a $type) -> $type {
+                $type::sub(self, other)
+            }
+        }
+
+        impl<'a> Neg for &'a $type {
+            type Output = $type;
+
+            fn neg(self) -> $type {
+                $type::neg(self)
+            }
+        }
+
+        impl<'a> Mul<&'a Scalar> for &'a $type {
+            type Output = $type;
+
+            fn mul(self, scalar: &'a Scalar) -> $type {
+                $type::mul(self, scalar)
+            }
+        }
+
+        impl<'a> AddAssign<&'a $type> for $type {
+            fn add_assign(&mut self, other: &'a $type) {
+                self.0.add(&other.0);
+                self.0.affine();
+            }
+        }
+
+        impl AddAssign for $type {
+            fn add_assign(&mut self, other: $type) {
+                *self += &other;
+            }
+        }
+
+        impl<'a> SubAssign<&'a $type> for $type {
+            fn sub_assign(&mut self, other: &'a $type) {
+                self.0.sub(&other.0);
+                self.0.affine();
+            }
+        }
+
+        impl SubAssign for $type {
+            fn sub_assign(&mut self, other: $type) {
+                *self -= &other;
+            }
+        }
+
+        impl<'a> MulAssign<&'a Scalar> for $type {
+            fn mul_assign(&mut self, scalar: &'a Scalar) {
+                *self = $type::mul(self, scalar);
+            }
+        }
+
+        impl fmt::Debug for $type {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}(0x", stringify!($type))?;
+                for byte in self.as_bytes().iter() {
+                    write!(f, "{:02x}", byte)?;
+                }
+                write!(f, ")")
+            }
+        }
+    };
+}
+
+/// A point in the prime order subgroup G1.
+#[derive(Clone)]
+pub struct G1Point(pub(crate) GroupG1);
+
+impl_point!(
+    G1Point,
+    GroupG1,
+    G1_BYTES,
+    GENERATORG1,
+    compress_g1,
+    decompress_g1,
+    serialize_uncompressed_g1,
+    deserialize_uncompressed_g1,
+    on_curve_check_g1,
+    subgroup_check_g1,
+    g1mul
+);
+
+/// A point in the prime order subgroup G2.
+#[derive(Clone)]
+pub struct G2Point(pub(crate) GroupG2);
+
+impl_point!(
+    G2Point,
+    GroupG2,
+    G2_BYTES,
+    GENERATORG2,
+    compress_g2,
+    decompress_g2,
+    serialize_uncompressed_g2,
+    deserialize_uncompressed_g2,
+    on_curve_check_g2,
+    subgroup_check_g2,
+    g2mul
+);
+
+impl G2Point {
+    /// hash_to_curve of a message with the Proof of Possession ciphersuite, as signed by
+    /// `Signature::new()`.
+    ///
+    /// The point may be computed once and shared, see `SecretKey::sign_hashed()`.
+    pub fn hash_to_curve(msg: &[u8]) -> Self {
+        G2Point(hash_to_curve_g2(msg))
+    }
+
+    /// The endomorphism psi = untwist-Frobenius-twist, which on G2 is multiplication by the
+    /// curve parameter x = -0xd201000000010000.
+    ///
+    /// For subgroup checks, GLS multiplication and other endomorphism based methods.
+    pub fn psi(&self) -> Self {
+        let mut point = psi(&self.0);
+        point.affine();
+        G2Point(point)
+    }
+}
+
+/// An element of Gt, the order r subgroup of Fp12 containing the pairing outputs.
+#[derive(Clone)]
+pub struct Gt(pub(crate) FP12);
+
+impl Gt {
+    /// The identity, 1.
+    pub fn identity() -> Self {
+        Gt(FP12::new_int(1))
+    }
+
+    /// The generator e(G1, G2).
+    pub fn generator() -> Self {
+        pairing(&G1Point::generator(), &G2Point::generator())
+    }
+
+    /// Instantiate a Gt from uncompressed bytes, checking it is in the subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let gt = Gt(Fp12::from_bytes(bytes)?.0);
+        if !gt.is_in_subgroup() {
+            return Err(DecodeError::NotInSubgroup);
+        }
+        Ok(gt)
+    }
+
+    /// Export the Gt as uncompressed bytes, the encoding of `Fp12::as_bytes()`.
+    pub fn as_bytes(&self) -> [u8; GT_BYTES] {
+        Fp12(self.0.clone()).as_bytes()
+    }
+
+    /// Instantiate a Gt from compressed bytes, checking it is in the subgroup.
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != GT_COMPRESSED_BYTES {
+            return Err(DecodeError::InvalidLength {
+                expected: GT_COMPRESSED_BYTES,
+                actual: bytes.len(),
+            });
+        }
+        if bytes.iter().all(|byte| *byte == 0) {
+            return Ok(Gt::identity());
+        }
+        let mut c = [FP2::new(), FP2::new(), FP2::new()];
+        for (coefficient, chunk) in c.iter_mut().zip(bytes.chunks(FP2_BYTES)) {
+            *coefficient = Fp2::from_bytes(chunk)?.0;
+        }
+
+        // x = (c + w) / (c - w)
+        let mut minus_one = FP2::new_int(1);
+        minus_one.neg();
+        let mut x = from_fp6_parts(&c, &[FP2::new(), FP2::new_int(1), FP2::new()]);
+        let mut denominator = from_fp6_parts(&c, &[FP2::new(), minus_one, FP2::new()]);
+        denominator.inverse();
+        x.mul(&denominator);
+        x.reduce();
+
+        let gt = Gt(x);
+        if !gt.is_in_subgroup() {
+            return Err(DecodeError::NotInSubgroup);
+        }
+        Ok(gt)
+    }
+
+    /// Export the Gt as compressed bytes, half the length of the uncompressed bytes.
+    ///
+    /// Elements of Gt have norm 1 over Fp6, so x = g + h * w is determined by the Fp6 element
+    /// c = (1 + g) / h as x = (c + w) / (c - w). The identity, where h = 0, is all zeros.
+    /// See https://eprint.iacr.org/2007/429 for this torus based compression.
+    pub fn as_compressed_bytes(&self) -> [u8; GT_COMPRESSED_BYTES] {
+        let mut bytes = [0u8; GT_COMPRESSED_BYTES];
+        if self.is_identity() {
+            return bytes;
+        }
+
+        // c = (1 + g) * w / (h * w)
+        let zero = [FP2::new(), FP2::new(), FP2::new()];
+        let (mut g, h) = fp6_parts(&self.0);
+        g[0].add(&FP2::new_int(1));
+        let mut c = from_fp6_parts(&g, &zero);
+        c.mul(&from_fp6_parts(&zero, &[FP2::new(), FP2::new_int(1), FP2::new()]));
+        let mut h = from_fp6_parts(&zero, &h);
+        h.inverse();
+        c.mul(&h);
+        c.reduce();
+
+        let (c, _) = fp6_parts(&c);
+        for (chunk, coefficient) in bytes.chunks_mut(FP2_BYTES).zip(c.iter()) {
+            chunk.copy_from_slice(&Fp2(coefficient.clone()).as_bytes());
+        }
+        bytes
+    }
+
+    /// Returns true if the Gt is the identity.
+    pub fn is_identity(&self) -> bool {
+        self.0.is_unity()
+    }
+
+    /// Returns true if the Fp12 element is in Gt, x^r = 1.
+    ///
+    /// Values of this type are always in Gt, this is for elements built with `from_fp12()`.
+    pub fn is_in_subgroup(&self) -> bool {
+        self.0.pow(&Big::new_ints(&CURVE_ORDER)).is_unity()
+    }
+
+    /// Instantiate a Gt from an Fp12 element, checking it is in the subgroup.
+    pub fn from_fp12(x: &Fp12) -> Result<Self, DecodeError> {
+        let gt = Gt(x.0.clone());
+        if !gt.is_in_subgroup() {
+            return Err(DecodeError::NotInSubgroup);
+        }
+        Ok(gt)
+    }
+
+    /// The Gt as an Fp12 element.
+    pub fn to_fp12(&self) -> Fp12 {
+        Fp12(self.0.clone())
+    }
+
+    /// The product of two elements, the group operation.
+    pub fn mul(&self, other: &Gt) -> Gt {
+        let mut x = self.0.clone();
+        x.mul(&other.0);
+        x.reduce();
+        Gt(x)
+    }
+
+    /// The inverse, which is the conjugate over Fp6 for elements of Gt.
+    pub fn inverse(&self) -> Gt {
+        let mut x = self.0.clone();
+        x.conj();
+        x.reduce();
+        Gt(x)
+    }
+
+    /// The Gt raised to the power of a Scalar.
+    pub fn pow(&self, scalar: &Scalar) -> Gt {
+        let mut x = self.0.pow(&scalar.0);
+        x.reduce();
+        Gt(x)
+    }
+
+    /// The product of each Gt raised to its Scalar, an empty slice gives the identity.
+    ///
+    /// Simultaneous exponentiation shares one chain of squarings between all of the terms,
+    /// rather than one per term as when multiplying the results of `pow()`.
+    pub fn multi_exp(terms: &[(Gt, Scalar)]) -> Gt {
+        let bits = terms.iter().map(|(_, scalar)| scalar.0.nbits()).max().unwrap_or(0);
+        let mut x = FP12::new_int(1);
+        for i in (0..bits).rev() {
+            // Elements of Gt are unitary, so the faster unitary squaring applies
+            x.usqr();
+            for (gt, scalar) in terms {
+                if scalar.0.bit(i) == 1 {
+                    x.mul(&gt.0);
+                }
+            }
+        }
+        x.reduce();
+        Gt(x)
+    }
+}
+
+impl PartialEq for Gt {
+    fn eq(&self, other: &Gt) -> bool {
+        self.0.equals(&other.0)
+    }
+}
+
+impl Eq for Gt {}
+
+impl fmt::Debug for Gt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Gt(0x")?;
+        for byte in self.as_compressed_bytes().iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// The optimal ate pairing e(P, Q), the final exponentiation of the Miller loop.
+pub fn pairing(p: &G1Point, q: &G2Point) -> Gt {
+    miller_loop(p, q).final_exponentiation()
+}
+
+/// The output of a Miller loop, which is only meaningful after the final exponentiation.
+///
+/// Results may be multiplied together so that several pairings share a single final
+/// exponentiation, e.g. checking e(A, B) * e(C, D) == 1 as
+/// `miller_loop(A, B).mul(&miller_loop(C, D)).final_exponentiation().is_identity()`.
+#[derive(Clone)]
+pub struct MillerLoopResult(pub(crate) FP12);
+
+impl MillerLoopResult {
+    /// The product of two Miller loop outputs.
+    pub fn mul(&self, other: &MillerLoopResult) -> MillerLoopResult {
+        let mut x = self.0.clone();
+        x.mul(&other.0);
+        MillerLoopResult(x)
+    }
+
+    /// The final exponentiation, mapping the Miller loop output into Gt.
+    pub fn final_exponentiation(&self) -> Gt {
+        let mut x = fexp(&self.0);
+        x.reduce();
+        Gt(x)
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for MillerLoopResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MillerLoopResult({:?})", Fp12(self.0.clone()))
+    }
+}
+
+// Points must be affine for pairing.
+fn affine_pair(p: &G1Point, q: &G2Point) -> (GroupG1, GroupG2) {
+    let mut p = p.0.clone();
+    let mut q = q.0.clone();
+    p.affine();
+    q.affine();
+    (p, q)
+}
+
+/// The Miller loop of the optimal ate pairing e(P, Q).
+pub fn miller_loop(p: &G1Point, q: &G2Point) -> MillerLoopResult {
+    // e(P, Q) is one if either point is the identity
+    if p.is_identity() || q.is_identity() {
+        return MillerLoopResult(FP12::new_int(1));
+    }
+    let (p, q) = affine_pair(p, q);
+    MillerLoopResult(ate(&q, &p))
+}
+
+/// The product of the Miller loops of many pairs, sharing the squarings of a single loop.
+pub fn multi_miller_loop(pairs: &[(&G1Point, &G2Point)]) -> MillerLoopResult {
+    let mut pairing = pair::initmp();
+    for (p, q) in pairs.iter().filter(|(p, q)| !p.is_identity() && !q.is_identity()) {
+        let (p, q) = affine_pair(p, q);
+        pair::another(&mut pairing, &q, &p);
+    }
+    MillerLoopResult(pair::miller(&pairing))
+}
+
+/// Accumulates the Miller loops of pairs as they become available, finalizing the product of
+/// their pairings with a single final exponentiation.
+#[derive(Clone)]
+pub struct PairingAccumulator {
+    miller_loop: MillerLoopResult,
+}
+
+impl PairingAccumulator {
+    /// An empty accumulator, the product of no pairings.
+    pub fn new() -> Self {
+        PairingAccumulator { miller_loop: MillerLoopResult(FP12::new_int(1)) }
+    }
+
+    /// Multiply in the Miller loop of e(P, Q).
+    pub fn add(&mut self, p: &G1Point, q: &G2Point) {
+        self.miller_loop = self.miller_loop.mul(&miller_loop(p, q));
+    }
+
+    /// Multiply in a Miller loop output computed elsewhere.
+    pub fn add_miller_loop(&mut self, miller_loop: &MillerLoopResult) {
+        self.miller_loop = self.miller_loop.mul(miller_loop);
+    }
+
+    /// The product of the accumulated pairings.
+    pub fn finalize(&self) -> Gt {
+        self.miller_loop.final_exponentiation()
+    }
+}
+
+impl Default for PairingAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Fp12 is built by amcl as x = A + B * w + C * w^2 over Fp4 = Fp2(w^3), so A = a0 + a1 * w^3
+// and so on. Returns the coefficients in Fp6 = Fp2(w^2), (a0, b1, c0) of 1, w^4, w^2, and those
+// of h where h * w is the rest, (a1, b0, c1) of w^3, w, w^5.
+fn fp6_parts(x: &FP12) -> ([FP2; 3], [FP2; 3]) {
+    let (a, b, c) = (x.geta(), x.getb(), x.getc());
+    ([a.geta(), b.getb(), c.geta()], [a.getb(), b.geta(), c.getb()])
+}
+
+fn from_fp6_parts(g: &[FP2; 3], h: &[FP2; 3]) -> FP12 {
+    FP12::new_fp4s(
+        &FP4::new_fp2s(&g[0], &h[0]),
+        &FP4::new_fp2s(&h[1], &g[1]),
+        &FP4::new_fp2s(&g[2], &h[2]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate hex;
+    extern crate rand;
+
+    use super::*;
+
+    #[test]
+    fn test_scalar_arithmetic() {
+        let a = Scalar::from_u64(u64::MAX);
+        let b = Scalar::from_u64(12345);
+        assert_eq!(a.add(&b).sub(&b), a);
+        assert_eq!(a.sub(&a), Scalar::zero());
+        assert_eq!(b.mul(&Scalar::one()), b);
+        assert_eq!(b.add(&b.neg()), Scalar::zero());
+        assert_eq!(Scalar::zero().neg(), Scalar::zero());
+        assert_eq!(Scalar::from_u64(6).mul(&Scalar::from_u64(7)), Scalar::from_u64(42));
+        assert!(Scalar::zero().is_zero());
+        assert!(!Scalar::one().is_zero());
+
+        // r - 1 + 1 wraps to 0
+        let minus_one = Scalar::one().neg();
+        assert_eq!(minus_one.add(&Scalar::one()), Scalar::zero());
+        assert_eq!(minus_one.mul(&minus_one), Scalar::one());
+    }
+
+    #[test]
+    fn test_scalar_bytes() {
+        let scalar = Scalar::from_u64(0x0102030405060708);
+        let bytes = scalar.as_bytes();
+        assert_eq!(&bytes[24..], &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(Scalar::from_bytes(&bytes).unwrap(), scalar);
+
+        // r - 1 is the largest Scalar
+        let r_minus_one =
+            hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000")
+                .unwrap();
+        assert_eq!(Scalar::from_bytes(&r_minus_one).unwrap(), Scalar::one().neg());
+        let mut r = r_minus_one.clone();
+        r[31] = 1;
+        assert_eq!(Scalar::from_bytes(&r), Err(DecodeError::FieldElementTooLarge));
+        assert_eq!(
+            Scalar::from_bytes(&r[1..]),
+            Err(DecodeError::InvalidLength { expected: 32, actual: 31 })
+        );
+        assert_eq!(
+            format!("{:?}", Scalar::one()),
+            "Scalar(0x0000000000000000000000000000000000000000000000000000000000000001)"
+        );
+    }
+
+    #[test]
+    fn test_scalar_little_endian() {
+        let scalar = Scalar::from_u64(0x0102030405060708);
+        let bytes = scalar.as_bytes_le();
+        assert_eq!(&bytes[..8], &[8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(Scalar::from_bytes_le(&bytes).unwrap(), scalar);
+
+        let mut r = hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001")
+            .unwrap();
+        r.reverse();
+        assert_eq!(Scalar::from_bytes_le(&r), Err(DecodeError::FieldElementTooLarge));
+    }
+
+    #[test]
+    fn test_scalar_invert() {
+        assert!(Scalar::zero().invert().is_none());
+        assert_eq!(Scalar::one().invert().unwrap(), Scalar::one());
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let scalar = Scalar::random(&mut rng);
+            assert_eq!(scalar.mul(&scalar.invert().unwrap()), Scalar::one());
+        }
+    }
+
+    #[test]
+    fn test_scalar_zeroize() {
+        let mut scalar = Scalar::random(&mut rand::thread_rng());
+        scalar.zeroize();
+        assert!(scalar.0.w.iter().all(|limb| *limb == 0));
+    }
+
+    #[test]
+    fn test_scalar_from_bytes_wide() {
+        // Small values are unchanged
+        let mut bytes = [0u8; SCALAR_WIDE_BYTES];
+        bytes[63] = 42;
+        assert_eq!(Scalar::from_bytes_wide(&bytes), Scalar::from_u64(42));
+
+        // 2^256 and 2^256 + 1
+        bytes[63] = 0;
+        bytes[31] = 1;
+        let two_64 = Scalar::from_u64(u64::MAX).add(&Scalar::one());
+        let two_128 = two_64.mul(&two_64);
+        let two_256 = two_128.mul(&two_128);
+        assert_eq!(Scalar::from_bytes_wide(&bytes), two_256);
+        bytes[63] = 1;
+        assert_eq!(Scalar::from_bytes_wide(&bytes), two_256.add(&Scalar::one()));
+
+        // r reduces to 0 and 2^512 - 1 to (2^256 - 1) * 2^256 + 2^256 - 1
+        let r = hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001")
+            .unwrap();
+        let mut bytes = [0u8; SCALAR_WIDE_BYTES];
+        bytes[32..].copy_from_slice(&r);
+        assert!(Scalar::from_bytes_wide(&bytes).is_zero());
+        let max = Scalar::from_bytes_wide(&[0xff; SCALAR_WIDE_BYTES]);
+        let two_256_minus_one = two_256.sub(&Scalar::one());
+        assert_eq!(max, two_256_minus_one.mul(&two_256).add(&two_256_minus_one));
+        assert_eq!(
+            hex::encode(max.as_bytes()),
+            "0748d9d99f59ff1105d314967254398f2b6cedcb87925c23c999e990f3f29c6c"
+        );
+    }
+
+    #[test]
+    fn test_g1_point() {
+        let g = G1Point::generator();
+        let two = Scalar::from_u64(2);
+        let three = Scalar::from_u64(3);
+        assert_eq!(g.add(&g), g.mul(&two));
+        assert_eq!(g.mul(&three).sub(&g), g.mul(&two));
+        assert_eq!(g.add(&g.neg()), G1Point::identity());
+        assert!(g.sub(&g).is_identity());
+        assert!(g.mul(&Scalar::zero()).is_identity());
+        assert_eq!(g.mul(&Scalar::one().neg()), g.neg());
+
+        let point = g.mul(&Scalar::from_u64(987654321));
+        assert_eq!(G1Point::from_bytes(&point.as_bytes()).unwrap(), point);
+        let uncompressed = point.to_uncompressed_bytes();
+        assert_eq!(G1Point::from_uncompressed_bytes(&uncompressed).unwrap(), point);
+        let identity = G1Point::identity();
+        assert_eq!(G1Point::from_bytes(&identity.as_bytes()).unwrap(), identity);
+        assert!(format!("{:?}", identity).starts_with("G1Point(0xc000"));
+        assert!(point.is_on_curve() && point.is_in_subgroup());
+        assert!(identity.is_on_curve() && identity.is_in_subgroup());
+    }
+
+    #[test]
+    fn test_g2_point() {
+        let g = G2Point::generator();
+        let two = Scalar::from_u64(2);
+        let three = Scalar::from_u64(3);
+        assert_eq!(g.add(&g), g.mul(&two));
+        assert_eq!(g.mul(&three).sub(&g), g.mul(&two));
+        assert_eq!(g.add(&g.neg()), G2Point::identity());
+        assert!(g.mul(&Scalar::zero()).is_identity());
+
+        let point = g.mul(&Scalar::from_u64(987654321));
+        assert_eq!(G2Point::from_bytes(&point.as_bytes()).unwrap(), point);
+        let uncompressed = point.to_uncompressed_bytes();
+        assert_eq!(G2Point::from_uncompressed_bytes(&uncompressed).unwrap(), point);
+        assert!(point.is_on_curve() && point.is_in_subgroup());
+    }
+
+    #[test]
+    fn test_g2_psi() {
+        let x = Scalar::from_u64(0xd201000000010000).neg();
+        let point = G2Point::hash_to_curve(b"cats");
+        assert_eq!(point.psi(), point.mul(&x));
+        assert_eq!(point.psi().psi(), point.mul(&x.mul(&x)));
+        assert_eq!(
+            point.add(&G2Point::generator()).psi(),
+            point.psi().add(&G2Point::generator().psi())
+        );
+        assert!(G2Point::identity().psi().is_identity());
+    }
+
+    #[test]
+    fn test_point_operators() {
+        let g = G1Point::generator();
+        let two = Scalar::from_u64(2);
+        assert_eq!(&g + &g, g.mul(&two));
+        assert_eq!(&(&g * &two) - &g, g);
+        assert_eq!(&g + &-&g, G1Point::identity());
+        assert_eq!(-&g, g.neg());
+
+        let mut point = G1Point::identity();
+        point += &g;
+        point += g.clone();
+        point -= &g;
+        assert_eq!(point, g);
+        point *= &two;
+        assert_eq!(point, g.mul(&two));
+
+        let h = G2Point::generator();
+        let mut point = &h * &two;
+        point -= h.clone();
+        assert_eq!(point, h);
+        assert_eq!(&h - &h, G2Point::identity());
+        assert_eq!(-&(&h + &h), h.mul(&two).neg());
+    }
+
+    #[test]
+    fn test_point_not_in_subgroup() {
+        // (0, 2) is on E(Fp) but not in G1
+        let mut bytes = [0u8; G1_BYTES * 2];
+        bytes[G1_BYTES * 2 - 1] = 2;
+        assert_eq!(G1Point::from_uncompressed_bytes(&bytes), Err(DecodeError::NotInSubgroup));
+    }
+
+    #[test]
+    fn test_pairing_bilinearity() {
+        let a = Scalar::from_u64(1234);
+        let b = Scalar::from_u64(5678);
+        let p = G1Point::generator();
+        let q = G2Point::generator();
+        let e = Gt::generator();
+        assert!(!e.is_identity());
+        assert!(e.is_in_subgroup());
+        assert_eq!(pairing(&p.mul(&a), &q.mul(&b)), e.pow(&a.mul(&b)));
+        assert_eq!(pairing(&p.mul(&a), &q), pairing(&p, &q.mul(&a)));
+        assert_eq!(pairing(&p.neg(), &q), e.inverse());
+        assert_eq!(pairing(&G1Point::identity(), &q), Gt::identity());
+        assert_eq!(pairing(&p, &G2Point::identity()), Gt::identity());
+
+        assert_eq!(e.mul(&e), e.pow(&Scalar::from_u64(2)));
+        assert_eq!(e.mul(&e.inverse()), Gt::identity());
+        assert_eq!(e.pow(&Scalar::zero()), Gt::identity());
+        assert_eq!(e.pow(&Scalar::one().neg()), e.inverse());
+    }
+
+    #[test]
+    fn test_gt_multi_exp() {
+        let mut rng = rand::thread_rng();
+        let e = Gt::generator();
+        assert_eq!(Gt::multi_exp(&[]), Gt::identity());
+        assert_eq!(Gt::multi_exp(&[(e.clone(), Scalar::zero())]), Gt::identity());
+        assert_eq!(Gt::multi_exp(&[(e.clone(), Scalar::one())]), e);
+
+        let terms: Vec<(Gt, Scalar)> =
+            (0..5).map(|_| (e.pow(&Scalar::random(&mut rng)), Scalar::random(&mut rng))).collect();
+        let expected =
+            terms.iter().fold(Gt::identity(), |product, (gt, scalar)| product.mul(&gt.pow(scalar)));
+        assert_eq!(Gt::multi_exp(&terms), expected);
+
+        // Scalars of different lengths
+        let terms = [(e.clone(), Scalar::from_u64(3)), (e.inverse(), Scalar::one().neg())];
+        assert_eq!(Gt::multi_exp(&terms), e.pow(&Scalar::from_u64(4)));
+    }
+
+    #[test]
+    fn test_gt_bytes() {
+        let mut rng = rand::thread_rng();
+        let values = [
+            Gt::identity(),
+            Gt::generator(),
+            Gt::generator().inverse(),
+            Gt::generator().pow(&Scalar::random(&mut rng)),
+        ];
+        for gt in values.iter() {
+            assert_eq!(&Gt::from_bytes(&gt.as_bytes()).unwrap(), gt);
+            let compressed = gt.as_compressed_bytes();
+            assert_eq!(&Gt::from_compressed_bytes(&compressed).unwrap(), gt);
+            assert_eq!(&Gt::from_fp12(&gt.to_fp12()).unwrap(), gt);
+        }
+        assert_eq!(Gt::identity().as_compressed_bytes().to_vec(), vec![0u8; GT_COMPRESSED_BYTES]);
+        // Inverses differ in h alone, so their compressions differ
+        assert_ne!(
+            Gt::generator().as_compressed_bytes().to_vec(),
+            Gt::generator().inverse().as_compressed_bytes().to_vec()
+        );
+
+        // The Miller loop output is not in Gt
+        let mut p = GENERATORG1.clone();
+        let mut q = GENERATORG2.clone();
+        p.affine();
+        q.affine();
+        let miller_loop = Fp12(ate(&q, &p));
+        assert_eq!(Gt::from_fp12(&miller_loop), Err(DecodeError::NotInSubgroup));
+        assert_eq!(Gt::from_bytes(&miller_loop.as_bytes()), Err(DecodeError::NotInSubgroup));
+        assert_eq!(Gt::from_bytes(&Fp12::zero().as_bytes()), Err(DecodeError::NotInSubgroup));
+
+        // c = 1 is (1 + w) / (1 - w), which has norm 1 but is not in Gt
+        let mut one = [0u8; GT_COMPRESSED_BYTES];
+        one[FP2_BYTES - 1] = 1;
+        assert_eq!(Gt::from_compressed_bytes(&one), Err(DecodeError::NotInSubgroup));
+        assert_eq!(
+            Gt::from_compressed_bytes(&one[1..]),
+            Err(DecodeError::InvalidLength { expected: 288, actual: 287 })
+        );
+        let too_large = [0xff; GT_COMPRESSED_BYTES];
+        assert_eq!(Gt::from_compressed_bytes(&too_large), Err(DecodeError::FieldElementTooLarge));
+    }
+
+    #[test]
+    fn test_miller_loop() {
+        let p = G1Point::generator().mul(&Scalar::from_u64(1234));
+        let q = G2Point::generator().mul(&Scalar::from_u64(5678));
+        assert_eq!(miller_loop(&p, &q).final_exponentiation(), pairing(&p, &q));
+        assert_eq!(
+            multi_miller_loop(&[(&p, &q), (&p, &G2Point::generator())]).final_exponentiation(),
+            pairing(&p, &q).mul(&pairing(&p, &G2Point::generator()))
+        );
+        assert!(multi_miller_loop(&[]).final_exponentiation().is_identity());
+
+        // e(P, Q) * e(-P, Q) == 1 with a single final exponentiation
+        let product = miller_loop(&p, &q).mul(&miller_loop(&p.neg(), &q));
+        assert!(product.final_exponentiation().is_identity());
+        assert!(multi_miller_loop(&[(&p, &q), (&p.neg(), &q), (&G1Point::identity(), &q)])
+            .final_exponentiation()
+            .is_identity());
+
+        let mut accumulator = PairingAccumulator::new();
+        assert!(accumulator.finalize().is_identity());
+        accumulator.add(&p, &q);
+        assert_eq!(accumulator.finalize(), pairing(&p, &q));
+        accumulator.add_miller_loop(&miller_loop(&p.neg(), &q));
+        assert!(accumulator.finalize().is_identity());
+    }
+}
@@ -0,0 +1,109 @@
+//! Property based tests, enabled by the `property-tests` feature.
+//!
+//! Keys, messages and arbitrary bytes are generated by proptest, failures are shrunk to a
+//! minimal case.
+
+use super::aggregates::{AggregatePublicKey, AggregateSignature};
+use super::amcl_utils::{G1_BYTES, G2_BYTES};
+use super::keys::{PublicKey, SecretKey};
+use super::signature::Signature;
+use proptest::prelude::*;
+
+fn secret_key() -> impl Strategy<Value = SecretKey> {
+    any::<[u8; 32]>().prop_map(|ikm| SecretKey::key_generate(&ikm, &[]).unwrap())
+}
+
+fn public_key() -> impl Strategy<Value = PublicKey> {
+    secret_key().prop_map(|sk| PublicKey::from_secret_key(&sk))
+}
+
+fn message() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(any::<u8>(), 0..64)
+}
+
+fn signature() -> impl Strategy<Value = Signature> {
+    (secret_key(), message()).prop_map(|(sk, msg)| Signature::new(&msg, &sk))
+}
+
+proptest! {
+    #[test]
+    fn prop_secret_key_round_trip(sk in secret_key()) {
+        let decoded = SecretKey::from_bytes(&sk.as_bytes()).unwrap();
+        prop_assert_eq!(decoded.as_bytes(), sk.as_bytes());
+    }
+
+    #[test]
+    fn prop_public_key_round_trip(pk in public_key()) {
+        let decoded = PublicKey::from_bytes(&pk.as_bytes()).unwrap();
+        prop_assert_eq!(decoded.as_bytes(), pk.as_bytes());
+
+        let decoded = PublicKey::from_uncompressed_bytes(&pk.to_uncompressed_bytes()).unwrap();
+        prop_assert_eq!(decoded.as_bytes(), pk.as_bytes());
+    }
+
+    #[test]
+    fn prop_signature_round_trip(sig in signature()) {
+        let decoded = Signature::from_bytes(&sig.as_bytes()).unwrap();
+        prop_assert_eq!(decoded.as_bytes(), sig.as_bytes());
+
+        let decoded = Signature::from_uncompressed_bytes(&sig.to_uncompressed_bytes()).unwrap();
+        prop_assert_eq!(decoded.as_bytes(), sig.as_bytes());
+    }
+
+    #[test]
+    fn prop_public_key_canonical(bytes in prop::collection::vec(any::<u8>(), G1_BYTES)) {
+        // Any encoding which decodes must be the unique encoding of that point
+        if let Ok(pk) = PublicKey::from_bytes(&bytes) {
+            prop_assert_eq!(&pk.as_bytes()[..], &bytes[..]);
+        }
+    }
+
+    #[test]
+    fn prop_signature_canonical(bytes in prop::collection::vec(any::<u8>(), G2_BYTES)) {
+        if let Ok(sig) = Signature::from_bytes(&bytes) {
+            prop_assert_eq!(&sig.as_bytes()[..], &bytes[..]);
+        }
+    }
+
+    #[test]
+    fn prop_sign_verify(sk in secret_key(), msg in message(), other_msg in message()) {
+        let pk = PublicKey::from_secret_key(&sk);
+        let sig = Signature::new(&msg, &sk);
+        prop_assert!(sig.verify(&msg, &pk));
+        prop_assert_eq!(sig.verify(&other_msg, &pk), msg == other_msg);
+    }
+
+    #[test]
+    fn prop_aggregate_associative(a in signature(), b in signature(), c in signature()) {
+        // (a + b) + c
+        let mut left = AggregateSignature::from_signature(&a);
+        left.add(&b);
+        left.add(&c);
+
+        // a + (c + b)
+        let mut right = AggregateSignature::from_signature(&c);
+        right.add(&b);
+        let mut aggregate = AggregateSignature::from_signature(&a);
+        aggregate.add_aggregate(&right);
+
+        prop_assert_eq!(left.as_bytes(), aggregate.as_bytes());
+        prop_assert_eq!(left.as_bytes(), AggregateSignature::aggregate(&[&b, &c, &a]).as_bytes());
+    }
+
+    #[test]
+    fn prop_fast_aggregate_verify(
+        sks in prop::collection::vec(secret_key(), 1..8),
+        msg in message(),
+    ) {
+        let pks: Vec<PublicKey> = sks.iter().map(PublicKey::from_secret_key).collect();
+        let sigs: Vec<Signature> = sks.iter().map(|sk| Signature::new(&msg, sk)).collect();
+        let pks: Vec<&PublicKey> = pks.iter().collect();
+        let sigs: Vec<&Signature> = sigs.iter().collect();
+
+        let agg_sig = AggregateSignature::aggregate(&sigs);
+        prop_assert!(agg_sig.fast_aggregate_verify(&msg, &pks));
+
+        let agg_pk = AggregatePublicKey::aggregate(&pks).unwrap();
+        prop_assert!(agg_sig.fast_aggregate_verify_pre_aggregated(&msg, &agg_pk));
+    }
+}
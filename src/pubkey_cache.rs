@@ -0,0 +1,232 @@
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use super::aggregates::{AggregatePublicKey, AggregateSignature};
+use super::amcl_utils::G1_BYTES;
+use super::errors::DecodeError;
+use super::keys::PublicKey;
+use super::signature::Signature;
+
+/// A cache of decompressed and validated PublicKeys, keyed by their compressed bytes.
+///
+/// Decompression and the subgroup check dominate the cost of verifying against keys received
+/// as bytes, e.g. from blocks and attestations. Once the cache holds `capacity` keys the least
+/// recently used key is evicted.
+pub struct PubkeyCache {
+    capacity: usize,
+    // Incremented on every access, orders the keys by when they were last used
+    tick: u64,
+    keys: BTreeMap<[u8; G1_BYTES], (PublicKey, u64)>,
+    last_used: BTreeMap<u64, [u8; G1_BYTES]>,
+}
+
+impl PubkeyCache {
+    /// An empty cache holding at most `capacity` PublicKeys.
+    pub fn new(capacity: usize) -> Self {
+        PubkeyCache {
+            capacity: capacity.max(1),
+            tick: 0,
+            keys: BTreeMap::new(),
+            last_used: BTreeMap::new(),
+        }
+    }
+
+    /// The PublicKey encoded by the compressed bytes, decompressed and validated as in
+    /// `PublicKey::from_bytes()` unless it is cached.
+    pub fn get(&mut self, bytes: &[u8]) -> Result<&PublicKey, DecodeError> {
+        let key = cache_key(bytes)?;
+        self.tick += 1;
+        let tick = self.tick;
+        let public_key = match self.keys.remove(&key) {
+            Some((public_key, last_used)) => {
+                self.last_used.remove(&last_used);
+                public_key
+            }
+            None => {
+                let public_key = PublicKey::from_bytes(bytes)?;
+                self.evict();
+                public_key
+            }
+        };
+        self.last_used.insert(tick, key);
+        Ok(&self.keys.entry(key).or_insert((public_key, tick)).0)
+    }
+
+    /// Cache a PublicKey which is already validated, e.g. when loading a validator registry.
+    pub fn insert(&mut self, public_key: &PublicKey) {
+        let key = public_key.as_bytes();
+        self.tick += 1;
+        if let Some((_, last_used)) = self.keys.remove(&key) {
+            self.last_used.remove(&last_used);
+        } else {
+            self.evict();
+        }
+        self.keys.insert(key, (public_key.clone(), self.tick));
+        self.last_used.insert(self.tick, key);
+    }
+
+    /// Returns true if the PublicKey of the compressed bytes is cached.
+    pub fn contains(&self, bytes: &[u8]) -> bool {
+        match cache_key(bytes) {
+            Ok(key) => self.keys.contains_key(&key),
+            Err(_) => false,
+        }
+    }
+
+    /// The number of cached PublicKeys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns true if no PublicKeys are cached.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Remove every cached PublicKey.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.last_used.clear();
+    }
+
+    /// CoreVerify against the PublicKey of the compressed bytes.
+    ///
+    /// Returns false if the bytes are not a valid PublicKey.
+    pub fn verify(&mut self, signature: &Signature, msg: &[u8], public_key: &[u8]) -> bool {
+        match self.get(public_key) {
+            Ok(public_key) => signature.verify(msg, public_key),
+            Err(_) => false,
+        }
+    }
+
+    /// FastAggregateVerify against the PublicKeys of the compressed bytes.
+    ///
+    /// Returns false if there are no PublicKeys or any bytes are not a valid PublicKey.
+    /// PublicKeys must all be verified via Proof of Possession.
+    pub fn fast_aggregate_verify(
+        &mut self,
+        signature: &AggregateSignature,
+        msg: &[u8],
+        public_keys: &[&[u8]],
+    ) -> bool {
+        let (first, rest) = match public_keys.split_first() {
+            Some(split) => split,
+            None => return false,
+        };
+        let mut aggregate_public_key = match self.get(first) {
+            Ok(public_key) => AggregatePublicKey::from_public_key(public_key),
+            Err(_) => return false,
+        };
+        for bytes in rest {
+            match self.get(bytes) {
+                Ok(public_key) => aggregate_public_key.add(public_key),
+                Err(_) => return false,
+            }
+        }
+        signature.fast_aggregate_verify_pre_aggregated(msg, &aggregate_public_key)
+    }
+
+    // Evict the least recently used key if the cache is full.
+    fn evict(&mut self) {
+        if self.keys.len() < self.capacity {
+            return;
+        }
+        let oldest = self.last_used.keys().next().cloned();
+        if let Some(oldest) = oldest {
+            if let Some(key) = self.last_used.remove(&oldest) {
+                self.keys.remove(&key);
+            }
+        }
+    }
+}
+
+fn cache_key(bytes: &[u8]) -> Result<[u8; G1_BYTES], DecodeError> {
+    if bytes.len() != G1_BYTES {
+        return Err(DecodeError::InvalidLength { expected: G1_BYTES, actual: bytes.len() });
+    }
+    let mut key = [0u8; G1_BYTES];
+    key.copy_from_slice(bytes);
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::keys::Keypair;
+    use super::*;
+
+    #[test]
+    fn test_pubkey_cache_get() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let bytes = keypair.pk.as_bytes();
+        let mut cache = PubkeyCache::new(4);
+
+        assert!(!cache.contains(&bytes));
+        assert_eq!(cache.get(&bytes).unwrap().as_bytes(), bytes);
+        assert!(cache.contains(&bytes));
+        assert_eq!(cache.get(&bytes).unwrap().as_bytes(), bytes);
+        assert_eq!(cache.len(), 1);
+
+        assert_eq!(
+            cache.get(&bytes[..47]).err(),
+            Some(DecodeError::InvalidLength { expected: G1_BYTES, actual: 47 })
+        );
+        assert!(cache.get(&[0u8; G1_BYTES]).is_err());
+        assert_eq!(cache.len(), 1);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_pubkey_cache_eviction() {
+        let keys: Vec<[u8; G1_BYTES]> =
+            (0..3).map(|_| Keypair::random(&mut rand::thread_rng()).pk.as_bytes()).collect();
+        let mut cache = PubkeyCache::new(2);
+
+        cache.get(&keys[0]).unwrap();
+        cache.get(&keys[1]).unwrap();
+        // Using the first key makes the second the least recently used
+        cache.get(&keys[0]).unwrap();
+        cache.get(&keys[2]).unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains(&keys[0]));
+        assert!(!cache.contains(&keys[1]));
+        assert!(cache.contains(&keys[2]));
+
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        cache.insert(&keypair.pk);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains(&keypair.pk.as_bytes()));
+        assert!(!cache.contains(&keys[0]));
+    }
+
+    #[test]
+    fn test_pubkey_cache_verify() {
+        let keypairs: Vec<Keypair> =
+            (0..3).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let keys: Vec<[u8; G1_BYTES]> =
+            keypairs.iter().map(|keypair| keypair.pk.as_bytes()).collect();
+        let key_refs: Vec<&[u8]> = keys.iter().map(|key| &key[..]).collect();
+        let mut cache = PubkeyCache::new(8);
+
+        let msg = b"cats";
+        let signature = Signature::new(msg, &keypairs[0].sk);
+        assert!(cache.verify(&signature, msg, &keys[0]));
+        assert!(!cache.verify(&signature, msg, &keys[1]));
+        assert!(!cache.verify(&signature, msg, &[0u8; G1_BYTES]));
+
+        let mut aggregate_signature = AggregateSignature::new();
+        for keypair in &keypairs {
+            aggregate_signature.add(&Signature::new(msg, &keypair.sk));
+        }
+        assert!(cache.fast_aggregate_verify(&aggregate_signature, msg, &key_refs));
+        assert!(!cache.fast_aggregate_verify(&aggregate_signature, msg, &key_refs[..2]));
+        assert!(!cache.fast_aggregate_verify(&aggregate_signature, msg, &[]));
+        assert_eq!(cache.len(), 3);
+    }
+}
@@ -0,0 +1,446 @@
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+#[cfg(feature = "mmap")]
+use std::collections::HashMap;
+#[cfg(feature = "mmap")]
+use std::fs::File;
+#[cfg(feature = "mmap")]
+use std::io;
+#[cfg(feature = "mmap")]
+use std::path::Path;
+#[cfg(feature = "mmap")]
+use std::sync::atomic::{AtomicU8, Ordering};
+#[cfg(feature = "mmap")]
+use std::sync::Arc;
+
+use super::aggregates::AggregatePublicKey;
+use super::amcl_utils::{decompress_g1_unchecked, AmclError, GroupG1, G1_BYTES};
+use super::errors::DecodeError;
+use super::keys::PublicKey;
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
+#[cfg(feature = "mmap")]
+use spin::Once;
+
+/// A handle to a PublicKey in a PublicKeySet.
+///
+/// Handles are issued in insertion order, following the keys of a mapped file, so the handle
+/// of the `i`th key has index `i`, matching bit `i` of a participation bitfield.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct KeyHandle(usize);
+
+impl KeyHandle {
+    /// The index of the PublicKey in its PublicKeySet.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// Interned storage for a large set of validated PublicKeys, e.g. a validator registry.
+///
+/// The compressed keys are stored contiguously and, once `decompress()` is called, so are the
+/// decompressed points. Keys are aggregated directly from this storage rather than through
+/// individually allocated PublicKeys.
+///
+/// With the `mmap` feature a set may be loaded from a memory-mapped file, see `from_file()`.
+#[derive(Clone, Default)]
+pub struct PublicKeySet {
+    // Keys of a mapped file, which precede the inserted keys
+    #[cfg(feature = "mmap")]
+    mapped: Option<Arc<MappedKeys>>,
+    // G1_BYTES per inserted key, in handle order
+    compressed: Vec<u8>,
+    points: Option<Vec<GroupG1>>,
+    handles: BTreeMap<[u8; G1_BYTES], KeyHandle>,
+}
+
+impl PublicKeySet {
+    /// An empty set storing only the compressed keys.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A set of the keys in a file of concatenated compressed PublicKeys, e.g. a validator
+    /// registry, with handles in file order.
+    ///
+    /// The file is memory-mapped rather than read onto the heap, and each key is validated as
+    /// in `PublicKey::from_bytes()` the first time it is used. The keys of the file are indexed
+    /// on the first call to `handle()` or `insert()`, a duplicated key has the handle of its
+    /// first occurrence. The file must not be modified while it is mapped.
+    #[cfg(feature = "mmap")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // The file is documented to not be modified while it is mapped
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() % G1_BYTES != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "file length is not a multiple of the compressed PublicKey length",
+            ));
+        }
+        let validity = (0..map.len() / G1_BYTES).map(|_| AtomicU8::new(UNCHECKED)).collect();
+        let mapped = MappedKeys { map, validity, index: Once::new() };
+        Ok(PublicKeySet { mapped: Some(Arc::new(mapped)), ..Self::default() })
+    }
+
+    /// Insert the PublicKey of the compressed bytes, validated as in `PublicKey::from_bytes()`.
+    ///
+    /// Returns the existing handle if the key is already in the set.
+    pub fn insert(&mut self, bytes: &[u8]) -> Result<KeyHandle, DecodeError> {
+        if let Some(handle) = self.handle(bytes) {
+            return Ok(handle);
+        }
+        let public_key = PublicKey::from_bytes(bytes)?;
+        Ok(self.push(public_key))
+    }
+
+    /// Insert a PublicKey which is already validated.
+    ///
+    /// Returns the existing handle if the key is already in the set.
+    pub fn insert_public_key(&mut self, public_key: &PublicKey) -> KeyHandle {
+        match self.handle(&public_key.as_bytes()) {
+            Some(handle) => handle,
+            None => self.push(public_key.clone()),
+        }
+    }
+
+    /// The handle of the PublicKey of the compressed bytes, if it is in the set.
+    pub fn handle(&self, bytes: &[u8]) -> Option<KeyHandle> {
+        if bytes.len() != G1_BYTES {
+            return None;
+        }
+        let mut key = [0u8; G1_BYTES];
+        key.copy_from_slice(bytes);
+        #[cfg(feature = "mmap")]
+        {
+            if let Some(ref mapped) = self.mapped {
+                if let Some(index) = mapped.position(&key) {
+                    return Some(KeyHandle(index));
+                }
+            }
+        }
+        self.handles.get(&key).cloned()
+    }
+
+    /// The number of PublicKeys in the set.
+    pub fn len(&self) -> usize {
+        self.mapped_len() + self.handles.len()
+    }
+
+    /// Returns true if the set holds no PublicKeys.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Keep the decompressed form of every PublicKey, current and future, trading memory for
+    /// faster aggregation.
+    pub fn decompress(&mut self) {
+        if self.points.is_some() {
+            return;
+        }
+        let mut points = Vec::with_capacity(self.len());
+        for index in 0..self.len() {
+            // Invalid mapped keys are kept as infinity, they remain rejected when aggregated
+            let mut point = GroupG1::new();
+            let _ = self.add_point(&mut point, index);
+            points.push(point);
+        }
+        self.points = Some(points);
+    }
+
+    /// Returns true if the decompressed form of the PublicKeys is kept.
+    pub fn is_decompressed(&self) -> bool {
+        self.points.is_some()
+    }
+
+    /// The compressed bytes of the PublicKey, None if the handle is from another set.
+    pub fn bytes(&self, handle: KeyHandle) -> Option<&[u8]> {
+        #[cfg(feature = "mmap")]
+        {
+            if let Some(ref mapped) = self.mapped {
+                if handle.0 < mapped.len() {
+                    return mapped.bytes(handle.0);
+                }
+            }
+        }
+        let index = handle.0 - self.mapped_len();
+        self.compressed.get(index * G1_BYTES..(index + 1) * G1_BYTES)
+    }
+
+    /// The PublicKey, None if the handle is from another set or the key of a mapped file is
+    /// invalid.
+    pub fn public_key(&self, handle: KeyHandle) -> Option<PublicKey> {
+        let mut point = GroupG1::new();
+        self.add_point(&mut point, handle.0).ok()?;
+        Some(PublicKey { point })
+    }
+
+    /// Aggregate the PublicKeys of the handles.
+    ///
+    /// Errors if there are no handles or any handle is from another set.
+    pub fn aggregate(&self, handles: &[KeyHandle]) -> Result<AggregatePublicKey, AmclError> {
+        if handles.is_empty() {
+            return Err(AmclError::AggregateEmptyPoints);
+        }
+        let mut point = GroupG1::new();
+        for handle in handles {
+            self.add_point(&mut point, handle.0)?;
+        }
+        Ok(AggregatePublicKey { point })
+    }
+
+    /// Aggregate the PublicKeys selected by a bitfield.
+    ///
+    /// Bit `i` of the bitfield (little-endian bit order within each byte) selects the key with
+    /// index `i`, as in `AggregatePublicKey::from_pubkeys_filtered()`. Errors if no key is
+    /// selected or a bit beyond the end of the set is set.
+    pub fn aggregate_filtered(&self, bitfield: &[u8]) -> Result<AggregatePublicKey, AmclError> {
+        let mut point = GroupG1::new();
+        let mut participants = 0;
+        for (i, byte) in bitfield.iter().enumerate() {
+            for bit in 0..8 {
+                if byte >> bit & 1 == 1 {
+                    self.add_point(&mut point, i * 8 + bit)?;
+                    participants += 1;
+                }
+            }
+        }
+
+        if participants == 0 {
+            return Err(AmclError::AggregateEmptyPoints);
+        }
+        Ok(AggregatePublicKey { point })
+    }
+
+    fn push(&mut self, public_key: PublicKey) -> KeyHandle {
+        let handle = KeyHandle(self.len());
+        let bytes = public_key.as_bytes();
+        self.compressed.extend_from_slice(&bytes);
+        if let Some(ref mut points) = self.points {
+            points.push(public_key.point);
+        }
+        self.handles.insert(bytes, handle);
+        handle
+    }
+
+    #[cfg(feature = "mmap")]
+    fn mapped_len(&self) -> usize {
+        self.mapped.as_ref().map_or(0, |mapped| mapped.len())
+    }
+
+    #[cfg(not(feature = "mmap"))]
+    fn mapped_len(&self) -> usize {
+        0
+    }
+
+    // Add the key at `index` to `point`, decompressing it unless the decompressed form is kept.
+    fn add_point(&self, point: &mut GroupG1, index: usize) -> Result<(), AmclError> {
+        #[cfg(feature = "mmap")]
+        {
+            if let Some(ref mapped) = self.mapped {
+                if index < mapped.len() && !mapped.is_valid(index) {
+                    point.add(&mapped.validate(index)?);
+                    return Ok(());
+                }
+            }
+        }
+        if let Some(ref points) = self.points {
+            let key = points.get(index).ok_or(AmclError::InvalidPoint)?;
+            point.add(key);
+            return Ok(());
+        }
+        let bytes = self.bytes(KeyHandle(index)).ok_or(AmclError::InvalidPoint)?;
+        // Inserted keys are validated on insertion, mapped keys by the check above
+        let key = decompress_g1_unchecked(bytes).map_err(|_| AmclError::InvalidPoint)?;
+        point.add(&key);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mmap")]
+const UNCHECKED: u8 = 0;
+#[cfg(feature = "mmap")]
+const VALID: u8 = 1;
+#[cfg(feature = "mmap")]
+const INVALID: u8 = 2;
+
+// The keys of a mapped file along with whether each has been validated.
+#[cfg(feature = "mmap")]
+struct MappedKeys {
+    map: Mmap,
+    validity: Vec<AtomicU8>,
+    // The index of each key, built on the first lookup
+    index: Once<HashMap<[u8; G1_BYTES], usize>>,
+}
+
+#[cfg(feature = "mmap")]
+impl MappedKeys {
+    fn len(&self) -> usize {
+        self.validity.len()
+    }
+
+    fn bytes(&self, index: usize) -> Option<&[u8]> {
+        self.map.get(index * G1_BYTES..(index + 1) * G1_BYTES)
+    }
+
+    // The index of the first occurrence of the key in the file.
+    fn position(&self, key: &[u8; G1_BYTES]) -> Option<usize> {
+        let positions = self.index.call_once(|| {
+            let mut positions = HashMap::with_capacity(self.len());
+            for (index, bytes) in self.map.chunks_exact(G1_BYTES).enumerate() {
+                let mut key = [0u8; G1_BYTES];
+                key.copy_from_slice(bytes);
+                positions.entry(key).or_insert(index);
+            }
+            positions
+        });
+        positions.get(key).cloned()
+    }
+
+    fn is_valid(&self, index: usize) -> bool {
+        self.validity.get(index).is_some_and(|validity| validity.load(Ordering::Relaxed) == VALID)
+    }
+
+    // Validate the key at `index` as in `PublicKey::from_bytes()`, recording the result.
+    fn validate(&self, index: usize) -> Result<GroupG1, AmclError> {
+        let validity = self.validity.get(index).ok_or(AmclError::InvalidPoint)?;
+        if validity.load(Ordering::Relaxed) == INVALID {
+            return Err(AmclError::InvalidPoint);
+        }
+        let bytes = self.bytes(index).ok_or(AmclError::InvalidPoint)?;
+        match PublicKey::from_bytes(bytes) {
+            Ok(public_key) => {
+                validity.store(VALID, Ordering::Relaxed);
+                Ok(public_key.point)
+            }
+            Err(_) => {
+                validity.store(INVALID, Ordering::Relaxed);
+                Err(AmclError::InvalidPoint)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::keys::Keypair;
+    use super::*;
+
+    fn public_keys(n: usize) -> Vec<PublicKey> {
+        (0..n).map(|_| Keypair::random(&mut rand::thread_rng()).pk).collect()
+    }
+
+    #[test]
+    fn test_public_key_set_insert() {
+        let keys = public_keys(3);
+        let mut set = PublicKeySet::new();
+
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(set.insert(&key.as_bytes()).unwrap().index(), i);
+        }
+        // Keys are interned
+        assert_eq!(set.insert(&keys[1].as_bytes()).unwrap().index(), 1);
+        assert_eq!(set.insert_public_key(&keys[2]).index(), 2);
+        assert_eq!(set.len(), 3);
+
+        let handle = set.handle(&keys[1].as_bytes()).unwrap();
+        assert_eq!(set.bytes(handle).unwrap(), &keys[1].as_bytes()[..]);
+        assert_eq!(set.public_key(handle).unwrap().as_bytes(), keys[1].as_bytes());
+        assert_eq!(set.handle(&[0u8; G1_BYTES]), None);
+        assert_eq!(set.bytes(KeyHandle(3)), None);
+
+        // Invalid keys are rejected
+        let mut infinity = [0u8; G1_BYTES];
+        infinity[0] = 0xc0;
+        assert_eq!(set.insert(&infinity), Err(DecodeError::Infinity));
+        assert!(set.insert(&keys[0].as_bytes()[..47]).is_err());
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn test_public_key_set_aggregate() {
+        let keys = public_keys(10);
+        let bitfield = [0b0100_1001, 0b0000_0010];
+        let expected = AggregatePublicKey::from_pubkeys_filtered(&keys, &bitfield).unwrap();
+
+        let mut set = PublicKeySet::new();
+        for key in keys.iter().take(5) {
+            set.insert_public_key(key);
+        }
+        set.decompress();
+        assert!(set.is_decompressed());
+        for key in keys.iter().skip(5) {
+            set.insert_public_key(key);
+        }
+
+        let mut compressed_only = PublicKeySet::new();
+        for key in &keys {
+            compressed_only.insert_public_key(key);
+        }
+
+        for set in &[set, compressed_only] {
+            let aggregate = set.aggregate_filtered(&bitfield).unwrap();
+            assert_eq!(aggregate.as_bytes(), expected.as_bytes());
+
+            let handles = [KeyHandle(0), KeyHandle(3), KeyHandle(6), KeyHandle(9)];
+            assert_eq!(set.aggregate(&handles).unwrap().as_bytes(), expected.as_bytes());
+
+            assert_eq!(set.aggregate_filtered(&[0, 0]), Err(AmclError::AggregateEmptyPoints));
+            assert_eq!(set.aggregate(&[]), Err(AmclError::AggregateEmptyPoints));
+            // Bits beyond the end of the set
+            assert_eq!(set.aggregate_filtered(&[0, 0b0000_0100]), Err(AmclError::InvalidPoint));
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_public_key_set_from_file() {
+        use std::fs;
+
+        let keys = public_keys(4);
+        let mut contents: Vec<u8> = keys.iter().flat_map(|key| key.as_bytes().to_vec()).collect();
+        // An invalid key, only rejected once used
+        contents[G1_BYTES * 3 + G1_BYTES - 1] ^= 1;
+        // A duplicate of the first key
+        contents.extend_from_slice(&keys[0].as_bytes());
+        let path = std::env::temp_dir().join(format!("milagro_bls_keys_{}", std::process::id()));
+        fs::write(&path, &contents).unwrap();
+
+        let mut set = PublicKeySet::from_file(&path).unwrap();
+        assert_eq!(set.len(), 5);
+        assert_eq!(set.handle(&keys[2].as_bytes()), Some(KeyHandle(2)));
+        assert_eq!(set.handle(&keys[0].as_bytes()), Some(KeyHandle(0)));
+        assert_eq!(set.handle(&[0u8; G1_BYTES]), None);
+        assert_eq!(set.bytes(KeyHandle(1)).unwrap(), &keys[1].as_bytes()[..]);
+
+        let expected = AggregatePublicKey::aggregate(&[&keys[0], &keys[2]]).unwrap();
+        assert_eq!(set.aggregate_filtered(&[0b0000_0101]).unwrap().as_bytes(), expected.as_bytes());
+        assert!(set.aggregate_filtered(&[0b0000_1001]).is_err());
+        assert!(set.public_key(KeyHandle(3)).is_none());
+
+        // Keys are inserted after the mapped keys, which are interned
+        assert_eq!(set.insert_public_key(&keys[1]), KeyHandle(1));
+        let other = public_keys(1);
+        assert_eq!(set.insert_public_key(&other[0]), KeyHandle(5));
+        assert_eq!(set.public_key(KeyHandle(5)).unwrap().as_bytes(), other[0].as_bytes());
+
+        set.decompress();
+        assert_eq!(
+            set.aggregate(&[KeyHandle(0), KeyHandle(2)]).unwrap().as_bytes(),
+            expected.as_bytes()
+        );
+        assert!(set.aggregate(&[KeyHandle(3)]).is_err());
+
+        // Truncated files are rejected
+        fs::write(&path, &contents[1..]).unwrap();
+        assert!(PublicKeySet::from_file(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
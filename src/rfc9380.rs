@@ -0,0 +1,323 @@
+//! RFC 9380 test vectors for hashing to G1 and G2, for the BLS12381G1_XMD:SHA-256_SSWU_RO_,
+//! BLS12381G2_XMD:SHA-256_SSWU_RO_ and matching _NU_ suites.
+//!
+//! Each vector checks hash_to_field (u), map_to_curve (Q0, Q1) where listed, and the final
+//! point P, see https://www.rfc-editor.org/rfc/rfc9380#appendix-J
+//!
+//! Field elements are big-endian hex, with elements of Fp2 given as (c0, c1).
+
+extern crate hex;
+
+use super::amcl_utils::{self, GroupG1, GroupG2, FP2, MODBYTES};
+use super::fields::{Fp, Fp2};
+use super::hash_to_curve::*;
+use BLSCurve::fp::FP;
+
+const DST_G1_RO: &[u8] = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+const DST_G2_RO: &[u8] = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+const DST_G1_NU: &[u8] = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_NU_";
+const DST_G2_NU: &[u8] = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_NU_";
+
+struct G1Vector {
+    u: [&'static str; 2],
+    p: [&'static str; 2],
+}
+
+struct G2Vector {
+    u: [&'static str; 4],
+    p: [&'static str; 4],
+}
+
+// The messages of every suite, in order.
+fn messages() -> Vec<Vec<u8>> {
+    let mut q128 = b"q128_".to_vec();
+    q128.extend_from_slice(&[b'q'; 128]);
+    let mut a512 = b"a512_".to_vec();
+    a512.extend_from_slice(&[b'a'; 512]);
+    vec![b"".to_vec(), b"abc".to_vec(), b"abcdef0123456789".to_vec(), q128, a512]
+}
+
+fn fp_hex(element: &FP) -> String {
+    let mut bytes = [0u8; MODBYTES];
+    element.redc().to_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn fp2_hex(element: &FP2) -> [String; 2] {
+    let mut c0 = [0u8; MODBYTES];
+    let mut c1 = [0u8; MODBYTES];
+    element.geta().to_bytes(&mut c0);
+    element.getb().to_bytes(&mut c1);
+    [hex::encode(c0), hex::encode(c1)]
+}
+
+fn g1_hex(point: &GroupG1) -> [String; 2] {
+    let (x, y) = amcl_utils::g1_to_coordinates(point);
+    [hex::encode(x), hex::encode(y)]
+}
+
+// Coordinates are serialized as (c1, c0), reorder to (x.c0, x.c1, y.c0, y.c1)
+fn g2_hex(point: &GroupG2) -> [String; 4] {
+    let (x, y) = amcl_utils::g2_to_coordinates(point);
+    [
+        hex::encode(&x[MODBYTES..]),
+        hex::encode(&x[..MODBYTES]),
+        hex::encode(&y[MODBYTES..]),
+        hex::encode(&y[..MODBYTES]),
+    ]
+}
+
+const G1_VECTORS: [G1Vector; 5] = [
+    G1Vector {
+        u: [
+            "0ba14bd907ad64a016293ee7c2d276b8eae71f25a4b941eece7b0d89f17f75cb3ae5438a614fb61d6835ad59f29c564f",
+            "019b9bd7979f12657976de2884c7cce192b82c177c80e0ec604436a7f538d231552f0d96d9f7babe5fa3b19b3ff25ac9",
+        ],
+        p: [
+            "052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1",
+            "08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265",
+        ],
+    },
+    G1Vector {
+        u: [
+            "0d921c33f2bad966478a03ca35d05719bdf92d347557ea166e5bba579eea9b83e9afa5c088573c2281410369fbd32951",
+            "003574a00b109ada2f26a37a91f9d1e740dffd8d69ec0c35e1e9f4652c7dba61123e9dd2e76c655d956e2b3462611139",
+        ],
+        p: [
+            "03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903",
+            "0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d",
+        ],
+    },
+    G1Vector {
+        u: [
+            "062d1865eb80ebfa73dcfc45db1ad4266b9f3a93219976a3790ab8d52d3e5f1e62f3b01795e36834b17b70e7b76246d4",
+            "0cdc3e2f271f29c4ff75020857ce6c5d36008c9b48385ea2f2bf6f96f428a3deb798aa033cd482d1cdc8b30178b08e3a",
+        ],
+        p: [
+            "11e0b079dea29a68f0383ee94fed1b940995272407e3bb916bbf268c263ddd57a6a27200a784cbc248e84f357ce82d98",
+            "03a87ae2caf14e8ee52e51fa2ed8eefe80f02457004ba4d486d6aa1f517c0889501dc7413753f9599b099ebcbbd2d709",
+        ],
+    },
+    G1Vector {
+        u: [
+            "010476f6a060453c0b1ad0b628f3e57c23039ee16eea5e71bb87c3b5419b1255dc0e5883322e563b84a29543823c0e86",
+            "0b1a912064fb0554b180e07af7e787f1f883a0470759c03c1b6509eb8ce980d1670305ae7b928226bb58fdc0a419f46e",
+        ],
+        p: [
+            "15f68eaa693b95ccb85215dc65fa81038d69629f70aeee0d0f677cf22285e7bf58d7cb86eefe8f2e9bc3f8cb84fac488",
+            "1807a1d50c29f430b8cafc4f8638dfeeadf51211e1602a5f184443076715f91bb90a48ba1e370edce6ae1062f5e6dd38",
+        ],
+    },
+    G1Vector {
+        u: [
+            "0a8ffa7447f6be1c5a2ea4b959c9454b431e29ccc0802bc052413a9c5b4f9aac67a93431bd480d15be1e057c8a08e8c6",
+            "05d487032f602c90fa7625dbafe0f4a49ef4a6b0b33d7bb349ff4cf5410d297fd6241876e3e77b651cfc8191e40a68b7",
+        ],
+        p: [
+            "082aabae8b7dedb0e78aeb619ad3bfd9277a2f77ba7fad20ef6aabdc6c31d19ba5a6d12283553294c1825c4b3ca2dcfe",
+            "05b84ae5a942248eea39e1d91030458c40153f3b654ab7872d779ad1e942856a20c438e8d99bc8abfbf74729ce1f7ac8",
+        ],
+    },
+];
+
+const G2_VECTORS: [G2Vector; 5] = [
+    G2Vector {
+        u: [
+            "03dbc2cce174e91ba93cbb08f26b917f98194a2ea08d1cce75b2b9cc9f21689d80bd79b594a613d0a68eb807dfdc1cf8",
+            "05a2acec64114845711a54199ea339abd125ba38253b70a92c876df10598bd1986b739cad67961eb94f7076511b3b39a",
+            "02f99798e8a5acdeed60d7e18e9120521ba1f47ec090984662846bc825de191b5b7641148c0dbc237726a334473eee94",
+            "145a81e418d4010cc027a68f14391b30074e89e60ee7a22f87217b2f6eb0c4b94c9115b436e6fa4607e95a98de30a435",
+        ],
+        p: [
+            "0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a",
+            "05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+            "0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92",
+            "12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+        ],
+    },
+    G2Vector {
+        u: [
+            "15f7c0aa8f6b296ab5ff9c2c7581ade64f4ee6f1bf18f55179ff44a2cf355fa53dd2a2158c5ecb17d7c52f63e7195771",
+            "01c8067bf4c0ba709aa8b9abc3d1cef589a4758e09ef53732d670fd8739a7274e111ba2fcaa71b3d33df2a3a0c8529dd",
+            "187111d5e088b6b9acfdfad078c4dacf72dcd17ca17c82be35e79f8c372a693f60a033b461d81b025864a0ad051a06e4",
+            "08b852331c96ed983e497ebc6dee9b75e373d923b729194af8e72a051ea586f3538a6ebb1e80881a082fa2b24df9f566",
+        ],
+        p: [
+            "02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6",
+            "139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8",
+            "1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48",
+            "00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16",
+        ],
+    },
+    G2Vector {
+        u: [
+            "0313d9325081b415bfd4e5364efaef392ecf69b087496973b229303e1816d2080971470f7da112c4eb43053130b785e1",
+            "062f84cb21ed89406890c051a0e8b9cf6c575cf6e8e18ecf63ba86826b0ae02548d83b483b79e48512b82a6c0686df8f",
+            "1739123845406baa7be5c5dc74492051b6d42504de008c635f3535bb831d478a341420e67dcc7b46b2e8cba5379cca97",
+            "01897665d9cb5db16a27657760bbea7951f67ad68f8d55f7113f24ba6ddd82caef240a9bfa627972279974894701d975",
+        ],
+        p: [
+            "121982811d2491fde9ba7ed31ef9ca474f0e1501297f68c298e9f4c0028add35aea8bb83d53c08cfc007c1e005723cd0",
+            "190d119345b94fbd15497bcba94ecf7db2cbfd1e1fe7da034d26cbba169fb3968288b3fafb265f9ebd380512a71c3f2c",
+            "05571a0f8d3c08d094576981f4a3b8eda0a8e771fcdcc8ecceaf1356a6acf17574518acb506e435b639353c2e14827c8",
+            "0bb5e7572275c567462d91807de765611490205a941a5a6af3b1691bfe596c31225d3aabdf15faff860cb4ef17c7c3be",
+        ],
+    },
+    G2Vector {
+        u: [
+            "025820cefc7d06fd38de7d8e370e0da8a52498be9b53cba9927b2ef5c6de1e12e12f188bbc7bc923864883c57e49e253",
+            "034147b77ce337a52e5948f66db0bab47a8d038e712123bb381899b6ab5ad20f02805601e6104c29df18c254b8618c7b",
+            "0930315cae1f9a6017c3f0c8f2314baa130e1cf13f6532bff0a8a1790cd70af918088c3db94bda214e896e1543629795",
+            "10c4df2cacf67ea3cb3108b00d4cbd0b3968031ebc8eac4b1ebcefe84d6b715fde66bef0219951ece29d1facc8a520ef",
+        ],
+        p: [
+            "19a84dd7248a1066f737cc34502ee5555bd3c19f2ecdb3c7d9e24dc65d4e25e50d83f0f77105e955d78f4762d33c17da",
+            "0934aba516a52d8ae479939a91998299c76d39cc0c035cd18813bec433f587e2d7a4fef038260eef0cef4d02aae3eb91",
+            "14f81cd421617428bc3b9fe25afbb751d934a00493524bc4e065635b0555084dd54679df1536101b2c979c0152d09192",
+            "09bcccfa036b4847c9950780733633f13619994394c23ff0b32fa6b795844f4a0673e20282d07bc69641cee04f5e5662",
+        ],
+    },
+    G2Vector {
+        u: [
+            "190b513da3e66fc9a3587b78c76d1d132b1152174d0b83e3c1114066392579a45824c5fa17649ab89299ddd4bda54935",
+            "12ab625b0fe0ebd1367fe9fac57bb1168891846039b4216b9d94007b674de2d79126870e88aeef54b2ec717a887dcf39",
+            "0e6a42010cf435fb5bacc156a585e1ea3294cc81d0ceb81924d95040298380b164f702275892cedd81b62de3aba3f6b5",
+            "117d9a0defc57a33ed208428cb84e54c85a6840e7648480ae428838989d25d97a0af8e3255be62b25c2a85630d2dddd8",
+        ],
+        p: [
+            "01a6ba2f9a11fa5598b2d8ace0fbe0a0eacb65deceb476fbbcb64fd24557c2f4b18ecfc5663e54ae16a84f5ab7f62534",
+            "11fca2ff525572795a801eed17eb12785887c7b63fb77a42be46ce4a34131d71f7a73e95fee3f812aea3de78b4d01569",
+            "0b6798718c8aed24bc19cb27f866f1c9effcdbf92397ad6448b5c9db90d2b9da6cbabf48adc1adf59a1a28344e79d57e",
+            "03a47f8e6d1763ba0cad63d6114c0accbef65707825a511b251a660a9b3994249ae4e63fac38b23da0c398689ee2ab52",
+        ],
+    },
+];
+
+#[test]
+fn test_expand_message_xmd_sha256() {
+    // https://www.rfc-editor.org/rfc/rfc9380#appendix-K.1
+    let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    let mut output = [0u8; 32];
+
+    expand_message_xmd(b"", dst, XmdHash::Sha256, &mut output).unwrap();
+    assert_eq!(
+        hex::encode(output),
+        "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+    );
+
+    expand_message_xmd(b"abc", dst, XmdHash::Sha256, &mut output).unwrap();
+    assert_eq!(
+        hex::encode(output),
+        "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+    );
+}
+
+#[test]
+fn test_hash_to_curve_g1() {
+    for (msg, vector) in messages().iter().zip(G1_VECTORS.iter()) {
+        let u = hash_to_field_fp(msg, DST_G1_RO, XmdHash::Sha256).unwrap();
+        assert_eq!([fp_hex(&u[0].0), fp_hex(&u[1].0)], vector.u);
+
+        let point = hash_to_curve_g1(msg, DST_G1_RO, XmdHash::Sha256).unwrap();
+        assert_eq!(g1_hex(&point), vector.p);
+        assert!(point.equals(&amcl_utils::hash_to_curve_g1_with_dst(msg, DST_G1_RO)));
+    }
+}
+
+#[test]
+fn test_hash_to_curve_g2() {
+    for (msg, vector) in messages().iter().zip(G2_VECTORS.iter()) {
+        let u = hash_to_field_fp2(msg, DST_G2_RO, XmdHash::Sha256).unwrap();
+        let [u0_c0, u0_c1] = fp2_hex(&u[0].0);
+        let [u1_c0, u1_c1] = fp2_hex(&u[1].0);
+        assert_eq!([u0_c0, u0_c1, u1_c0, u1_c1], vector.u);
+
+        let point = hash_to_curve_g2(msg, DST_G2_RO, XmdHash::Sha256).unwrap();
+        assert_eq!(g2_hex(&point), vector.p);
+        assert!(point.equals(&amcl_utils::hash_to_curve_g2_with_dst(msg, DST_G2_RO)));
+    }
+}
+
+// map_to_curve of u is Q, both as amcl computes it and as sswu then iso_map.
+fn check_map_to_curve_g1(u: &Fp, q: [&str; 2]) {
+    assert_eq!(g1_hex(&map_to_curve_g1(u).0), q);
+    let (x, y) = sswu_g1(u);
+    assert_eq!(g1_hex(&iso_map_g1(&x, &y).0), q);
+}
+
+fn check_map_to_curve_g2(u: &Fp2, q: [&str; 4]) {
+    assert_eq!(g2_hex(&map_to_curve_g2(u).0), q);
+    let (x, y) = sswu_g2(u);
+    assert_eq!(g2_hex(&iso_map_g2(&x, &y).0), q);
+}
+
+#[test]
+fn test_map_to_curve_g1() {
+    // Q0 and Q1 for msg = ""
+    let u = hash_to_field_fp(b"", DST_G1_RO, XmdHash::Sha256).unwrap();
+    check_map_to_curve_g1(
+        &u[0],
+        [
+            "11a3cce7e1d90975990066b2f2643b9540fa40d6137780df4e753a8054d07580db3b7f1f03396333d4a359d1fe3766fe",
+            "0eeaf6d794e479e270da10fdaf768db4c96b650a74518fc67b04b03927754bac66f3ac720404f339ecdcc028afa091b7",
+        ],
+    );
+    check_map_to_curve_g1(
+        &u[1],
+        [
+            "160003aaf1632b13396dbad518effa00fff532f604de1a7fc2082ff4cb0afa2d63b2c32da1bef2bf6c5ca62dc6b72f9c",
+            "0d8bb2d14e20cf9f6036152ed386d79189415b6d015a20133acb4e019139b94e9c146aaad5817f866c95d609a361735e",
+        ],
+    );
+
+    // Q0 for msg = "abc"
+    let u = hash_to_field_fp(b"abc", DST_G1_RO, XmdHash::Sha256).unwrap();
+    check_map_to_curve_g1(
+        &u[0],
+        [
+            "125435adce8e1cbd1c803e7123f45392dc6e326d292499c2c45c5865985fd74fe8f042ecdeeec5ecac80680d04317d80",
+            "0e8828948c989126595ee30e4f7c931cbd6f4570735624fd25aef2fa41d3f79cfb4b4ee7b7e55a8ce013af2a5ba20bf2",
+        ],
+    );
+}
+
+#[test]
+fn test_map_to_curve_g2() {
+    // Q0 for msg = ""
+    let u = hash_to_field_fp2(b"", DST_G2_RO, XmdHash::Sha256).unwrap();
+    check_map_to_curve_g2(
+        &u[0],
+        [
+            "019ad3fc9c72425a998d7ab1ea0e646a1f6093444fc6965f1cad5a3195a7b1e099c050d57f45e3fa191cc6d75ed7458c",
+            "171c88b0b0efb5eb2b88913a9e74fe111a4f68867b59db252ce5868af4d1254bfab77ebde5d61cd1a86fb2fe4a5a1c1d",
+            "0ba10604e62bdd9eeeb4156652066167b72c8d743b050fb4c1016c31b505129374f76e03fa127d6a156213576910fef3",
+            "0eb22c7a543d3d376e9716a49b72e79a89c9bfe9feee8533ed931cbb5373dde1fbcd7411d8052e02693654f71e15410a",
+        ],
+    );
+}
+
+#[test]
+fn test_encode_to_curve_g1() {
+    // P for msg = ""
+    assert_eq!(
+        g1_hex(&encode_to_curve_g1(b"", DST_G1_NU, XmdHash::Sha256).unwrap()),
+        [
+            "184bb665c37ff561a89ec2122dd343f20e0f4cbcaec84e3c3052ea81d1834e192c426074b02ed3dca4e7676ce4ce48ba",
+            "04407b8d35af4dacc809927071fc0405218f1401a6d15af775810e4e460064bcc9468beeba82fdc751be70476c888bf3",
+        ]
+    );
+}
+
+#[test]
+fn test_encode_to_curve_g2() {
+    // P for msg = ""
+    assert_eq!(
+        g2_hex(&encode_to_curve_g2(b"", DST_G2_NU, XmdHash::Sha256).unwrap()),
+        [
+            "00e7f4568a82b4b7dc1f14c6aaa055edf51502319c723c4dc2688c7fe5944c213f510328082396515734b6612c4e7bb7",
+            "126b855e9e69b1f691f816e48ac6977664d24d99f8724868a184186469ddfd4617367e94527d4b74fc86413483afb35b",
+            "0caead0fd7b6176c01436833c79d305c78be307da5f6af6c133c47311def6ff1e0babf57a0fb5539fce7ee12407b0a42",
+            "1498aadcf7ae2b345243e281ae076df6de84455d766ab6fcdaad71fab60abb2e8b980a440043cd305db09d283c895e3d",
+        ]
+    );
+}
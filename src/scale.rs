@@ -0,0 +1,155 @@
+use super::amcl_utils::{G1_BYTES, G2_BYTES};
+use super::keys::PublicKey;
+use super::signature::Signature;
+use codec::{Decode, Encode, EncodeLike, Error, Input, MaxEncodedLen};
+use scale_info::build::Fields;
+use scale_info::{Path, Type, TypeInfo};
+
+// PublicKeys and Signatures are SCALE encoded as their fixed size compressed bytes, without a
+// length prefix, which is the same encoding as `[u8; G1_BYTES]` and `[u8; G2_BYTES]`.
+
+impl Encode for PublicKey {
+    fn size_hint(&self) -> usize {
+        G1_BYTES
+    }
+
+    fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
+        f(&self.as_bytes())
+    }
+
+    fn encoded_size(&self) -> usize {
+        G1_BYTES
+    }
+}
+
+impl EncodeLike for PublicKey {}
+
+// Decoding runs the same validation as `PublicKey::from_bytes()`.
+impl Decode for PublicKey {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let mut bytes = [0u8; G1_BYTES];
+        input.read(&mut bytes)?;
+        PublicKey::from_bytes(&bytes).map_err(|_| Error::from("Invalid PublicKey"))
+    }
+
+    fn encoded_fixed_size() -> Option<usize> {
+        Some(G1_BYTES)
+    }
+}
+
+impl MaxEncodedLen for PublicKey {
+    fn max_encoded_len() -> usize {
+        G1_BYTES
+    }
+}
+
+impl TypeInfo for PublicKey {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type::builder()
+            .path(Path::new("PublicKey", module_path!()))
+            .composite(Fields::unnamed().field(|f| f.ty::<[u8; G1_BYTES]>().type_name("[u8; 48]")))
+    }
+}
+
+impl Encode for Signature {
+    fn size_hint(&self) -> usize {
+        G2_BYTES
+    }
+
+    fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
+        f(&self.as_bytes())
+    }
+
+    fn encoded_size(&self) -> usize {
+        G2_BYTES
+    }
+}
+
+impl EncodeLike for Signature {}
+
+// Decoding runs the same validation as `Signature::from_bytes()`.
+impl Decode for Signature {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let mut bytes = [0u8; G2_BYTES];
+        input.read(&mut bytes)?;
+        Signature::from_bytes(&bytes).map_err(|_| Error::from("Invalid Signature"))
+    }
+
+    fn encoded_fixed_size() -> Option<usize> {
+        Some(G2_BYTES)
+    }
+}
+
+impl MaxEncodedLen for Signature {
+    fn max_encoded_len() -> usize {
+        G2_BYTES
+    }
+}
+
+impl TypeInfo for Signature {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type::builder()
+            .path(Path::new("Signature", module_path!()))
+            .composite(Fields::unnamed().field(|f| f.ty::<[u8; G2_BYTES]>().type_name("[u8; 96]")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::keys::Keypair;
+    use super::*;
+    use codec::DecodeAll;
+
+    #[test]
+    fn test_scale_public_key_round_trip() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let encoded = keypair.pk.encode();
+
+        // Same encoding as the fixed size compressed bytes
+        assert_eq!(encoded, keypair.pk.as_bytes().encode());
+        assert_eq!(encoded.len(), PublicKey::max_encoded_len());
+
+        let decoded = PublicKey::decode_all(&mut &encoded[..]).unwrap();
+        assert_eq!(decoded, keypair.pk);
+    }
+
+    #[test]
+    fn test_scale_signature_round_trip() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signature = Signature::new(&[42u8; 32], &keypair.sk);
+        let encoded = signature.encode();
+
+        assert_eq!(encoded, signature.as_bytes().encode());
+        assert_eq!(encoded.len(), Signature::max_encoded_len());
+
+        let decoded = Signature::decode_all(&mut &encoded[..]).unwrap();
+        assert_eq!(decoded, signature);
+    }
+
+    #[test]
+    fn test_scale_decode_invalid() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signature = Signature::new(&[42u8; 32], &keypair.sk);
+
+        // Truncated input
+        let encoded = keypair.pk.encode();
+        assert!(PublicKey::decode(&mut &encoded[..G1_BYTES - 1]).is_err());
+        let encoded = signature.encode();
+        assert!(Signature::decode(&mut &encoded[..G2_BYTES - 1]).is_err());
+
+        // Invalid points
+        assert!(PublicKey::decode(&mut &[0u8; G1_BYTES][..]).is_err());
+        assert!(Signature::decode(&mut &[0u8; G2_BYTES][..]).is_err());
+
+        // Infinity PublicKey
+        let mut infinity = [0u8; G1_BYTES];
+        infinity[0] = 0xc0;
+        assert!(PublicKey::decode(&mut &infinity[..]).is_err());
+    }
+}
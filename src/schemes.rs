@@ -0,0 +1,290 @@
+//! The signature schemes of the BLS draft, layered over the core operations.
+//!
+//! CoreSign, CoreVerify and CoreAggregateVerify take the ciphersuite's domain separation tag.
+//! The Basic, message augmentation and Proof of Possession schemes only differ in that tag and
+//! in how messages are bound to their signers, so a new ciphersuite is a new `Scheme`.
+//! https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-3
+
+use super::aggregates::AggregateSignature;
+use super::amcl_utils::{hash_to_curve_g2_with_dst, GroupG2, DST_G2};
+use super::errors::VerifyError;
+use super::hash_to_curve::MessageHasher;
+use super::keys::{PublicKey, SecretKey};
+use super::points::G2Point;
+use super::signature::Signature;
+
+/// Domain separation tag of the Basic scheme.
+pub const DST_BASIC: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+/// Domain separation tag of the message augmentation scheme.
+pub const DST_AUG: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_AUG_";
+/// Domain separation tag of the Proof of Possession scheme, as used by `Signature::new()`.
+pub const DST_POP: &[u8] = DST_G2;
+/// Domain separation tag of proofs of possession.
+pub const DST_POP_PROOF: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// CoreSign
+///
+/// Signs a message with the given domain separation tag.
+/// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-2.6
+pub fn core_sign(sk: &SecretKey, msg: &[u8], dst: &[u8]) -> Signature {
+    sk.sign_hashed(&G2Point(hash_to_curve_g2_with_dst(msg, dst)))
+}
+
+/// CoreVerify
+///
+/// Verifies a Signature against a PublicKey and a message hashed with the given domain
+/// separation tag.
+/// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-2.7
+pub fn core_verify(
+    pk: &PublicKey,
+    msg: &[u8],
+    signature: &Signature,
+    dst: &[u8],
+) -> Result<(), VerifyError> {
+    signature.verify_hash_point(hash_to_curve_g2_with_dst(msg, dst), pk)
+}
+
+/// CoreAggregateVerify
+///
+/// Verifies an AggregateSignature against PublicKeys and their messages, hashed with the
+/// given domain separation tag.
+/// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-2.9
+pub fn core_aggregate_verify(
+    public_keys: &[&PublicKey],
+    msgs: &[&[u8]],
+    signature: &AggregateSignature,
+    dst: &[u8],
+) -> Result<(), VerifyError> {
+    check_lengths(public_keys, msgs)?;
+    signature.verify_hash_points(
+        public_keys.iter().zip(msgs).map(|(pk, msg)| (*pk, hash_to_curve_g2_with_dst(msg, dst))),
+    )
+}
+
+// Require same number of messages as PublicKeys and >=1 PublicKeys.
+fn check_lengths(public_keys: &[&PublicKey], msgs: &[&[u8]]) -> Result<(), VerifyError> {
+    if msgs.len() != public_keys.len() {
+        return Err(VerifyError::LengthMismatch);
+    }
+    if public_keys.is_empty() {
+        return Err(VerifyError::EmptyInput);
+    }
+    Ok(())
+}
+
+/// A BLS signature scheme: Sign, Verify and AggregateVerify for a ciphersuite.
+///
+/// The provided methods are CoreSign, CoreVerify and CoreAggregateVerify with the scheme's
+/// domain separation tag, schemes override them to bind messages to their signers.
+pub trait Scheme {
+    /// The ciphersuite's domain separation tag.
+    const DST: &'static [u8];
+
+    /// Sign
+    fn sign(sk: &SecretKey, msg: &[u8]) -> Signature {
+        core_sign(sk, msg, Self::DST)
+    }
+
+    /// Verify
+    fn verify(pk: &PublicKey, msg: &[u8], signature: &Signature) -> bool {
+        core_verify(pk, msg, signature, Self::DST).is_ok()
+    }
+
+    /// AggregateVerify
+    fn aggregate_verify(
+        public_keys: &[&PublicKey],
+        msgs: &[&[u8]],
+        signature: &AggregateSignature,
+    ) -> bool {
+        core_aggregate_verify(public_keys, msgs, signature, Self::DST).is_ok()
+    }
+}
+
+/// The Basic scheme, where aggregated messages must be distinct.
+/// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-3.1
+pub struct Basic;
+
+impl Scheme for Basic {
+    const DST: &'static [u8] = DST_BASIC;
+
+    fn aggregate_verify(
+        public_keys: &[&PublicKey],
+        msgs: &[&[u8]],
+        signature: &AggregateSignature,
+    ) -> bool {
+        // Messages must all be distinct
+        let distinct = msgs.iter().enumerate().all(|(i, msg)| !msgs[..i].contains(msg));
+        distinct && core_aggregate_verify(public_keys, msgs, signature, Self::DST).is_ok()
+    }
+}
+
+/// The message augmentation scheme, where each message is prefixed with its signer's
+/// PublicKey.
+/// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-3.2
+pub struct MessageAugmentation;
+
+impl MessageAugmentation {
+    // hash_to_curve of PK || msg, without concatenating them.
+    fn hash_augmented(pk: &PublicKey, msg: &[u8]) -> GroupG2 {
+        MessageHasher::new(Self::DST).update(&pk.as_bytes()).update(msg).finalize_to_g2()
+    }
+}
+
+impl Scheme for MessageAugmentation {
+    const DST: &'static [u8] = DST_AUG;
+
+    fn sign(sk: &SecretKey, msg: &[u8]) -> Signature {
+        let pk = PublicKey::from_secret_key(sk);
+        sk.sign_hashed(&G2Point(Self::hash_augmented(&pk, msg)))
+    }
+
+    fn verify(pk: &PublicKey, msg: &[u8], signature: &Signature) -> bool {
+        signature.verify_hash_point(Self::hash_augmented(pk, msg), pk).is_ok()
+    }
+
+    fn aggregate_verify(
+        public_keys: &[&PublicKey],
+        msgs: &[&[u8]],
+        signature: &AggregateSignature,
+    ) -> bool {
+        check_lengths(public_keys, msgs).is_ok()
+            && signature
+                .verify_hash_points(
+                    public_keys
+                        .iter()
+                        .zip(msgs)
+                        .map(|(pk, msg)| (*pk, Self::hash_augmented(pk, msg))),
+                )
+                .is_ok()
+    }
+}
+
+/// The Proof of Possession scheme, as used by Ethereum and the methods of `Signature` and
+/// `AggregateSignature`.
+/// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-3.3
+pub struct ProofOfPossession;
+
+impl ProofOfPossession {
+    /// PopProve
+    ///
+    /// Proves possession of the SecretKey, by signing its PublicKey.
+    pub fn pop_prove(sk: &SecretKey) -> Signature {
+        core_sign(sk, &PublicKey::from_secret_key(sk).as_bytes(), DST_POP_PROOF)
+    }
+
+    /// PopVerify
+    ///
+    /// Verifies a proof of possession of the PublicKey, which must also be valid.
+    pub fn pop_verify(pk: &PublicKey, proof: &Signature) -> bool {
+        pk.key_validate() && core_verify(pk, &pk.as_bytes(), proof, DST_POP_PROOF).is_ok()
+    }
+
+    /// FastAggregateVerify
+    ///
+    /// PublicKeys must all be verified via `pop_verify()` before running this function.
+    pub fn fast_aggregate_verify(
+        public_keys: &[&PublicKey],
+        msg: &[u8],
+        signature: &AggregateSignature,
+    ) -> bool {
+        signature.fast_aggregate_verify(msg, public_keys)
+    }
+}
+
+impl Scheme for ProofOfPossession {
+    const DST: &'static [u8] = DST_POP;
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::keys::Keypair;
+    use super::*;
+
+    fn keypairs(n: usize) -> Vec<Keypair> {
+        (0..n).map(|_| Keypair::random(&mut rand::thread_rng())).collect()
+    }
+
+    fn check_scheme<S: Scheme>() {
+        let keypairs = keypairs(3);
+        let msgs: [&[u8]; 3] = [b"cats", b"dogs", b"mice"];
+
+        let signatures: Vec<Signature> =
+            keypairs.iter().zip(&msgs).map(|(keypair, msg)| S::sign(&keypair.sk, msg)).collect();
+        for ((keypair, msg), signature) in keypairs.iter().zip(&msgs).zip(&signatures) {
+            assert!(S::verify(&keypair.pk, msg, signature));
+            assert!(!S::verify(&keypair.pk, b"other", signature));
+        }
+
+        let public_keys: Vec<&PublicKey> = keypairs.iter().map(|keypair| &keypair.pk).collect();
+        let signature_refs: Vec<&Signature> = signatures.iter().collect();
+        let aggregate = AggregateSignature::aggregate(&signature_refs);
+        assert!(S::aggregate_verify(&public_keys, &msgs, &aggregate));
+        assert!(!S::aggregate_verify(&public_keys[..2], &msgs[..2], &aggregate));
+        assert!(!S::aggregate_verify(&public_keys, &msgs[..2], &aggregate));
+        assert!(!S::aggregate_verify(&[], &[], &aggregate));
+    }
+
+    #[test]
+    fn test_schemes() {
+        check_scheme::<Basic>();
+        check_scheme::<MessageAugmentation>();
+        check_scheme::<ProofOfPossession>();
+    }
+
+    #[test]
+    fn test_schemes_are_domain_separated() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let msg = b"cats";
+        let basic = Basic::sign(&keypair.sk, msg);
+        let aug = MessageAugmentation::sign(&keypair.sk, msg);
+        let pop = ProofOfPossession::sign(&keypair.sk, msg);
+
+        assert_eq!(basic, Signature::new_with_dst(msg, &keypair.sk, DST_BASIC));
+        assert_eq!(pop, Signature::new(msg, &keypair.sk));
+        assert!(!ProofOfPossession::verify(&keypair.pk, msg, &basic));
+        assert!(!Basic::verify(&keypair.pk, msg, &aug));
+        assert!(!MessageAugmentation::verify(&keypair.pk, msg, &pop));
+    }
+
+    #[test]
+    fn test_basic_rejects_duplicate_messages() {
+        let keypairs = keypairs(2);
+        let msg: &[u8] = b"cats";
+        let signatures: Vec<Signature> =
+            keypairs.iter().map(|keypair| Basic::sign(&keypair.sk, msg)).collect();
+        let aggregate = AggregateSignature::aggregate(&[&signatures[0], &signatures[1]]);
+        let public_keys = [&keypairs[0].pk, &keypairs[1].pk];
+
+        assert!(!Basic::aggregate_verify(&public_keys, &[msg, msg], &aggregate));
+        assert!(core_aggregate_verify(&public_keys, &[msg, msg], &aggregate, DST_BASIC).is_ok());
+        assert!(MessageAugmentation::aggregate_verify(
+            &public_keys,
+            &[msg, msg],
+            &AggregateSignature::aggregate(&[
+                &MessageAugmentation::sign(&keypairs[0].sk, msg),
+                &MessageAugmentation::sign(&keypairs[1].sk, msg),
+            ]),
+        ));
+    }
+
+    #[test]
+    fn test_proof_of_possession() {
+        let keypairs = keypairs(3);
+        let proofs: Vec<Signature> =
+            keypairs.iter().map(|keypair| ProofOfPossession::pop_prove(&keypair.sk)).collect();
+        assert!(ProofOfPossession::pop_verify(&keypairs[0].pk, &proofs[0]));
+        assert!(!ProofOfPossession::pop_verify(&keypairs[1].pk, &proofs[0]));
+
+        let msg = b"cats";
+        let signatures: Vec<Signature> =
+            keypairs.iter().map(|keypair| ProofOfPossession::sign(&keypair.sk, msg)).collect();
+        let aggregate = AggregateSignature::aggregate(&signatures.iter().collect::<Vec<_>>());
+        let public_keys: Vec<&PublicKey> = keypairs.iter().map(|keypair| &keypair.pk).collect();
+        assert!(ProofOfPossession::fast_aggregate_verify(&public_keys, msg, &aggregate));
+        // A proof of possession is not a signature over the PublicKey
+        let pk_bytes = keypairs[0].pk.as_bytes();
+        assert!(!ProofOfPossession::verify(&keypairs[0].pk, &pk_bytes, &proofs[0]));
+    }
+}
@@ -0,0 +1,196 @@
+#[cfg(feature = "serde-secret")]
+extern crate zeroize;
+
+#[cfg(feature = "serde-secret")]
+use self::zeroize::Zeroize;
+use super::aggregates::AggregateSignature;
+#[cfg(feature = "serde-secret")]
+use super::amcl_utils::SECRET_KEY_BYTES;
+use super::keys::PublicKey;
+#[cfg(feature = "serde-secret")]
+use super::keys::SecretKey;
+use super::signature::Signature;
+#[cfg(all(feature = "serde-secret", not(feature = "std")))]
+use core::fmt;
+use serde::de::Error;
+#[cfg(feature = "serde-secret")]
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_bytes::{ByteBuf, Bytes};
+#[cfg(all(feature = "serde-secret", feature = "std"))]
+use std::fmt;
+
+// Implements Serialize and Deserialize using the compressed bytes of the point.
+//
+// Points are written with `serialize_bytes()` so binary formats (e.g. bincode, CBOR) store them as
+// a single byte string rather than a sequence of individually encoded u8s. Deserialization accepts
+// both byte strings and sequences of u8s and runs the same validation as `from_bytes()`.
+macro_rules! impl_serde {
+    ($type: ty) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                Bytes::new(&self.as_bytes()).serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let bytes = ByteBuf::deserialize(deserializer)?;
+                <$type>::from_bytes(&bytes).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+impl_serde!(PublicKey);
+impl_serde!(Signature);
+impl_serde!(AggregateSignature);
+
+// SecretKeys are only serializable with the `serde-secret` feature, so that default builds cannot
+// write a key into e.g. a log line or API response by accident. Copies of the key bytes made here
+// are zeroized.
+#[cfg(feature = "serde-secret")]
+impl Serialize for SecretKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = self.as_bytes();
+        let result = serializer.serialize_bytes(&bytes);
+        bytes.zeroize();
+        result
+    }
+}
+
+#[cfg(feature = "serde-secret")]
+impl<'de> Deserialize<'de> for SecretKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(SecretKeyVisitor)
+    }
+}
+
+// Reads the key into a fixed size buffer which is zeroized, rather than a ByteBuf which may
+// leave copies behind as it grows.
+#[cfg(feature = "serde-secret")]
+struct SecretKeyVisitor;
+
+#[cfg(feature = "serde-secret")]
+impl<'de> Visitor<'de> for SecretKeyVisitor {
+    type Value = SecretKey;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bytes of a secret key", SECRET_KEY_BYTES)
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<SecretKey, E> {
+        SecretKey::from_bytes(v).map_err(|_| E::custom("invalid secret key"))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<SecretKey, A::Error> {
+        let mut bytes = [0u8; SECRET_KEY_BYTES];
+        let mut len = 0;
+        let result = loop {
+            match seq.next_element::<u8>() {
+                Ok(Some(byte)) if len < SECRET_KEY_BYTES => {
+                    bytes[len] = byte;
+                    len += 1;
+                }
+                Ok(Some(_)) => break Err(A::Error::custom("invalid secret key")),
+                Ok(None) => {
+                    break SecretKey::from_bytes(&bytes[..len])
+                        .map_err(|_| A::Error::custom("invalid secret key"))
+                }
+                Err(error) => break Err(error),
+            }
+        };
+        bytes.zeroize();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_test;
+
+    use self::serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+    use super::*;
+
+    const PUBLIC_KEY: [u8; 48] = [
+        0xa4, 0x91, 0xd1, 0xb0, 0xec, 0xd9, 0xbb, 0x91, 0x79, 0x89, 0xf0, 0xe7, 0x4f, 0x0d, 0xea,
+        0x04, 0x22, 0xea, 0xc4, 0xa8, 0x73, 0xe5, 0xe2, 0x64, 0x4f, 0x36, 0x8d, 0xff, 0xb9, 0xa6,
+        0xe2, 0x0f, 0xd6, 0xe1, 0x0c, 0x1b, 0x77, 0x65, 0x4d, 0x06, 0x7c, 0x06, 0x18, 0xf6, 0xe5,
+        0xa7, 0xf7, 0x9a,
+    ];
+
+    const SIGNATURE: [u8; 96] = [
+        0xa6, 0x66, 0xd3, 0x1d, 0x7e, 0x65, 0x61, 0x37, 0x16, 0x44, 0xeb, 0x9c, 0xa7, 0xdb, 0xcb,
+        0x87, 0x25, 0x7d, 0x8f, 0xd8, 0x4a, 0x09, 0xe3, 0x8a, 0x7a, 0x49, 0x1c, 0xe0, 0xbb, 0xac,
+        0x64, 0xa3, 0x24, 0xaa, 0x26, 0x38, 0x5a, 0xeb, 0xc9, 0x9f, 0x47, 0x43, 0x29, 0x70, 0x39,
+        0x9a, 0x2e, 0xcb, 0x0d, 0xef, 0x2d, 0x4b, 0xe3, 0x59, 0x64, 0x0e, 0x6d, 0xae, 0x64, 0x38,
+        0x11, 0x9c, 0xbd, 0xc4, 0xf1, 0x8e, 0x5e, 0x44, 0x96, 0xc6, 0x8a, 0x97, 0x94, 0x73, 0xa7,
+        0x2b, 0x72, 0xd3, 0xba, 0xdf, 0x98, 0x46, 0x44, 0x12, 0xe9, 0xd8, 0xf8, 0xd2, 0xea, 0x9b,
+        0x31, 0x95, 0x3b, 0xb2, 0x48, 0x99,
+    ];
+
+    #[test]
+    fn test_serde_bytes() {
+        let public_key = PublicKey::from_bytes(&PUBLIC_KEY).unwrap();
+        assert_tokens(&public_key, &[Token::Bytes(&PUBLIC_KEY)]);
+        assert_de_tokens(&public_key, &[Token::ByteBuf(&PUBLIC_KEY)]);
+
+        let signature = Signature::from_bytes(&SIGNATURE).unwrap();
+        assert_tokens(&signature, &[Token::Bytes(&SIGNATURE)]);
+        assert_de_tokens(&signature, &[Token::ByteBuf(&SIGNATURE)]);
+
+        let aggregate_signature = AggregateSignature::from_bytes(&SIGNATURE).unwrap();
+        assert_tokens(&aggregate_signature, &[Token::Bytes(&SIGNATURE)]);
+    }
+
+    #[test]
+    fn test_serde_deserialize_seq() {
+        // Formats without byte strings (e.g. JSON) represent the bytes as a sequence of u8s
+        let mut tokens = vec![Token::Seq { len: Some(48) }];
+        tokens.extend(PUBLIC_KEY.iter().map(|byte| Token::U8(*byte)));
+        tokens.push(Token::SeqEnd);
+
+        assert_de_tokens(&PublicKey::from_bytes(&PUBLIC_KEY).unwrap(), &tokens);
+    }
+
+    #[test]
+    fn test_serde_deserialize_invalid() {
+        assert_de_tokens_error::<PublicKey>(
+            &[Token::Bytes(&PUBLIC_KEY[..47])],
+            "invalid length: expected 48 bytes, got 47",
+        );
+        assert_de_tokens_error::<Signature>(
+            &[Token::Bytes(&[0u8; 96])],
+            "invalid compression flag",
+        );
+    }
+
+    #[cfg(feature = "serde-secret")]
+    const SECRET_KEY: [u8; 32] = [
+        78, 252, 122, 126, 32, 0, 75, 89, 252, 31, 42, 130, 254, 88, 6, 90, 138, 202, 135, 194,
+        233, 117, 181, 75, 96, 238, 79, 100, 237, 59, 140, 111,
+    ];
+
+    #[test]
+    #[cfg(feature = "serde-secret")]
+    fn test_serde_secret_key() {
+        let secret_key = SecretKey::from_bytes(&SECRET_KEY).unwrap();
+        assert_tokens(&secret_key, &[Token::Bytes(&SECRET_KEY)]);
+        assert_de_tokens(&secret_key, &[Token::ByteBuf(&SECRET_KEY)]);
+
+        let mut tokens = vec![Token::Seq { len: Some(32) }];
+        tokens.extend(SECRET_KEY.iter().map(|byte| Token::U8(*byte)));
+        tokens.push(Token::SeqEnd);
+        assert_de_tokens(&secret_key, &tokens);
+    }
+
+    #[test]
+    #[cfg(feature = "serde-secret")]
+    fn test_serde_secret_key_invalid() {
+        assert_de_tokens_error::<SecretKey>(
+            &[Token::Bytes(&SECRET_KEY[..31])],
+            "invalid secret key",
+        );
+        assert_de_tokens_error::<SecretKey>(&[Token::Bytes(&[0xff; 32])], "invalid secret key");
+    }
+}
@@ -1,22 +1,92 @@
 extern crate amcl;
 
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use core::cmp::Ordering;
+#[cfg(not(feature = "std"))]
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
+use std::cmp::Ordering;
+#[cfg(feature = "std")]
+use std::hash::{Hash, Hasher};
+
 use super::amcl_utils::{
-    self, ate2_evaluation, compress_g2, decompress_g2, g2mul, hash_to_curve_g2, subgroup_check_g2,
-    AmclError, GroupG2, G2_BYTES,
+    ate2_evaluation, compress_g2, decompress_g2, decompress_g2_unchecked,
+    deserialize_uncompressed_g2, g2_from_coordinates, g2_from_eip2537, g2_to_coordinates,
+    g2_to_eip2537, g2mul, hash_to_curve_g2, on_curve_check_g2, serialize_uncompressed_g2,
+    subgroup_check_g2, AmclError, GroupG2, DST_G2, EIP2537_G2_BYTES, G2_BYTES,
+    NEGATIVE_GENERATORG1,
 };
-use super::keys::{PublicKey, SecretKey};
+use super::errors::{DecodeError, VerifyError};
+use super::hash_to_curve::{self, HashToCurveHasher};
+use super::keys::{PublicKey, SecretKey, ALLOW_INFINITY_PUBLIC_KEY};
+use super::points::{G2Point, Scalar};
+use super::schemes;
+use super::strictness::{self, Strictness};
+#[cfg(feature = "parallel")]
+use super::thread_pool;
+use rand_core::{CryptoRng, RngCore};
 
 #[derive(Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct Signature {
+    #[cfg(feature = "unstable-raw")]
     pub point: GroupG2,
+    #[cfg(not(feature = "unstable-raw"))]
+    pub(crate) point: GroupG2,
 }
 
 impl Signature {
     /// Instantiate a new Signature from a message and a SecretKey.
     pub fn new(msg: &[u8], sk: &SecretKey) -> Self {
-        let hash_point = hash_to_curve_g2(msg);
+        schemes::core_sign(sk, msg, DST_G2)
+    }
+
+    /// Instantiate a new Signature from a message and a SecretKey, hashing the message
+    /// with the given domain separation tag rather than the Proof of Possession ciphersuite.
+    pub fn new_with_dst(msg: &[u8], sk: &SecretKey, dst: &[u8]) -> Self {
+        schemes::core_sign(sk, msg, dst)
+    }
+
+    /// Instantiate a new Signature from a message and a SecretKey, hashing the message with
+    /// the given domain separation tag and expand_message_xmd hash function.
+    ///
+    /// Errors if the hash function is not supported, see `hash_to_curve::hash_to_field_fp2()`.
+    pub fn new_with_hash<H: HashToCurveHasher>(
+        msg: &[u8],
+        sk: &SecretKey,
+        dst: &[u8],
+        hash: H,
+    ) -> Result<Self, AmclError> {
+        let hash_point = hash_to_curve::hash_to_curve_g2(msg, dst, hash)?;
         let sig = g2mul(&hash_point, sk.as_raw());
+        Ok(Self { point: sig })
+    }
+
+    /// Instantiate a new Signature from a message and a SecretKey, blinding the SecretKey as a
+    /// side-channel countermeasure.
+    ///
+    /// The SecretKey is split into a fresh random share k and sk - k, which are multiplied by
+    /// the message point separately and summed. The Signature is the same as `new()`.
+    pub fn new_blinded<R: RngCore + CryptoRng + ?Sized>(
+        msg: &[u8],
+        sk: &SecretKey,
+        rng: &mut R,
+    ) -> Self {
+        let hash_point = hash_to_curve_g2(msg);
+        let mut x = Scalar(sk.as_raw().clone());
+        let mut k = Scalar::random(rng);
+        let mut neg_k = k.neg();
+        let mut blinded = x.add(&neg_k);
+        let mut sig = g2mul(&hash_point, &k.0);
+        sig.add(&g2mul(&hash_point, &blinded.0));
+
+        // Together k and sk - k give the SecretKey
+        x.zeroize();
+        k.zeroize();
+        neg_k.zeroize();
+        blinded.zeroize();
         Self { point: sig }
     }
 
@@ -25,30 +95,228 @@ impl Signature {
     /// Verifies the Signature against a PublicKey.
     /// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-02#section-3.3
     pub fn verify(&self, msg: &[u8], pk: &PublicKey) -> bool {
+        self.try_verify(msg, pk).is_ok()
+    }
+
+    /// CoreVerify - with the reason for failure
+    ///
+    /// Verifies the Signature against a PublicKey, returning why verification failed.
+    pub fn try_verify(&self, msg: &[u8], pk: &PublicKey) -> Result<(), VerifyError> {
+        schemes::core_verify(pk, msg, self, DST_G2)
+    }
+
+    /// CoreVerify - custom domain separation tag
+    ///
+    /// Verifies the Signature against a PublicKey where the message was hashed
+    /// with the given domain separation tag.
+    pub fn verify_with_dst(&self, msg: &[u8], pk: &PublicKey, dst: &[u8]) -> bool {
+        schemes::core_verify(pk, msg, self, dst).is_ok()
+    }
+
+    /// CoreVerify - custom domain separation tag and hash function
+    ///
+    /// Verifies the Signature against a PublicKey where the message was hashed with the given
+    /// domain separation tag and expand_message_xmd hash function.
+    ///
+    /// Returns false if the hash function is not supported.
+    pub fn verify_with_hash<H: HashToCurveHasher>(
+        &self,
+        msg: &[u8],
+        pk: &PublicKey,
+        dst: &[u8],
+        hash: H,
+    ) -> bool {
+        match hash_to_curve::hash_to_curve_g2(msg, dst, hash) {
+            Ok(hash_point) => self.verify_hash_point(hash_point, pk).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    // Verifies the Signature against a PublicKey and an already hashed message.
+    pub(crate) fn verify_hash_point(
+        &self,
+        mut msg_hash_point: GroupG2,
+        pk: &PublicKey,
+    ) -> Result<(), VerifyError> {
         // Signature Subgroup checks
-        if !subgroup_check_g2(&self.point) {
-            return false;
+        if !self.is_in_subgroup() {
+            return Err(VerifyError::SignatureNotInSubgroup);
+        }
+
+        // PublicKey must not be infinity
+        if !ALLOW_INFINITY_PUBLIC_KEY && pk.point.is_infinity() {
+            return Err(VerifyError::InvalidPublicKey);
         }
 
-        let mut msg_hash_point = hash_to_curve_g2(msg);
         msg_hash_point.affine();
 
         // Faster ate2 evaualtion checks e(S, -G1) * e(H, PK) == 1
-        let mut generator_g1_negative = amcl_utils::GroupG1::generator();
-        generator_g1_negative.neg();
-        ate2_evaluation(&self.point, &generator_g1_negative, &msg_hash_point, &pk.point)
+        if !ate2_evaluation(&self.point, &NEGATIVE_GENERATORG1, &msg_hash_point, &pk.point) {
+            return Err(VerifyError::InvalidSignature);
+        }
+        Ok(())
     }
 
     /// Instantiate a Signature from compressed bytes.
-    pub fn from_bytes(bytes: &[u8]) -> Result<Signature, AmclError> {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Signature, DecodeError> {
         let point = decompress_g2(bytes)?;
         Ok(Self { point })
     }
 
+    /// Instantiate a Signature from compressed bytes without the subgroup check.
+    ///
+    /// The point is on the curve, `is_in_subgroup()` MUST be checked before the Signature is
+    /// aggregated. Verification checks the subgroup itself.
+    pub fn from_bytes_unchecked(bytes: &[u8]) -> Result<Signature, DecodeError> {
+        let point = decompress_g2_unchecked(bytes)?;
+        Ok(Self { point })
+    }
+
+    /// Instantiate a Signature from bytes under the given encoding rules.
+    ///
+    /// The Signature is validated as in `from_bytes()`, the rules only relax how it may be
+    /// encoded.
+    pub fn from_bytes_with_strictness(
+        bytes: &[u8],
+        strictness: &Strictness,
+    ) -> Result<Signature, DecodeError> {
+        let point = strictness::decode_g2(bytes, strictness)?;
+        Ok(Self { point })
+    }
+
+    /// Instantiate many Signatures from compressed bytes.
+    ///
+    /// With the `parallel` feature the Signatures are decompressed across threads.
+    #[cfg(feature = "alloc")]
+    pub fn from_bytes_batch(bytes: &[&[u8]]) -> Result<Vec<Signature>, DecodeError> {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            thread_pool::install(|| bytes.par_iter().map(|bytes| Self::from_bytes(bytes)).collect())
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            bytes.iter().map(|bytes| Self::from_bytes(bytes)).collect()
+        }
+    }
+
+    /// Subgroup check of many Signatures, e.g. before they are aggregated.
+    ///
+    /// With the `parallel` feature the Signatures are checked across threads.
+    pub fn subgroup_check_batch(signatures: &[Signature]) -> bool {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            thread_pool::install(|| {
+                signatures.par_iter().all(|signature| signature.is_in_subgroup())
+            })
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            signatures.iter().all(|signature| signature.is_in_subgroup())
+        }
+    }
+
+    /// Returns true if the Signature is in the G2 subgroup.
+    ///
+    /// Uses the endomorphism based check, which is faster than multiplying by the group order.
+    pub fn is_in_subgroup(&self) -> bool {
+        subgroup_check_g2(&self.point)
+    }
+
+    /// Returns true if the Signature is on the curve E2, which is not a subgroup check.
+    ///
+    /// Every Signature decoded by this crate is on the curve, including those of
+    /// `from_bytes_unchecked()`.
+    pub fn is_on_curve(&self) -> bool {
+        on_curve_check_g2(&self.point)
+    }
+
     /// Compress the Signature as bytes.
     pub fn as_bytes(&self) -> [u8; G2_BYTES] {
         compress_g2(&self.point)
     }
+
+    /// Instantiate a Signature from uncompressed (x, y) bytes.
+    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Signature, DecodeError> {
+        let point = deserialize_uncompressed_g2(bytes)?;
+        if !subgroup_check_g2(&point) {
+            return Err(DecodeError::NotInSubgroup);
+        }
+        Ok(Self { point })
+    }
+
+    /// Instantiate a Signature from big-endian affine coordinates (x, y), each encoded as
+    /// (c1, c0). (0, 0) is the point at infinity.
+    pub fn from_coordinates(
+        x: &[u8; G2_BYTES],
+        y: &[u8; G2_BYTES],
+    ) -> Result<Signature, DecodeError> {
+        let point = g2_from_coordinates(x, y)?;
+        if !subgroup_check_g2(&point) {
+            return Err(DecodeError::NotInSubgroup);
+        }
+        Ok(Self { point })
+    }
+
+    /// Export the Signature as big-endian affine coordinates (x, y), each encoded as (c1, c0).
+    pub fn as_coordinates(&self) -> ([u8; G2_BYTES], [u8; G2_BYTES]) {
+        g2_to_coordinates(&self.point)
+    }
+
+    /// Instantiate a Signature from the 256 byte encoding used by the EIP-2537 precompiles.
+    ///
+    /// The point must be in G2, the point at infinity is accepted.
+    pub fn from_eip2537_bytes(bytes: &[u8]) -> Result<Signature, DecodeError> {
+        let point = g2_from_eip2537(bytes)?;
+        if !subgroup_check_g2(&point) {
+            return Err(DecodeError::NotInSubgroup);
+        }
+        Ok(Self { point })
+    }
+
+    /// Export the Signature to the 256 byte encoding used by the EIP-2537 precompiles.
+    pub fn to_eip2537_bytes(&self) -> [u8; EIP2537_G2_BYTES] {
+        g2_to_eip2537(&self.point)
+    }
+
+    /// Export the Signature to uncompressed (x, y) bytes.
+    pub fn to_uncompressed_bytes(&self) -> [u8; G2_BYTES * 2] {
+        serialize_uncompressed_g2(&self.point)
+    }
+
+    /// Advanced: instantiate a Signature directly from a G2 point, e.g. one produced by
+    /// threshold combination, without a byte round-trip.
+    ///
+    /// The point is not checked, `verify()` still rejects points outside of G2.
+    pub fn from_point(point: &G2Point) -> Self {
+        Self { point: point.0.clone() }
+    }
+
+    /// Advanced: the G2 point underlying the Signature.
+    pub fn as_point(&self) -> G2Point {
+        G2Point(self.point.clone())
+    }
+}
+
+// Hashing and ordering use the compressed bytes, ordering lexicographically, so equal points
+// hash and compare equal regardless of their projective representation.
+impl Hash for Signature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
+}
+
+impl Ord for Signature {
+    fn cmp(&self, other: &Signature) -> Ordering {
+        self.as_bytes().cmp(&other.as_bytes())
+    }
+}
+
+impl PartialOrd for Signature {
+    fn partial_cmp(&self, other: &Signature) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 #[cfg(test)]
@@ -56,6 +324,8 @@ mod tests {
     extern crate hex;
     extern crate rand;
 
+    use super::super::amcl_utils;
+    use super::super::hash_to_curve::XmdHash;
     use super::super::keys::Keypair;
     use super::*;
 
@@ -99,6 +369,178 @@ mod tests {
         assert_eq!(sig.verify(&msg.as_bytes(), &vk), false);
     }
 
+    #[test]
+    fn sign_verify_with_dst() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let dst = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+        let msg = "Some msg".as_bytes();
+
+        let sig = Signature::new_with_dst(msg, &keypair.sk, dst);
+        assert!(sig.verify_with_dst(msg, &keypair.pk, dst));
+
+        // The default ciphersuite uses a different domain
+        assert!(!sig.verify(msg, &keypair.pk));
+        let sig = Signature::new(msg, &keypair.sk);
+        assert!(!sig.verify_with_dst(msg, &keypair.pk, dst));
+    }
+
+    #[test]
+    fn sign_verify_with_hash() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let dst = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+        let msg = "Some msg".as_bytes();
+
+        // SHA-256 is the hash function of the default ciphersuites
+        let sig = Signature::new_with_hash(msg, &keypair.sk, dst, XmdHash::Sha256).unwrap();
+        assert_eq!(sig, Signature::new_with_dst(msg, &keypair.sk, dst));
+        assert!(sig.verify_with_hash(msg, &keypair.pk, dst, XmdHash::Sha256));
+        assert!(!sig.verify_with_hash(b"Other msg", &keypair.pk, dst, XmdHash::Sha256));
+
+        #[cfg(feature = "keccak")]
+        {
+            let dst = b"BLS_SIG_BLS12381G2_XMD:KECCAK-256_SSWU_RO_NUL_";
+            let sig = Signature::new_with_hash(msg, &keypair.sk, dst, XmdHash::Keccak256).unwrap();
+            assert!(sig.verify_with_hash(msg, &keypair.pk, dst, XmdHash::Keccak256));
+            assert!(!sig.verify_with_hash(msg, &keypair.pk, dst, XmdHash::Sha256));
+        }
+    }
+
+    #[test]
+    fn verify_infinity_public_key() {
+        // Infinity Signature and PublicKey satisfy the pairing equation for every message
+        let signature = Signature { point: GroupG2::new() };
+        let public_key = PublicKey { point: amcl_utils::GroupG1::new() };
+
+        assert_eq!(signature.verify(b"message", &public_key), ALLOW_INFINITY_PUBLIC_KEY);
+    }
+
+    #[test]
+    fn signature_from_bytes_batch() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let sigs: Vec<[u8; G2_BYTES]> =
+            (0..10u8).map(|i| Signature::new(&[i], &keypair.sk).as_bytes()).collect();
+        let mut bytes: Vec<&[u8]> = sigs.iter().map(|sig| &sig[..]).collect();
+
+        let decoded = Signature::from_bytes_batch(&bytes).unwrap();
+        assert_eq!(decoded.len(), sigs.len());
+        for (i, sig) in decoded.iter().enumerate() {
+            assert!(sig.verify(&[i as u8], &keypair.pk));
+        }
+
+        // A single malformed Signature fails the batch
+        bytes[3] = &sigs[3][1..];
+        assert!(Signature::from_bytes_batch(&bytes).is_err());
+    }
+
+    #[test]
+    fn signature_from_bytes_not_in_subgroup() {
+        // A point on the curve without clearing the cofactor
+        let point = GroupG2::map2point(&amcl_utils::FP2::new_ints(1, 2));
+        let bytes = compress_g2(&point);
+        assert_eq!(Signature::from_bytes(&bytes), Err(DecodeError::NotInSubgroup));
+
+        // Decoded without the subgroup check the point is on the curve only
+        let unchecked = Signature::from_bytes_unchecked(&bytes).unwrap();
+        assert!(unchecked.is_on_curve());
+        assert!(!unchecked.is_in_subgroup());
+        assert_eq!(unchecked.as_bytes(), bytes);
+        assert_eq!(
+            Signature::from_bytes(&bytes[1..]),
+            Err(DecodeError::InvalidLength { expected: G2_BYTES, actual: G2_BYTES - 1 })
+        );
+    }
+
+    #[test]
+    fn signature_uncompressed_serialization_isomorphism() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let sig = Signature::new(b"uncompressed", &keypair.sk);
+
+        let bytes = sig.to_uncompressed_bytes();
+        let decoded = Signature::from_uncompressed_bytes(&bytes).unwrap();
+        assert_eq!(decoded, sig);
+        assert_eq!(decoded.to_uncompressed_bytes().to_vec(), bytes.to_vec());
+
+        // Compressed bytes are rejected
+        assert_eq!(
+            Signature::from_uncompressed_bytes(&sig.as_bytes()),
+            Err(DecodeError::InvalidLength { expected: 192, actual: 96 })
+        );
+
+        // A point on the curve without clearing the cofactor
+        let point = GroupG2::map2point(&amcl_utils::FP2::new_ints(1, 2));
+        assert_eq!(
+            Signature::from_uncompressed_bytes(&serialize_uncompressed_g2(&point)),
+            Err(DecodeError::NotInSubgroup)
+        );
+    }
+
+    #[test]
+    fn signature_coordinates() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let sig = Signature::new(b"coordinates", &keypair.sk);
+        let (x, y) = sig.as_coordinates();
+        assert_eq!(Signature::from_coordinates(&x, &y).unwrap(), sig);
+
+        // Infinity
+        let (x, y) = Signature { point: GroupG2::new() }.as_coordinates();
+        assert_eq!((x.to_vec(), y.to_vec()), (vec![0; 96], vec![0; 96]));
+        assert!(Signature::from_coordinates(&x, &y).unwrap().point.is_infinity());
+
+        // A point on the curve without clearing the cofactor
+        let point = GroupG2::map2point(&amcl_utils::FP2::new_ints(1, 2));
+        let (x, y) = Signature { point }.as_coordinates();
+        assert_eq!(Signature::from_coordinates(&x, &y), Err(DecodeError::NotInSubgroup));
+    }
+
+    #[test]
+    fn signature_eip2537_bytes() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let sig = Signature::new(b"eip2537", &keypair.sk);
+        let bytes = sig.to_eip2537_bytes();
+        assert_eq!(Signature::from_eip2537_bytes(&bytes).unwrap(), sig);
+
+        // Infinity
+        let bytes = Signature { point: GroupG2::new() }.to_eip2537_bytes();
+        assert!(Signature::from_eip2537_bytes(&bytes).unwrap().point.is_infinity());
+
+        // A point on the curve without clearing the cofactor
+        let point = GroupG2::map2point(&amcl_utils::FP2::new_ints(1, 2));
+        let bytes = Signature { point }.to_eip2537_bytes();
+        assert_eq!(Signature::from_eip2537_bytes(&bytes), Err(DecodeError::NotInSubgroup));
+    }
+
+    #[test]
+    fn signature_subgroup_check_batch() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signatures: Vec<Signature> =
+            (0..30u8).map(|i| Signature::new(&[i], &keypair.sk)).collect();
+        assert!(Signature::subgroup_check_batch(&signatures));
+        assert!(Signature::subgroup_check_batch(&[]));
+    }
+
+    #[test]
+    fn test_signature_hash_ord() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signatures: Vec<Signature> =
+            (0..10u8).map(|i| Signature::new(&[i], &keypair.sk)).collect();
+
+        // Equal signatures hash equally, even if only one is in affine form
+        let decoded = Signature::from_bytes(&signatures[0].as_bytes()).unwrap();
+        let mut set = HashSet::new();
+        assert!(set.insert(signatures[0].clone()));
+        assert!(!set.insert(decoded.clone()));
+        assert_eq!(signatures[0].cmp(&decoded), Ordering::Equal);
+
+        // Ordered lexicographically by compressed bytes
+        let mut sorted = signatures.clone();
+        sorted.sort();
+        assert!(sorted.windows(2).all(|pair| pair[0].as_bytes() < pair[1].as_bytes()));
+        let btree: BTreeSet<Signature> = signatures.iter().cloned().collect();
+        assert_eq!(btree.into_iter().collect::<Vec<_>>(), sorted);
+    }
+
     #[test]
     fn test_readme() {
         // This is an exact replica of the README.md at the top level.
@@ -123,4 +565,62 @@ mod tests {
         // Verify the message
         assert!(signature.verify(&message, &pk));
     }
+
+    #[test]
+    fn test_signature_point() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let msg = b"point";
+        let sig = Signature::new(msg, &keypair.sk);
+
+        let point = sig.as_point();
+        assert_eq!(point.as_bytes(), sig.as_bytes());
+        let from_point = Signature::from_point(&point);
+        assert_eq!(from_point, sig);
+        assert!(from_point.verify(msg, &keypair.pk));
+
+        // Doubling the point gives a signature that no longer verifies
+        let doubled = Signature::from_point(&point.add(&point));
+        assert!(!doubled.verify(msg, &keypair.pk));
+    }
+
+    #[test]
+    fn test_new_blinded() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let msg = b"blinded";
+        let sig = Signature::new_blinded(msg, &keypair.sk, &mut rand::thread_rng());
+        assert_eq!(sig, Signature::new(msg, &keypair.sk));
+        assert!(sig.verify(msg, &keypair.pk));
+    }
+
+    #[test]
+    fn test_try_verify() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let msg = b"try_verify";
+        let sig = Signature::new(msg, &keypair.sk);
+
+        assert_eq!(sig.try_verify(msg, &keypair.pk), Ok(()));
+        assert_eq!(sig.try_verify(b"other", &keypair.pk), Err(VerifyError::InvalidSignature));
+
+        let infinity = PublicKey { point: amcl_utils::GroupG1::new() };
+        if !ALLOW_INFINITY_PUBLIC_KEY {
+            assert_eq!(sig.try_verify(msg, &infinity), Err(VerifyError::InvalidPublicKey));
+        }
+
+        // A point on the curve outside of G2
+        let not_in_subgroup =
+            Signature { point: GroupG2::map2point(&amcl_utils::FP2::new_ints(1, 2)) };
+        assert_eq!(
+            not_in_subgroup.try_verify(msg, &keypair.pk),
+            Err(VerifyError::SignatureNotInSubgroup)
+        );
+
+        // Decoding failures convert with `?`
+        let decode = || -> Result<(), VerifyError> {
+            Signature::from_bytes(&[0u8; 1])?.try_verify(msg, &keypair.pk)
+        };
+        assert_eq!(
+            decode(),
+            Err(VerifyError::Decode(DecodeError::InvalidLength { expected: G2_BYTES, actual: 1 }))
+        );
+    }
 }
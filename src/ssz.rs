@@ -0,0 +1,157 @@
+use super::aggregates::AggregateSignature;
+use super::amcl_utils::{G1_BYTES, G2_BYTES};
+use super::keys::PublicKey;
+use super::signature::Signature;
+use ethereum_ssz::{Decode, DecodeError, Encode};
+use tree_hash::{merkle_root, Hash256, PackedEncoding, TreeHash, TreeHashType, BYTES_PER_CHUNK};
+
+// Implements SSZ encoding and tree hashing as a fixed-length byte vector of the compressed point,
+// i.e. `Vector[byte, $byte_size]` (`BLSPubkey` and `BLSSignature` in the Eth2 spec).
+macro_rules! impl_ssz {
+    ($type: ty, $byte_size: expr) => {
+        impl Encode for $type {
+            fn is_ssz_fixed_len() -> bool {
+                true
+            }
+
+            fn ssz_fixed_len() -> usize {
+                $byte_size
+            }
+
+            fn ssz_append(&self, buf: &mut Vec<u8>) {
+                buf.extend_from_slice(&self.as_bytes())
+            }
+
+            fn ssz_bytes_len(&self) -> usize {
+                $byte_size
+            }
+        }
+
+        impl Decode for $type {
+            fn is_ssz_fixed_len() -> bool {
+                true
+            }
+
+            fn ssz_fixed_len() -> usize {
+                $byte_size
+            }
+
+            fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+                if bytes.len() != $byte_size {
+                    return Err(DecodeError::InvalidByteLength {
+                        len: bytes.len(),
+                        expected: $byte_size,
+                    });
+                }
+                <$type>::from_bytes(bytes).map_err(|e| DecodeError::BytesInvalid(e.to_string()))
+            }
+        }
+
+        impl TreeHash for $type {
+            fn tree_hash_type() -> TreeHashType {
+                TreeHashType::Vector
+            }
+
+            fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+                unreachable!("Vector should never be packed.")
+            }
+
+            fn tree_hash_packing_factor() -> usize {
+                unreachable!("Vector should never be packed.")
+            }
+
+            fn tree_hash_root(&self) -> Hash256 {
+                let minimum_chunk_count = $byte_size.div_ceil(BYTES_PER_CHUNK);
+                merkle_root(&self.as_bytes(), minimum_chunk_count)
+            }
+        }
+    };
+}
+
+impl_ssz!(PublicKey, G1_BYTES);
+impl_ssz!(Signature, G2_BYTES);
+impl_ssz!(AggregateSignature, G2_BYTES);
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::keys::Keypair;
+    use super::*;
+
+    #[test]
+    fn test_ssz_round_trip() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signature = Signature::new(&[42u8; 32], &keypair.sk);
+        let aggregate_signature = AggregateSignature::from_signature(&signature);
+
+        let encoded = keypair.pk.as_ssz_bytes();
+        assert_eq!(encoded, keypair.pk.as_bytes().to_vec());
+        assert_eq!(PublicKey::from_ssz_bytes(&encoded).unwrap(), keypair.pk);
+
+        let encoded = signature.as_ssz_bytes();
+        assert_eq!(encoded, signature.as_bytes().to_vec());
+        assert_eq!(Signature::from_ssz_bytes(&encoded).unwrap(), signature);
+
+        let encoded = aggregate_signature.as_ssz_bytes();
+        assert_eq!(encoded, aggregate_signature.as_bytes().to_vec());
+        assert_eq!(AggregateSignature::from_ssz_bytes(&encoded).unwrap(), aggregate_signature);
+
+        // Infinity is a valid AggregateSignature, e.g. for an empty sync committee
+        let encoded = AggregateSignature::new().as_ssz_bytes();
+        assert_eq!(
+            AggregateSignature::from_ssz_bytes(&encoded).unwrap(),
+            AggregateSignature::new()
+        );
+    }
+
+    #[test]
+    fn test_ssz_fixed_len() {
+        assert!(<PublicKey as Encode>::is_ssz_fixed_len());
+        assert_eq!(<PublicKey as Encode>::ssz_fixed_len(), 48);
+        assert_eq!(<PublicKey as Decode>::ssz_fixed_len(), 48);
+        assert!(<Signature as Encode>::is_ssz_fixed_len());
+        assert_eq!(<Signature as Encode>::ssz_fixed_len(), 96);
+        assert_eq!(<AggregateSignature as Decode>::ssz_fixed_len(), 96);
+    }
+
+    #[test]
+    fn test_ssz_decode_invalid() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let encoded = keypair.pk.as_ssz_bytes();
+
+        assert_eq!(
+            PublicKey::from_ssz_bytes(&encoded[..47]),
+            Err(DecodeError::InvalidByteLength { len: 47, expected: 48 })
+        );
+        assert_eq!(
+            Signature::from_ssz_bytes(&[0u8; 97]),
+            Err(DecodeError::InvalidByteLength { len: 97, expected: 96 })
+        );
+
+        // Invalid points
+        match PublicKey::from_ssz_bytes(&[0u8; 48]) {
+            Err(DecodeError::BytesInvalid(_)) => {}
+            _ => panic!("expected BytesInvalid"),
+        }
+        match Signature::from_ssz_bytes(&[0u8; 96]) {
+            Err(DecodeError::BytesInvalid(_)) => {}
+            _ => panic!("expected BytesInvalid"),
+        }
+    }
+
+    #[test]
+    fn test_tree_hash_root() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signature = Signature::new(&[42u8; 32], &keypair.sk);
+
+        // Same root as Vector[byte, N], which merkleizes the bytes in 32 byte chunks
+        assert_eq!(<PublicKey as TreeHash>::tree_hash_type(), TreeHashType::Vector);
+        assert_eq!(keypair.pk.tree_hash_root(), merkle_root(&keypair.pk.as_bytes(), 2));
+        assert_eq!(signature.tree_hash_root(), merkle_root(&signature.as_bytes(), 3));
+        assert_eq!(
+            AggregateSignature::from_signature(&signature).tree_hash_root(),
+            signature.tree_hash_root()
+        );
+    }
+}
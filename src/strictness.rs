@@ -0,0 +1,188 @@
+//! Configurable encoding rules for decoding points.
+
+use super::amcl_utils::{
+    decompress_g1, decompress_g2, deserialize_uncompressed_g1, deserialize_uncompressed_g2,
+    subgroup_check_g1, subgroup_check_g2, GroupG1, GroupG2, COMPRESSION_FLAG, FLAGS_MASK, G1_BYTES,
+    G2_BYTES, INFINITY_FLAG, SORT_FLAG,
+};
+use super::errors::DecodeError;
+
+/// The encoding rules applied when decoding points, for chains which froze on rules other than
+/// this crate's.
+///
+/// `Strictness::default()` is the rule set of `from_bytes()`, every rule enabled. Each rule may
+/// be relaxed separately with the `*_with_strictness()` constructors.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Strictness {
+    /// Reject encodings of the point at infinity with any bits set besides the flags.
+    pub reject_infinity_padding: bool,
+    /// Reject the legacy encoding of the point at infinity with the sort (a_flag) bit set.
+    pub reject_legacy_a_flag: bool,
+    /// Reject uncompressed encodings, rather than accepting both lengths.
+    pub compressed_only: bool,
+}
+
+impl Strictness {
+    /// Every rule enabled, as `from_bytes()`.
+    pub const STRICT: Strictness = Strictness {
+        reject_infinity_padding: true,
+        reject_legacy_a_flag: true,
+        compressed_only: true,
+    };
+
+    /// Every rule relaxed.
+    pub const PERMISSIVE: Strictness = Strictness {
+        reject_infinity_padding: false,
+        reject_legacy_a_flag: false,
+        compressed_only: false,
+    };
+
+    // Returns true for an encoding of the point at infinity accepted by the relaxed rules,
+    // strict encodings are left to the regular decoding.
+    fn is_relaxed_infinity(&self, bytes: &[u8], compressed: bool) -> bool {
+        let (first, rest) = match bytes.split_first() {
+            Some(split) => split,
+            None => return false,
+        };
+        if first & INFINITY_FLAG == 0 || (first & COMPRESSION_FLAG != 0) != compressed {
+            return false;
+        }
+        let padded = first & !FLAGS_MASK != 0 || rest.iter().any(|byte| *byte != 0);
+        let a_flag = first & SORT_FLAG != 0;
+        (!padded || !self.reject_infinity_padding) && (!a_flag || !self.reject_legacy_a_flag)
+    }
+}
+
+impl Default for Strictness {
+    fn default() -> Self {
+        Strictness::STRICT
+    }
+}
+
+// Decode a G1 point in the G1 subgroup under the given rules.
+pub(crate) fn decode_g1(bytes: &[u8], strictness: &Strictness) -> Result<GroupG1, DecodeError> {
+    if strictness.compressed_only || bytes.len() != G1_BYTES * 2 {
+        if bytes.len() == G1_BYTES && strictness.is_relaxed_infinity(bytes, true) {
+            return Ok(GroupG1::new());
+        }
+        return decompress_g1(bytes);
+    }
+    if strictness.is_relaxed_infinity(bytes, false) {
+        return Ok(GroupG1::new());
+    }
+    let point = deserialize_uncompressed_g1(bytes)?;
+    if !subgroup_check_g1(&point) {
+        return Err(DecodeError::NotInSubgroup);
+    }
+    Ok(point)
+}
+
+// Decode a G2 point in the G2 subgroup under the given rules.
+pub(crate) fn decode_g2(bytes: &[u8], strictness: &Strictness) -> Result<GroupG2, DecodeError> {
+    if strictness.compressed_only || bytes.len() != G2_BYTES * 2 {
+        if bytes.len() == G2_BYTES && strictness.is_relaxed_infinity(bytes, true) {
+            return Ok(GroupG2::new());
+        }
+        return decompress_g2(bytes);
+    }
+    if strictness.is_relaxed_infinity(bytes, false) {
+        return Ok(GroupG2::new());
+    }
+    let point = deserialize_uncompressed_g2(bytes)?;
+    if !subgroup_check_g2(&point) {
+        return Err(DecodeError::NotInSubgroup);
+    }
+    Ok(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::amcl_utils::compress_g1;
+    use super::super::errors::EncodingFlag;
+    use super::super::{AggregateSignature, Keypair, PublicKey, Signature};
+    use super::*;
+
+    extern crate rand;
+
+    #[test]
+    fn test_strict_is_default() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let pk_bytes = keypair.pk.as_bytes();
+        assert_eq!(
+            PublicKey::from_bytes_with_strictness(&pk_bytes, &Strictness::default()),
+            PublicKey::from_bytes(&pk_bytes)
+        );
+
+        let mut infinity = [0u8; G2_BYTES];
+        infinity[0] = COMPRESSION_FLAG | INFINITY_FLAG;
+        let strict = Signature::from_bytes_with_strictness(&infinity, &Strictness::STRICT);
+        assert!(strict.unwrap().as_point().is_identity());
+    }
+
+    #[test]
+    fn test_infinity_padding() {
+        let mut padded = [0u8; G2_BYTES];
+        padded[0] = COMPRESSION_FLAG | INFINITY_FLAG | 0x01;
+        padded[G2_BYTES - 1] = 0x01;
+        assert_eq!(
+            Signature::from_bytes_with_strictness(&padded, &Strictness::STRICT),
+            Err(DecodeError::InvalidFlag(EncodingFlag::Infinity))
+        );
+
+        let relaxed = Strictness { reject_infinity_padding: false, ..Strictness::STRICT };
+        let signature = Signature::from_bytes_with_strictness(&padded, &relaxed).unwrap();
+        assert!(signature.as_point().is_identity());
+        let aggregate = AggregateSignature::from_bytes_with_strictness(&padded, &relaxed).unwrap();
+        assert!(aggregate.as_point().is_identity());
+    }
+
+    #[test]
+    fn test_legacy_a_flag() {
+        let mut legacy = [0u8; G2_BYTES];
+        legacy[0] = COMPRESSION_FLAG | INFINITY_FLAG | SORT_FLAG;
+        assert_eq!(
+            Signature::from_bytes_with_strictness(&legacy, &Strictness::STRICT),
+            Err(DecodeError::InvalidFlag(EncodingFlag::Sort))
+        );
+
+        let relaxed = Strictness { reject_legacy_a_flag: false, ..Strictness::STRICT };
+        assert!(Signature::from_bytes_with_strictness(&legacy, &relaxed).is_ok());
+        // Padding remains rejected
+        legacy[1] = 1;
+        assert!(Signature::from_bytes_with_strictness(&legacy, &relaxed).is_err());
+        assert!(Signature::from_bytes_with_strictness(&legacy, &Strictness::PERMISSIVE).is_ok());
+    }
+
+    #[test]
+    fn test_compressed_only() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let uncompressed = keypair.pk.to_uncompressed_bytes();
+        assert_eq!(
+            PublicKey::from_bytes_with_strictness(&uncompressed, &Strictness::STRICT),
+            Err(DecodeError::InvalidLength { expected: G1_BYTES, actual: G1_BYTES * 2 })
+        );
+
+        let relaxed = Strictness { compressed_only: false, ..Strictness::STRICT };
+        let decoded = PublicKey::from_bytes_with_strictness(&uncompressed, &relaxed).unwrap();
+        assert_eq!(decoded.as_bytes(), keypair.pk.as_bytes());
+
+        let signature = Signature::new(b"cats", &keypair.sk);
+        let decoded = Signature::from_bytes_with_strictness(
+            &signature.to_uncompressed_bytes(),
+            &Strictness::PERMISSIVE,
+        );
+        assert_eq!(decoded.unwrap().as_bytes(), signature.as_bytes());
+    }
+
+    #[test]
+    fn test_public_key_infinity_rejected() {
+        // Relaxed encodings of infinity are still not valid PublicKeys
+        let mut padded = compress_g1(&GroupG1::new());
+        padded[G1_BYTES - 1] = 1;
+        assert_eq!(
+            PublicKey::from_bytes_with_strictness(&padded, &Strictness::PERMISSIVE),
+            Err(DecodeError::Infinity)
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use super::aggregates::{AggregatePublicKey, AggregateSignature};
+use super::amcl_utils::AmclError;
+use super::keys::PublicKey;
+
+/// Verifies a sync committee aggregate as done by Ethereum light clients.
+///
+/// `participation_bits` is the SSZ `Bitvector` of the committee (little-endian bit order) and
+/// selects which of the `committee_pubkeys` signed the `signing_root`.
+///
+/// Follows `eth_fast_aggregate_verify`: when no member participated the signature must be the
+/// point at infinity, otherwise FastAggregateVerify is run over the participating keys.
+/// https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/bls.md#eth_fast_aggregate_verify
+///
+/// Pre-requsites: All public keys must be PoP verified before calling this function.
+pub fn verify_sync_committee(
+    signature: &AggregateSignature,
+    participation_bits: &[u8],
+    committee_pubkeys: &[PublicKey],
+    signing_root: &[u8],
+    dst: &[u8],
+) -> bool {
+    // Bitvector must have exactly one bit per committee member, with zeroed padding bits
+    if participation_bits.len() != committee_pubkeys.len().div_ceil(8) {
+        return false;
+    }
+    let padding_bits = committee_pubkeys.len() % 8;
+    if padding_bits != 0 && participation_bits[participation_bits.len() - 1] >> padding_bits != 0 {
+        return false;
+    }
+
+    // Aggregate participating PublicKeys, without participants only the infinity signature
+    // is valid. Any other error is an invalid participating PublicKey, e.g. infinity.
+    let aggregate_public_key =
+        match AggregatePublicKey::from_pubkeys_filtered(committee_pubkeys, participation_bits) {
+            Ok(aggregate_public_key) => aggregate_public_key,
+            Err(AmclError::AggregateEmptyPoints) => return signature.point.is_infinity(),
+            Err(_) => return false,
+        };
+
+    signature.fast_aggregate_verify_pre_aggregated_with_dst(
+        signing_root,
+        &aggregate_public_key,
+        dst,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::amcl_utils::GroupG1;
+    use super::super::keys::Keypair;
+    use super::super::signature::Signature;
+    use super::*;
+
+    const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+    #[test]
+    fn test_verify_sync_committee() {
+        let keypairs: Vec<Keypair> =
+            (0..10).map(|_| Keypair::random(&mut rand::thread_rng())).collect();
+        let public_keys: Vec<PublicKey> = keypairs.iter().map(|kp| kp.pk.clone()).collect();
+        let signing_root = [7u8; 32];
+
+        // Members 0, 3 and 9 participate
+        let participation_bits = [0b0000_1001, 0b0000_0010];
+        let mut signature = AggregateSignature::new();
+        for i in &[0, 3, 9] {
+            signature.add(&Signature::new_with_dst(&signing_root, &keypairs[*i].sk, DST));
+        }
+
+        assert!(verify_sync_committee(
+            &signature,
+            &participation_bits,
+            &public_keys,
+            &signing_root,
+            DST
+        ));
+
+        // Incorrect participants
+        assert!(!verify_sync_committee(
+            &signature,
+            &[0b0000_1001, 0b0000_0000],
+            &public_keys,
+            &signing_root,
+            DST
+        ));
+
+        // Incorrect message
+        assert!(!verify_sync_committee(
+            &signature,
+            &participation_bits,
+            &public_keys,
+            &[8u8; 32],
+            DST
+        ));
+    }
+
+    #[test]
+    fn test_verify_sync_committee_malformed_bits() {
+        let public_keys: Vec<PublicKey> =
+            (0..10).map(|_| Keypair::random(&mut rand::thread_rng()).pk).collect();
+        let signature = AggregateSignature::new();
+
+        // Too few and too many bytes
+        assert!(!verify_sync_committee(&signature, &[0], &public_keys, &[0; 32], DST));
+        assert!(!verify_sync_committee(&signature, &[0, 0, 0], &public_keys, &[0; 32], DST));
+
+        // Padding bit set
+        assert!(!verify_sync_committee(&signature, &[0, 0b0000_0100], &public_keys, &[0; 32], DST));
+    }
+
+    #[test]
+    fn test_verify_sync_committee_no_participants() {
+        let public_keys: Vec<PublicKey> =
+            (0..10).map(|_| Keypair::random(&mut rand::thread_rng()).pk).collect();
+
+        // Infinity signature is valid without participants
+        let signature = AggregateSignature::new();
+        assert!(verify_sync_committee(&signature, &[0, 0], &public_keys, &[0; 32], DST));
+
+        // Any other signature is not
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signature = AggregateSignature::from_signature(&Signature::new_with_dst(
+            &[0; 32],
+            &keypair.sk,
+            DST,
+        ));
+        assert!(!verify_sync_committee(&signature, &[0, 0], &public_keys, &[0; 32], DST));
+    }
+
+    #[test]
+    fn test_verify_sync_committee_infinity_public_key() {
+        let mut public_keys: Vec<PublicKey> =
+            (0..10).map(|_| Keypair::random(&mut rand::thread_rng()).pk).collect();
+        public_keys[3] = PublicKey { point: GroupG1::new() };
+        let signature = AggregateSignature::new();
+
+        // The infinity signature is only valid without participants
+        assert!(!verify_sync_committee(&signature, &[0b0000_1000, 0], &public_keys, &[0; 32], DST));
+        assert!(!verify_sync_committee(&signature, &[0b0000_1001, 0], &public_keys, &[0; 32], DST));
+    }
+}
@@ -0,0 +1,220 @@
+//! Encodings of invalid points for negative tests, enabled by the `test-support` feature.
+//!
+//! Each encoding is rejected by the decoding functions of this crate, so that applications can
+//! test their own stacks reject malicious keys and signatures end to end. Not for production
+//! use.
+
+use super::amcl_utils::{
+    compress_g1, compress_g2, serialize_uncompressed_g1, serialize_uncompressed_g2,
+    subgroup_check_g1, subgroup_check_g2, Big, GroupG1, GroupG2, COMPRESSION_FLAG, FP2, G1_BYTES,
+    G2_BYTES, INFINITY_FLAG, MODBYTES, MODULUS_BYTES, SORT_FLAG,
+};
+use super::errors::EncodingFlag;
+use super::fields::{Fp, Fp2};
+use super::hash_to_curve::{map_to_curve_g1, map_to_curve_g2};
+use BLSCurve::fp::FP;
+
+/// A compressed point on the curve E1 but not in G1, e.g. a small order torsion component.
+///
+/// Rejected with `DecodeError::NotInSubgroup`, the seed selects the point.
+pub fn g1_not_in_subgroup(seed: u32) -> [u8; G1_BYTES] {
+    compress_g1(&point_g1_not_in_subgroup(seed))
+}
+
+/// A compressed point on the curve E2 but not in G2, e.g. a small order torsion component.
+///
+/// Rejected with `DecodeError::NotInSubgroup`, the seed selects the point.
+pub fn g2_not_in_subgroup(seed: u32) -> [u8; G2_BYTES] {
+    compress_g2(&point_g2_not_in_subgroup(seed))
+}
+
+/// The uncompressed (x, y) bytes of a point on E1 but not in G1.
+pub fn g1_not_in_subgroup_uncompressed(seed: u32) -> [u8; G1_BYTES * 2] {
+    serialize_uncompressed_g1(&point_g1_not_in_subgroup(seed))
+}
+
+/// The uncompressed (x, y) bytes of a point on E2 but not in G2.
+pub fn g2_not_in_subgroup_uncompressed(seed: u32) -> [u8; G2_BYTES * 2] {
+    serialize_uncompressed_g2(&point_g2_not_in_subgroup(seed))
+}
+
+/// Uncompressed (x, y) bytes which do not satisfy the curve equation of E1.
+///
+/// Rejected with `DecodeError::NotOnCurve`.
+pub fn g1_not_on_curve() -> [u8; G1_BYTES * 2] {
+    // The generator with y changed by one
+    let mut bytes = serialize_uncompressed_g1(&GroupG1::generator());
+    bytes[G1_BYTES * 2 - 1] ^= 1;
+    bytes
+}
+
+/// Uncompressed (x, y) bytes which do not satisfy the curve equation of E2.
+///
+/// Rejected with `DecodeError::NotOnCurve`.
+pub fn g2_not_on_curve() -> [u8; G2_BYTES * 2] {
+    // The generator with y0 changed by one
+    let mut bytes = serialize_uncompressed_g2(&GroupG2::generator());
+    bytes[G2_BYTES * 2 - 1] ^= 1;
+    bytes
+}
+
+/// A compressed G1 encoding with the x coordinate equal to the field modulus p, the non-canonical
+/// encoding of x = 0.
+///
+/// Rejected with `DecodeError::FieldElementTooLarge`.
+pub fn g1_non_canonical() -> [u8; G1_BYTES] {
+    let mut bytes = MODULUS_BYTES;
+    bytes[0] |= COMPRESSION_FLAG;
+    bytes
+}
+
+/// A compressed G2 encoding with x = (c1, c0) where c1 is equal to the field modulus p.
+///
+/// Rejected with `DecodeError::FieldElementTooLarge`.
+pub fn g2_non_canonical() -> [u8; G2_BYTES] {
+    let mut bytes = [0u8; G2_BYTES];
+    bytes[..MODBYTES].copy_from_slice(&MODULUS_BYTES);
+    bytes[0] |= COMPRESSION_FLAG;
+    bytes
+}
+
+/// Toggle a flag in the first byte of an encoded point.
+///
+/// Toggling the compression or infinity flag of a valid encoding makes it invalid. Toggling the
+/// sort flag of a compressed encoding selects the other y coordinate, the negation of the point,
+/// so is only rejected for uncompressed encodings and encodings of the point at infinity.
+pub fn flip_flag(bytes: &mut [u8], flag: EncodingFlag) {
+    let mask = match flag {
+        EncodingFlag::Compression => COMPRESSION_FLAG,
+        EncodingFlag::Infinity => INFINITY_FLAG,
+        EncodingFlag::Sort => SORT_FLAG,
+    };
+    if let Some(first) = bytes.first_mut() {
+        *first ^= mask;
+    }
+}
+
+// The seed as an Fp element.
+fn fp(seed: u32) -> FP {
+    let mut bytes = [0u8; MODBYTES];
+    bytes[MODBYTES - 4..].copy_from_slice(&seed.to_be_bytes());
+    FP::new_big(&Big::from_bytes(&bytes))
+}
+
+fn point_g1_not_in_subgroup(seed: u32) -> GroupG1 {
+    // The cofactor is not cleared, so nearly every mapped point is outside of G1
+    let mut u = seed;
+    loop {
+        let point = map_to_curve_g1(&Fp(fp(u))).0;
+        if !subgroup_check_g1(&point) {
+            return point;
+        }
+        u = u.wrapping_add(1);
+    }
+}
+
+fn point_g2_not_in_subgroup(seed: u32) -> GroupG2 {
+    // The cofactor is not cleared, so nearly every mapped point is outside of G2
+    let mut u = seed;
+    loop {
+        let point = map_to_curve_g2(&Fp2(FP2::new_fps(&fp(u), &FP::new_int(1)))).0;
+        if !subgroup_check_g2(&point) {
+            return point;
+        }
+        u = u.wrapping_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::errors::DecodeError;
+    use super::super::{AggregateSignature, G1Point, G2Point, PublicKey, Signature};
+    use super::*;
+
+    #[test]
+    fn test_not_in_subgroup() {
+        for seed in 0..4 {
+            let bytes = g1_not_in_subgroup(seed);
+            assert_eq!(PublicKey::from_bytes(&bytes), Err(DecodeError::NotInSubgroup));
+            assert!(!PublicKey::from_bytes_unchecked(&bytes).unwrap().is_in_subgroup());
+            let bytes = g1_not_in_subgroup_uncompressed(seed);
+            assert_eq!(G1Point::from_uncompressed_bytes(&bytes), Err(DecodeError::NotInSubgroup));
+            assert_eq!(PublicKey::from_uncompressed_bytes(&bytes), Err(DecodeError::NotInSubgroup));
+            assert!(PublicKey::from_uncompressed_bytes_unchecked(&bytes).is_ok());
+
+            let bytes = g2_not_in_subgroup(seed);
+            assert_eq!(Signature::from_bytes(&bytes), Err(DecodeError::NotInSubgroup));
+            assert_eq!(AggregateSignature::from_bytes(&bytes), Err(DecodeError::NotInSubgroup));
+            assert!(Signature::from_bytes_unchecked(&bytes).unwrap().is_on_curve());
+            assert_eq!(
+                G2Point::from_uncompressed_bytes(&g2_not_in_subgroup_uncompressed(seed)),
+                Err(DecodeError::NotInSubgroup)
+            );
+        }
+        assert_ne!(g1_not_in_subgroup(0), g1_not_in_subgroup(1));
+        assert_ne!(g2_not_in_subgroup(0), g2_not_in_subgroup(1));
+    }
+
+    #[test]
+    fn test_not_on_curve() {
+        assert_eq!(
+            PublicKey::from_uncompressed_bytes(&g1_not_on_curve()),
+            Err(DecodeError::NotOnCurve)
+        );
+        assert_eq!(
+            Signature::from_uncompressed_bytes(&g2_not_on_curve()),
+            Err(DecodeError::NotOnCurve)
+        );
+    }
+
+    #[test]
+    fn test_non_canonical() {
+        assert_eq!(
+            PublicKey::from_bytes(&g1_non_canonical()),
+            Err(DecodeError::FieldElementTooLarge)
+        );
+        assert_eq!(
+            Signature::from_bytes(&g2_non_canonical()),
+            Err(DecodeError::FieldElementTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_flip_flag() {
+        let point = G2Point::hash_to_curve(b"cats");
+        let valid = point.as_bytes();
+
+        let mut bytes = valid;
+        flip_flag(&mut bytes, EncodingFlag::Compression);
+        assert_eq!(
+            Signature::from_bytes(&bytes),
+            Err(DecodeError::InvalidFlag(EncodingFlag::Compression))
+        );
+
+        // With the sort flag set it would be reported before the infinity flag
+        let mut bytes = if valid[0] & SORT_FLAG == 0 { valid } else { point.neg().as_bytes() };
+        flip_flag(&mut bytes, EncodingFlag::Infinity);
+        assert_eq!(
+            Signature::from_bytes(&bytes),
+            Err(DecodeError::InvalidFlag(EncodingFlag::Infinity))
+        );
+
+        // The sort flag of a compressed encoding selects the negation
+        let mut bytes = valid;
+        flip_flag(&mut bytes, EncodingFlag::Sort);
+        assert_eq!(G2Point::from_bytes(&bytes).unwrap(), point.neg());
+
+        let mut bytes = point.to_uncompressed_bytes();
+        flip_flag(&mut bytes, EncodingFlag::Sort);
+        assert_eq!(
+            G2Point::from_uncompressed_bytes(&bytes),
+            Err(DecodeError::InvalidFlag(EncodingFlag::Sort))
+        );
+
+        let mut bytes = G1Point::identity().as_bytes();
+        flip_flag(&mut bytes, EncodingFlag::Sort);
+        assert_eq!(G1Point::from_bytes(&bytes), Err(DecodeError::InvalidFlag(EncodingFlag::Sort)));
+
+        flip_flag(&mut [], EncodingFlag::Sort);
+    }
+}
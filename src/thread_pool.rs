@@ -0,0 +1,70 @@
+//! The rayon thread pool used by batch decoding, validation and aggregation with the
+//! `parallel` feature.
+//!
+//! By default the global rayon pool is used. Nodes may instead supply their own pool, or limit
+//! the number of threads, so that BLS work does not compete with networking or database threads.
+
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+use std::sync::{Arc, RwLock};
+
+static THREAD_POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+
+/// Run parallel work in the given thread pool rather than the global rayon pool.
+pub fn set_thread_pool(pool: Arc<ThreadPool>) {
+    *THREAD_POOL.write().unwrap_or_else(|error| error.into_inner()) = Some(pool);
+}
+
+/// Run parallel work in a dedicated thread pool of at most `num_threads` threads.
+pub fn set_max_threads(num_threads: usize) -> Result<(), ThreadPoolBuildError> {
+    let pool = ThreadPoolBuilder::new().num_threads(num_threads).build()?;
+    set_thread_pool(Arc::new(pool));
+    Ok(())
+}
+
+/// Run parallel work in the global rayon pool again.
+pub fn reset_thread_pool() {
+    *THREAD_POOL.write().unwrap_or_else(|error| error.into_inner()) = None;
+}
+
+/// The number of threads parallel work is spread across.
+pub fn current_num_threads() -> usize {
+    match thread_pool() {
+        Some(pool) => pool.current_num_threads(),
+        None => rayon::current_num_threads(),
+    }
+}
+
+// Run `op` in the configured thread pool, parallel iterators within it use that pool.
+pub(crate) fn install<OP, R>(op: OP) -> R
+where
+    OP: FnOnce() -> R + Send,
+    R: Send,
+{
+    match thread_pool() {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+// The lock is not held while work runs, so the pool may be replaced concurrently.
+fn thread_pool() -> Option<Arc<ThreadPool>> {
+    THREAD_POOL.read().unwrap_or_else(|error| error.into_inner()).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_pool() {
+        assert_eq!(install(|| 1 + 1), 2);
+
+        set_max_threads(2).unwrap();
+        assert!(thread_pool().is_some());
+        assert_eq!(install(|| 1 + 1), 2);
+
+        reset_thread_pool();
+        assert!(thread_pool().is_none());
+        assert_eq!(current_num_threads(), rayon::current_num_threads());
+    }
+}
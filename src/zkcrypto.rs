@@ -0,0 +1,171 @@
+extern crate zeroize;
+
+use self::zeroize::Zeroize;
+use super::aggregates::{AggregatePublicKey, AggregateSignature};
+use super::amcl_utils::{
+    deserialize_uncompressed_g1, deserialize_uncompressed_g2, serialize_uncompressed_g1,
+    serialize_uncompressed_g2, GroupG1, GroupG2,
+};
+use super::errors::DecodeError;
+use super::keys::{PublicKey, SecretKey};
+use super::signature::Signature;
+use bls12_381::{G1Affine, G2Affine, Scalar};
+#[cfg(not(feature = "std"))]
+use core::convert::TryFrom;
+#[cfg(feature = "std")]
+use std::convert::TryFrom;
+
+// Points are exchanged using the uncompressed encoding, which both libraries share. Both sides
+// only hold points in the prime order subgroup, so the square root of decompression and the
+// subgroup check are skipped. For the same reason the fallbacks to infinity are never taken.
+fn g1_to_affine(point: &GroupG1) -> G1Affine {
+    G1Affine::from_uncompressed_unchecked(&serialize_uncompressed_g1(point))
+        .unwrap_or(G1Affine::identity())
+}
+
+fn g1_from_affine(point: &G1Affine) -> GroupG1 {
+    deserialize_uncompressed_g1(&point.to_uncompressed()).unwrap_or_else(|_| GroupG1::new())
+}
+
+fn g2_to_affine(point: &GroupG2) -> G2Affine {
+    G2Affine::from_uncompressed_unchecked(&serialize_uncompressed_g2(point))
+        .unwrap_or(G2Affine::identity())
+}
+
+fn g2_from_affine(point: &G2Affine) -> GroupG2 {
+    deserialize_uncompressed_g2(&point.to_uncompressed()).unwrap_or_else(|_| GroupG2::new())
+}
+
+impl<'a> From<&'a PublicKey> for G1Affine {
+    fn from(public_key: &'a PublicKey) -> Self {
+        g1_to_affine(&public_key.point)
+    }
+}
+
+/// The identity is rejected, as in `PublicKey::from_bytes()`.
+impl TryFrom<G1Affine> for PublicKey {
+    type Error = DecodeError;
+
+    fn try_from(point: G1Affine) -> Result<Self, Self::Error> {
+        if bool::from(point.is_identity()) {
+            return Err(DecodeError::Infinity);
+        }
+        Ok(PublicKey { point: g1_from_affine(&point) })
+    }
+}
+
+impl<'a> From<&'a AggregatePublicKey> for G1Affine {
+    fn from(aggregate_public_key: &'a AggregatePublicKey) -> Self {
+        g1_to_affine(&aggregate_public_key.point)
+    }
+}
+
+impl From<G1Affine> for AggregatePublicKey {
+    fn from(point: G1Affine) -> Self {
+        AggregatePublicKey { point: g1_from_affine(&point) }
+    }
+}
+
+impl<'a> From<&'a Signature> for G2Affine {
+    fn from(signature: &'a Signature) -> Self {
+        g2_to_affine(&signature.point)
+    }
+}
+
+impl From<G2Affine> for Signature {
+    fn from(point: G2Affine) -> Self {
+        Signature { point: g2_from_affine(&point) }
+    }
+}
+
+impl<'a> From<&'a AggregateSignature> for G2Affine {
+    fn from(aggregate_signature: &'a AggregateSignature) -> Self {
+        g2_to_affine(&aggregate_signature.point)
+    }
+}
+
+impl From<G2Affine> for AggregateSignature {
+    fn from(point: G2Affine) -> Self {
+        AggregateSignature { point: g2_from_affine(&point) }
+    }
+}
+
+// SecretKeys are big-endian while Scalars are little-endian.
+impl<'a> From<&'a SecretKey> for Scalar {
+    fn from(secret_key: &'a SecretKey) -> Self {
+        // SecretKeys are less than r, so the wide reduction leaves them unchanged
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&secret_key.as_bytes());
+        bytes[..32].reverse();
+        let scalar = Scalar::from_bytes_wide(&bytes);
+        bytes.zeroize();
+        scalar
+    }
+}
+
+/// The zero Scalar is rejected, as in `SecretKey::from_bytes()`.
+impl TryFrom<Scalar> for SecretKey {
+    type Error = DecodeError;
+
+    fn try_from(scalar: Scalar) -> Result<Self, Self::Error> {
+        let mut bytes = scalar.to_bytes();
+        bytes.reverse();
+        let secret_key = SecretKey::from_bytes(&bytes);
+        bytes.zeroize();
+        secret_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use super::super::amcl_utils::compress_g1;
+    use super::super::keys::Keypair;
+    use super::*;
+
+    #[test]
+    fn test_zkcrypto_generators() {
+        assert_eq!(G1Affine::generator().to_compressed(), compress_g1(&GroupG1::generator()));
+        assert_eq!(AggregatePublicKey::from(G1Affine::generator()).point, GroupG1::generator());
+        assert_eq!(AggregateSignature::from(G2Affine::generator()).point, GroupG2::generator());
+
+        // SecretKey of one is the Scalar one
+        let mut bytes = [0u8; 32];
+        bytes[31] = 1;
+        let secret_key = SecretKey::from_bytes(&bytes).unwrap();
+        assert_eq!(Scalar::from(&secret_key), Scalar::from(1u64));
+        assert_eq!(SecretKey::try_from(Scalar::from(1u64)).unwrap(), secret_key);
+    }
+
+    #[test]
+    fn test_zkcrypto_round_trip() {
+        let keypair = Keypair::random(&mut rand::thread_rng());
+        let signature = Signature::new(&[42u8; 32], &keypair.sk);
+        let aggregate_signature = AggregateSignature::from_signature(&signature);
+
+        let point = G1Affine::from(&keypair.pk);
+        assert_eq!(point.to_compressed(), keypair.pk.as_bytes());
+        assert_eq!(PublicKey::try_from(point).unwrap(), keypair.pk);
+
+        let point = G2Affine::from(&signature);
+        assert_eq!(point.to_compressed(), signature.as_bytes());
+        assert_eq!(Signature::from(point), signature);
+
+        let point = G2Affine::from(&aggregate_signature);
+        assert_eq!(AggregateSignature::from(point), aggregate_signature);
+
+        let scalar = Scalar::from(&keypair.sk);
+        assert_eq!(SecretKey::try_from(scalar).unwrap(), keypair.sk);
+    }
+
+    #[test]
+    fn test_zkcrypto_identity() {
+        assert_eq!(PublicKey::try_from(G1Affine::identity()), Err(DecodeError::Infinity));
+        assert!(AggregatePublicKey::from(G1Affine::identity()).point.is_infinity());
+        assert!(Signature::from(G2Affine::identity()).point.is_infinity());
+        assert!(bool::from(G2Affine::from(&AggregateSignature::new()).is_identity()));
+
+        assert_eq!(SecretKey::try_from(Scalar::from(0u64)), Err(DecodeError::InvalidSecretKey));
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    uniffi::generate_scaffolding("src/milagro_bls.udl").unwrap();
+}
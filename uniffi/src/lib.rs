@@ -0,0 +1,174 @@
+//! UniFFI bindings for milagro_bls.
+//!
+//! The interface is defined in `src/milagro_bls.udl`. Kotlin and Swift sources are generated
+//! from the built library with, for example:
+//!
+//! ```text
+//! cargo run --bin uniffi-bindgen generate --library target/release/libmilagro_bls_uniffi.so \
+//!     --language kotlin --out-dir out
+//! ```
+
+use milagro_bls::{
+    AggregatePublicKey, AggregateSignature, AmclError, PublicKey, SecretKey, Signature,
+};
+use std::{error, fmt};
+
+uniffi::include_scaffolding!("milagro_bls");
+
+/// Errors returned to Kotlin and Swift.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlsError {
+    /// The input keying material is shorter than 32 bytes.
+    InvalidIkm,
+    /// The SecretKey is not 32 bytes or not in the range 0 < x < r.
+    InvalidSecretKey,
+    /// A PublicKey could not be decoded or failed validation.
+    InvalidPublicKey,
+    /// A Signature could not be decoded.
+    InvalidSignature,
+    /// No points were given to aggregate.
+    AggregateEmpty,
+}
+
+impl fmt::Display for BlsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlsError::InvalidIkm => write!(f, "input keying material is shorter than 32 bytes"),
+            BlsError::InvalidSecretKey => write!(f, "invalid secret key"),
+            BlsError::InvalidPublicKey => write!(f, "invalid public key"),
+            BlsError::InvalidSignature => write!(f, "invalid signature"),
+            BlsError::AggregateEmpty => write!(f, "no points to aggregate"),
+        }
+    }
+}
+
+impl error::Error for BlsError {}
+
+fn secret_key(bytes: &[u8]) -> Result<SecretKey, BlsError> {
+    SecretKey::from_bytes(bytes).map_err(|_| BlsError::InvalidSecretKey)
+}
+
+fn public_keys(keys: &[Vec<u8>]) -> Result<Vec<PublicKey>, BlsError> {
+    keys.iter()
+        .map(|bytes| PublicKey::from_bytes(bytes).map_err(|_| BlsError::InvalidPublicKey))
+        .collect()
+}
+
+fn signature(bytes: &[u8]) -> Result<AggregateSignature, BlsError> {
+    AggregateSignature::from_bytes(bytes).map_err(|_| BlsError::InvalidSignature)
+}
+
+pub fn key_generate(ikm: Vec<u8>, key_info: Vec<u8>) -> Result<Vec<u8>, BlsError> {
+    match SecretKey::key_generate(&ikm, &key_info) {
+        Ok(sk) => Ok(sk.as_bytes().to_vec()),
+        Err(AmclError::InvalidSecretKeySize) => Err(BlsError::InvalidIkm),
+        Err(_) => Err(BlsError::InvalidSecretKey),
+    }
+}
+
+pub fn secret_key_to_public_key(secret_key_bytes: Vec<u8>) -> Result<Vec<u8>, BlsError> {
+    let sk = secret_key(&secret_key_bytes)?;
+    Ok(PublicKey::from_secret_key(&sk).as_bytes().to_vec())
+}
+
+pub fn sign(secret_key_bytes: Vec<u8>, message: Vec<u8>) -> Result<Vec<u8>, BlsError> {
+    let sk = secret_key(&secret_key_bytes)?;
+    Ok(Signature::new(&message, &sk).as_bytes().to_vec())
+}
+
+pub fn verify(
+    public_key: Vec<u8>,
+    message: Vec<u8>,
+    signature_bytes: Vec<u8>,
+) -> Result<bool, BlsError> {
+    let pk = PublicKey::from_bytes(&public_key).map_err(|_| BlsError::InvalidPublicKey)?;
+    let sig = Signature::from_bytes(&signature_bytes).map_err(|_| BlsError::InvalidSignature)?;
+    Ok(sig.verify(&message, &pk))
+}
+
+pub fn fast_aggregate_verify(
+    public_key_bytes: Vec<Vec<u8>>,
+    message: Vec<u8>,
+    signature_bytes: Vec<u8>,
+) -> Result<bool, BlsError> {
+    let pks = public_keys(&public_key_bytes)?;
+    let sig = signature(&signature_bytes)?;
+    let pks: Vec<&PublicKey> = pks.iter().collect();
+    Ok(sig.fast_aggregate_verify(&message, &pks))
+}
+
+pub fn aggregate_verify(
+    public_key_bytes: Vec<Vec<u8>>,
+    messages: Vec<Vec<u8>>,
+    signature_bytes: Vec<u8>,
+) -> Result<bool, BlsError> {
+    let pks = public_keys(&public_key_bytes)?;
+    let sig = signature(&signature_bytes)?;
+    let pks: Vec<&PublicKey> = pks.iter().collect();
+    let msgs: Vec<&[u8]> = messages.iter().map(|msg| msg.as_slice()).collect();
+    Ok(sig.aggregate_verify(&msgs, &pks))
+}
+
+pub fn aggregate_signatures(signatures: Vec<Vec<u8>>) -> Result<Vec<u8>, BlsError> {
+    if signatures.is_empty() {
+        return Err(BlsError::AggregateEmpty);
+    }
+    let mut aggregate_signature = AggregateSignature::new();
+    for bytes in &signatures {
+        aggregate_signature.add_aggregate(&signature(bytes)?);
+    }
+    Ok(aggregate_signature.as_bytes().to_vec())
+}
+
+pub fn aggregate_public_keys(public_key_bytes: Vec<Vec<u8>>) -> Result<Vec<u8>, BlsError> {
+    let pks = public_keys(&public_key_bytes)?;
+    let aggregate_public_key =
+        AggregatePublicKey::into_aggregate(&pks).map_err(|_| BlsError::AggregateEmpty)?;
+    Ok(aggregate_public_key.as_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keypair(seed: u8) -> (Vec<u8>, Vec<u8>) {
+        let sk = key_generate(vec![seed; 32], vec![]).unwrap();
+        let pk = secret_key_to_public_key(sk.clone()).unwrap();
+        (sk, pk)
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let (sk, pk) = keypair(1);
+        let sig = sign(sk, b"cats".to_vec()).unwrap();
+        assert!(verify(pk.clone(), b"cats".to_vec(), sig.clone()).unwrap());
+        assert!(!verify(pk, b"dogs".to_vec(), sig).unwrap());
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let keypairs: Vec<_> = (0..3).map(keypair).collect();
+        let pks: Vec<Vec<u8>> = keypairs.iter().map(|(_, pk)| pk.clone()).collect();
+        let sigs: Vec<Vec<u8>> =
+            keypairs.iter().map(|(sk, _)| sign(sk.clone(), b"cats".to_vec()).unwrap()).collect();
+
+        let agg_sig = aggregate_signatures(sigs).unwrap();
+        let agg_pk = aggregate_public_keys(pks.clone()).unwrap();
+        assert!(verify(agg_pk, b"cats".to_vec(), agg_sig.clone()).unwrap());
+        assert!(fast_aggregate_verify(pks.clone(), b"cats".to_vec(), agg_sig.clone()).unwrap());
+        assert!(aggregate_verify(pks, vec![b"cats".to_vec(); 3], agg_sig).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(key_generate(vec![1; 31], vec![]), Err(BlsError::InvalidIkm));
+        assert_eq!(secret_key_to_public_key(vec![0; 32]), Err(BlsError::InvalidSecretKey));
+        assert_eq!(
+            verify(vec![0; 48], b"cats".to_vec(), vec![0; 96]),
+            Err(BlsError::InvalidPublicKey)
+        );
+        assert_eq!(aggregate_signatures(vec![]), Err(BlsError::AggregateEmpty));
+        assert_eq!(aggregate_public_keys(vec![]), Err(BlsError::AggregateEmpty));
+        assert_eq!(aggregate_signatures(vec![vec![0; 95]]), Err(BlsError::InvalidSignature));
+    }
+}